@@ -0,0 +1,42 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::DataStruct;
+
+/// A wrapper implementing `std::default::Default` with `DataStruct::data_default`,
+/// for structures that don't enable `std_default` but are passed to APIs requiring `Default`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Defaulted<T>(pub T);
+
+impl<T> Defaulted<T> {
+    /// Unwraps the structure.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: DataStruct> Default for Defaulted<T> {
+    fn default() -> Self {
+        Self(T::data_default())
+    }
+}
+
+impl<T> Deref for Defaulted<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Defaulted<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> From<T> for Defaulted<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
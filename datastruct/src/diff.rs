@@ -0,0 +1,64 @@
+use std::fmt::{Debug, Display, Formatter, Result};
+
+// The name of a changed field, with its old and new values unless it's hidden from `Debug`.
+type Change<'a> = (&'static str, Option<(&'a dyn Debug, &'a dyn Debug)>);
+
+/// The changed fields between two values, returned by the `diff_display` method,
+/// generated by the macro's `diff_display` option.
+///
+/// Displays like `port: 8080 → 9090, verbose: false → true`, or one field per line with `{:#}`.
+/// Fields configured with `debug = false` are displayed as `secret: changed`.
+pub struct DiffDisplay<'a> {
+    changes: Vec<Change<'a>>,
+}
+
+impl<'a> DiffDisplay<'a> {
+    #[doc(hidden)]
+    pub fn new(changes: Vec<Change<'a>>) -> Self {
+        Self { changes }
+    }
+
+    /// Whether no field is changed.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Names of the changed fields, in the order of their declarations.
+    pub fn fields(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.changes.iter().map(|(name, _)| *name)
+    }
+}
+
+impl Display for DiffDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let separator = if f.alternate() { "\n" } else { ", " };
+        for (i, (name, change)) in self.changes.iter().enumerate() {
+            if i > 0 {
+                f.write_str(separator)?;
+            }
+            match change {
+                Some((old, new)) => write!(f, "{}: {:?} → {:?}", name, old, new)?,
+                None => write!(f, "{}: changed", name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Debug for DiffDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.debug_list().entries(self.fields()).finish()
+    }
+}
+
+/// Asserts that two structures are equal, like `assert_eq!`,
+/// but panics with the differing fields instead of the `Debug` of the whole structures.
+///
+/// The structures must derive the macro's `assert_eq_verbose` option.
+#[cfg(feature = "testing")]
+#[macro_export]
+macro_rules! assert_fields_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $left.assert_eq_verbose(&$right)
+    };
+}
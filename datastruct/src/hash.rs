@@ -0,0 +1,40 @@
+/// A 64-bit FNV-1a hasher, used by the macro's `cache_key` option.
+///
+/// Unlike `std::collections::hash_map::DefaultHasher`, the algorithm is fixed and unkeyed,
+/// so the same value always produces the same hash across runs and compiler versions
+/// (as long as its `Hash` implementation is unchanged and the platform is the same).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StableHasher {
+    state: u64,
+}
+
+impl StableHasher {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    /// Creates a new hasher.
+    pub const fn new() -> Self {
+        Self {
+            state: Self::OFFSET_BASIS,
+        }
+    }
+}
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::hash::Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.state
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= *byte as u64;
+            self.state = self.state.wrapping_mul(Self::PRIME);
+        }
+    }
+}
@@ -4,9 +4,14 @@
 //!
 //! Currently Available:
 //! - Default: Standard `Default`, lib-specific `DataStruct::data_default` and constant default `ConstDataStruct::DEFAULT`.
+//! - Builder: Companion `<Struct>Builder` type.
 //! - Debug: Manual `Debug` filter.
 //! - Comparison: Standard `Eq`, `PartialEq`, `Ord`, `PartialOrd`.
 //! - Operations: Standard `Add(Assign)`, `Sub(Assign)`, `Mul(Assign)`, `Div(Assign)`.
+//! - Interpolation: Lib-specific `Lerp::lerp`.
+//! - Patch: Companion `<Struct>Patch` type applied via `+=`.
+//! - Conversion: Standard `From` and `TryFrom` from tuples and other structures.
+//! - Reflection: Field metadata constants.
 //!
 //! Unlike standard derive macros, the `DataStruct` macro accepts user-defined behaviors without
 //! writing implementation code.
@@ -37,9 +42,9 @@
 //! # use datastruct_derive::DataStruct;
 //!
 //! # #[derive(DataStruct)]
-//! # #[dstruct()]
+//! # #[dstruct(debug, cmp(peq))]
 //! # struct Data {
-//! #[dfield(no_debug, cmp(eq = false))]
+//! #[dfield(debug = false, cmp(eq = false))]
 //! #[dfield(cmp(eq = true))]
 //! #     field: u32,
 //! # }
@@ -51,13 +56,243 @@
 //! # use datastruct_derive::DataStruct;
 //!
 //! # #[derive(DataStruct)]
-//! # #[dstruct()]
+//! # #[dstruct(debug, cmp(peq))]
 //! # struct Data {
-//! #[dfield(no_debug, cmp(eq = true))]
+//! #[dfield(debug = false, cmp(eq = true))]
 //! #     field: u32,
 //! # }
 //! ```
 //!
+//! Lists like `cmp(...)` and `ops(...)` are merged key by key, so only the keys declared again are overridden.
+//!
+//! The direction can be flipped with `#[dstruct(precedence = "first")]`, so the first declaration of each option
+//! is kept instead, for both `dstruct` and `dfield` attributes. Options accumulating across declarations,
+//! like `cmp(...)`, `ops(...)`, `computed(...)` or `delegate(...)`, are not affected.
+//! Declaring an option more than once with different values results in a deprecation warning at the repeated declaration.
+//!
+//! Unknown options are rejected, e.g. `#[dfield(defautl = "1")]` results in a compile error
+//! pointing at `defautl` and suggesting `default`, instead of being silently ignored.
+//! Renamed options are still accepted with a deprecation warning naming the replacement,
+//! e.g. `#[dfield(no_debug)]` is read as `#[dfield(debug = false)]`.
+//!
+//! Only structs with named fields are supported, enums, unions, tuple and unit structs are rejected.
+//!
+//! Conflicting or ineffective combinations are reported as well, each at the offending option,
+//! e.g. `partial` together with `default`, or `#[dfield(lerp)]` without `#[dstruct(lerp)]`.
+//! Errors of all options and fields are collected, so they can be fixed in a single pass.
+//!
+//! Custom expressions can only reach the values provided by the macro via `$` placeholders, like `$self`, `$rhs` or `$other`.
+//! The parameters and locals of the generated code are hygienic, so a plain `rhs` or `other` in an expression
+//! refers to the item of that name in the user's scope, never to a generated binding.
+//!
+//! ### Conditional Fields
+//!
+//! `#[cfg(...)]` and `#[cfg_attr(...)]` attributes are evaluated by the compiler before the macro runs,
+//! so fields disabled by `#[cfg]` are never referred to by the generated code,
+//! and every generated method, implementation and default value only covers the enabled fields.
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(default, debug)]
+//! struct Features {
+//!     #[dfield(default = "1")]
+//!     base: i32,
+//!     #[cfg(feature = "extras")]
+//!     #[dfield(default = "2")]
+//!     extras: i32,
+//!     // feature-dependent configuration
+//!     #[dfield(default = "3")]
+//!     #[cfg_attr(feature = "extras", dfield(set))]
+//!     level: i32,
+//! }
+//! ```
+//!
+//! ### Field Groups
+//!
+//! To share the same configuration among several fields, declare a named group on the struct.
+//! Every option after `fields(...)` is applied to the listed fields as if written in their `#[dfield(...)]`.
+//! The fields' own `#[dfield(...)]` attributes are applied after the groups, so they take precedence.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(group(name, fields(a, b, ...), options...))]`
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(debug, set)]
+//! #[dstruct(group(secrets, fields(password, token), debug = false, set = "no"))]
+//! struct Account {
+//!     username: String,
+//!     password: String,
+//!     token: String,
+//! }
+//! ```
+//!
+//! ### Presets
+//!
+//! A preset enables a common bundle of struct options at once.
+//! Options declared after the preset can still override it.
+//!
+//! - `value_object`: `cmp(peq, eq, hash)`, `debug` and `get`.
+//! - `dto`: `default`, `set`, `get` and `debug`.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(preset = "name")]`
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(preset = "value_object")]
+//! struct Money {
+//!     amount: i64,
+//!     currency: &'static str,
+//! }
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(preset = "dto", debug = false)]
+//! struct UserDto {
+//!     #[dfield(default = "String::from(\"guest\")")]
+//!     name: String,
+//! }
+//! ```
+//!
+//! ### Crate Path
+//!
+//! The generated code refers to the runtime crate as `::datastruct`.
+//! If the crate is renamed or re-exported by another crate, specify its path instead.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(crate = "path")]`
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use my_facade::datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(crate = "::my_facade::datastruct", default)]
+//! struct Data {
+//!     #[dfield(default = "1")]
+//!     value: i32,
+//! }
+//! ```
+//!
+//! ### Optional Subsystems
+//!
+//! The `ops`, `cmp`, `builder` and `reflect` options are generated by cargo features of the same names,
+//! enabled by default. Projects only using defaults and accessors can disable them to build the macro faster;
+//! the options of a disabled subsystem are rejected with the feature they require.
+//! `cache_key`, `diff_display` and `preset = "value_object"` require `cmp`, and `visit` and `field_enum` require `reflect`.
+//!
+//! ```toml
+//! [dependencies]
+//! datastruct = { version = "0.1", default-features = false }
+//! ```
+//!
+//! ### Hidden Documentation
+//!
+//! Mark the generated inherent methods (accessors, constructors, etc.) and companion types
+//! (builders, patches, etc.) with `#[doc(hidden)]`, so that internal plumbing doesn't clutter the public documentation.
+//! Trait implementations are kept visible.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(doc_hidden)]` | `#[dstruct(doc_hidden = boolean)]`
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(doc_hidden, set, get, builder)]
+//! pub struct Internal {
+//!     value: i32,
+//! }
+//! ```
+//!
+//! ### Construction
+//!
+//! By default, the generated code (operators, `lerp`, `zip_with`, builders, conversions, `random`, `arbitrary`, etc.)
+//! constructs the structure with a struct literal, listing every field.
+//! With `construct = "default"`, it starts from `data_default()` and assigns the fields one by one instead,
+//! so that the shape of the generated code doesn't depend on the complete set of fields.
+//! This requires `default`, and every field to have a default value.
+//!
+//! The `ZERO` and `ONE` constants of `ops(identity)` and `zeroed` start from `ConstDataStruct::DEFAULT` instead, which requires `const`.
+//! The `const` constructor and the default implementations themselves always use struct literals.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(construct = "literal")]`: Struct literals. (Default)
+//! - `#[dstruct(construct = "default")]`: Assign to `data_default()`.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(construct = "default", default, ops(add))]
+//! struct Vector2 {
+//!     #[dfield(default = "0.0")]
+//!     x: f64,
+//!     #[dfield(default = "0.0")]
+//!     y: f64,
+//! }
+//!
+//! // generated code
+//! impl ::std::ops::Add for Vector2 {
+//!     type Output = Self;
+//!
+//!     fn add(self, rhs: Self) -> Self {
+//!         {
+//!             let mut __ds_value = <Vector2 as ::datastruct::DataStruct>::data_default();
+//!             __ds_value.x = self.x + rhs.x;
+//!             __ds_value.y = self.y + rhs.y;
+//!             __ds_value
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! ### Extra Bounds `where`
+//!
+//! Generic structures may need bounds only for some of the generated implementations.
+//! Add the bounds to the `where` clause of each kind of implementation,
+//! instead of requiring them on the structure itself.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(where(kind = "T: Bound, U: Bound", ...))]`, where `kind` is one of:
+//!   - `default`: `DataStruct`, `ConstDataStruct`, `Default` and the other methods evaluating the default values.
+//!   - `debug`: `Debug`, `debug_fields`, `record_fields` and `DataStructFields`.
+//!   - `cmp`: `PartialEq`, `Eq`, `PartialOrd`, `Ord`, `Hash`, `cache_key`, `diff_display` and `assert_eq_verbose`.
+//!   - `ops`: The operators, in addition to the inferred or specified `ops(bound = "...")` bounds.
+//!   - `lerp`: `Lerp`.
+//!   - `merge`: `merge`.
+//!   - `clone`: `Clone` and `clone_with_default`.
+//!   - `convert`: `From` and `TryFrom`, including the tuple conversions.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(default, debug)]
+//! #[dstruct(where(default = "T: Default", debug = "T: std::fmt::Debug"))]
+//! struct Labeled<T> {
+//!     #[dfield(default = "T::default()")]
+//!     value: T,
+//! }
+//! ```
+//!
 //! ## Api Document
 //!
 //! ### Default
@@ -121,6 +356,9 @@
 //!
 //! **Syntax:**
 //! - `#[dstruct(default)]`
+//! - `#[dstruct(default(bound = "T: Bound, ..."))]`: Add the bounds to the default implementations, like `where(default = "...")`,
+//!   so that the default values can refer to the generic parameters, e.g. `default = "T::DEFAULT"` with `T: ConstDataStruct`.
+//! - `#[dstruct(default(env_overrides))]`: See [environment overrides](#environment-overrides-env_overrides).
 //!
 //! **Restriction:**
 //! - All fields must be provided with default value.
@@ -128,7 +366,7 @@
 //! **Field Configuration:**
 //! - `#[dfield(default = xxx)]`
 //!
-//!   The literal expression should be wrapped inside a string, like this:
+//!   The expression can be wrapped inside a string, or written directly:
 //!
 //!   ```rust
 //!   # use datastruct_derive::DataStruct;
@@ -138,7 +376,7 @@
 //!   # struct Data {
 //!   #[dfield(default = "field + another")]
 //!   #     dfield: usize,
-//!   #[dfield(default = "42_usize")]
+//!   #[dfield(default = 42_usize)]
 //!   #     field: usize,
 //!   #     #[dfield(default = "0_usize")]
 //!   #     another: usize,
@@ -148,6 +386,7 @@
 //!
 //!   If no default value is provided, the field will be considered uninitialized
 //!   and `default`-related code cannot be generated.
+//!   If default values are given but no option makes use of them, a deprecation warning points at them.
 //! - `#[dfield(seq = xxx)]` | `#[dfield(sequence = xxx)]` where `xxx` is `isize`
 //!
 //!   Change the sequence of the fields. By default, the sequence to initialize the fields
@@ -167,6 +406,40 @@
 //!   }
 //!   ```
 //!
+//! #### Environment Overrides `env_overrides`
+//!
+//! Generate `data_default_from_env() -> Self`, starting from `data_default` and overriding the fields
+//! whose environment variables are set and parse with `FromStr`, the others keep their default values.
+//! The variable of a field is `<PREFIX>_<FIELD>`, like `SERVER_CONFIG_MAX_THREADS` for the `max_threads` field of `ServerConfig`,
+//! where the prefix is the structure name in `SCREAMING_SNAKE_CASE` by default.
+//!
+//! Fields whose types use the type parameters are required to be `FromStr` only by `data_default_from_env`.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(default(env_overrides))]`: Also enables `default`.
+//! - `#[dstruct(default(env_overrides, env_prefix = "APP"))]`: Read `APP_<FIELD>` instead, or only `<FIELD>` if the prefix is empty.
+//!
+//! **Field Configuration:**
+//! - `#[dfield(env = "NAME")]`: Read the variable `NAME` instead.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(default(env_overrides, env_prefix = "APP"))]
+//! struct Config {
+//!     #[dfield(default = "8080")]
+//!     port: u16,
+//!     #[dfield(default = "false", env = "VERBOSE")]
+//!     verbose: bool,
+//! }
+//!
+//! // reads `APP_PORT` and `VERBOSE`
+//! let config = Config::data_default_from_env();
+//! ```
+//!
 //! #### `const`
 //!
 //! Ask the macro to generate an implementation of `datastruct::ConstDataStruct`,
@@ -174,17 +447,40 @@
 //!
 //! **Syntax:**
 //! - `#[dstruct(const)]`
+//! - `#[dstruct(const(check))]`: Also generate a `#[cfg(test)]` test asserting that `DEFAULT`
+//!   equals the default values evaluated at runtime. Requires `default` and `PartialEq`, and a non-generic structure.
 //!
 //! **Restriction:**
 //! - All fields must be provided with **const** default value.
+//!   A non-const value is reported at its `default = "..."` option.
 //!
 //! **Field Configuration:**
 //! - Inherits from `default`.
 //!
+//! The value can be obtained without naming the associated constant,
+//! with `datastruct::const_default::<T>()` in const functions,
+//! or with `datastruct::const_default!(T)` anywhere a constant expression is expected.
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(const)]
+//! struct Limits {
+//!     #[dfield(default = "16")]
+//!     connections: u32,
+//! }
+//!
+//! static LIMITS: Limits = datastruct::const_default!(Limits);
+//! const CONNECTIONS: u32 = datastruct::const_default::<Limits>().connections;
+//! ```
+//!
 //! #### `std_default`
 //!
 //! The same as `default`, but implement `std::default::Default` instead.
 //!
+//! If `default` is also enabled, `Default::default()` simply calls `DataStruct::data_default()`.
+//!
 //! **Syntax:**
 //! - `#[dstruct(std_default)]`
 //!
@@ -197,6 +493,24 @@
 //! **Warning:**
 //! - This may pollute the namespace, and IDE may not be able to identify the implementation.
 //!
+//! To keep `Default` out of the structure, wrap it in `datastruct::Defaulted<T>` instead,
+//! which implements `Default` with `DataStruct::data_default()`, as well as `Deref`, `DerefMut` and `From<T>`.
+//!
+//! ```rust,ignore
+//! use datastruct::{DataStruct, Defaulted};
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(default)]
+//! struct Settings {
+//!     #[dfield(default = "8")]
+//!     threads: usize,
+//! }
+//!
+//! let settings: Defaulted<Settings> = Default::default();
+//! assert_eq!(8, settings.threads);
+//! let settings: Settings = settings.into_inner();
+//! ```
+//!
 //! #### `partial`
 //!
 //! Partially default implementation.
@@ -231,114 +545,91 @@
 //! **Field Configuration:**
 //! - Inherits from `default`.
 //!
-//! ### Setter and Getter
-//!
-//! #### `set`
-//!
-//! Generate default setter for fields.
-//!
-//! **Setter Type:**
-//! - `full` | `all`: Both `set` and `with`. (Default.)
-//! - `set`: `set_field_name(&mut self, value)`. Set the structure's value.
-//! - `with`: `with_field_name(self, value) -> Self`. Internally set the structure's value and return itself.
-//! - `no`: Ignore the field.
+//! #### `serde_defaults`
 //!
-//! **Syntax:**
-//! - `#[dstruct(set)]`: Default setter configuration.
-//! - `#[dstruct(set = "setter_type")]`: Set default setter configuration to `setter_type`.
+//! Generate a hidden `__default_xxx() -> Type` function for every field with a default value,
+//! so that the default expressions can be referred to by `serde` without duplicating them.
+//! A default expression may refer to the previous fields, whose default values are computed as well.
 //!
-//! **Field Configuration:**
-//! - `#[dfield(set)]`: Inherit the setter configuration from the structure. Typically, you don't need to specify this.
-//! - `#[dfield(set = "setter_type")]`: Override the default setter configuration.
+//! **Struct Configuration:**
+//! - `#[dstruct(serde_defaults)]` | `#[dstruct(serde_defaults = boolean)]`
 //!
-//! **Example:**
+//! **Examples:**
 //!
 //! ```rust,ignore
 //! use datastruct::DataStruct;
-//!
-//! #[derive(DataStruct)]
-//! #[dstruct(set)] // equivalent to `set = "full"`
-//! struct Data {
-//!     auto_set: usize,
-//!     #[dfield(set = "no")]
-//!     no_set: usize,
-//!     #[dfield(set = "set")]
-//!     only_set: usize,
+//! use serde::Deserialize;
+//!
+//! #[derive(DataStruct, Deserialize)]
+//! #[dstruct(serde_defaults)]
+//! struct Listener {
+//!     #[dfield(default = "8080")]
+//!     #[serde(default = "Listener::__default_port")]
+//!     port: u16,
 //! }
+//! ```
 //!
-//! // generated code
-//! impl Data {
-//!     pub fn set_auto_set(&mut self, auto_set: usize) {
-//!         self.auto_set = auto_set;
-//!     }
-//!     pub fn set_only_set(&mut self, only_set: usize) {
-//!         self.only_set = only_set;
-//!     }
+//! #### `serde_is_default`
 //!
-//!     pub fn with_auto_set(mut self, auto_set: usize) -> Self {
-//!         self.auto_set = auto_set;
-//!         self
-//!     }
-//! }
-//! ```
+//! Generate `xxx_is_default(&Type) -> bool` for every field with a default value,
+//! comparing the value against the default value of the field,
+//! so that serialized data can skip the default values without duplicating the default expressions.
 //!
+//! **Struct Configuration:**
+//! - `#[dstruct(serde_is_default)]` | `#[dstruct(serde_is_default = boolean)]`
 //!
-//! #### `get`
+//! **Examples:**
 //!
-//! Generate default getter for fields.
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//! use serde::Serialize;
+//!
+//! #[derive(DataStruct, Serialize)]
+//! #[dstruct(serde_is_default)]
+//! struct Listener {
+//!     #[dfield(default = "8080")]
+//!     #[serde(skip_serializing_if = "Listener::port_is_default")]
+//!     port: u16,
+//! }
+//! ```
 //!
-//! **Setter Type:**
-//! - `full` | `all`: Both `move` and `get`.
-//! - `get`: `field_name(&self) -> &value`. Get the structure's field's reference. (Default.)
-//! - `move`: `get_field_name(self) -> move`. Move the field out of the structure.
-//! - `no`: Ignore the field.
+//! #### `zeroed`
 //!
-//! **Syntax:**
-//! - `#[dstruct(get)]`: Default getter configuration.
-//! - `#[dstruct(get = "getter_type")]`: Set default getter configuration to `getter_type`.
+//! Generate `const fn zeroed() -> Self`, setting every numeric field to `0`, `bool` to `false`, `char` to `'\0'` and `Option` to `None`.
+//! Arrays and tuples of these types are zeroed element-wise.
+//! The configured default values are ignored, which makes `zeroed` a cheap baseline distinct from `data_default`.
 //!
-//! **Field Configuration:**
-//! - `#[dfield(get)]`: Inherit the getter configuration from the structure. Typically, you don't need to specify this.
-//! - `#[dfield(get = "getter_type")]`: Override the default getter configuration.
+//! Fields of any other type are rejected at compile time.
 //!
-//! **Example:**
+//! **Struct Configuration:**
+//! - `#[dstruct(zeroed)]` | `#[dstruct(zeroed = boolean)]`
+//!
+//! **Examples:**
 //!
 //! ```rust,ignore
 //! use datastruct::DataStruct;
 //!
 //! #[derive(DataStruct)]
-//! #[dstruct(get)] // equivalent to `get = "get"`
-//! struct Data {
-//!     auto_get: usize,
-//!     #[dfield(get = "no")]
-//!     no_get: usize,
-//!     #[dfield(get = "full")]
-//!     can_move: usize,
+//! #[dstruct(zeroed)]
+//! struct Counter {
+//!     hits: u64,
+//!     ratio: f32,
+//!     enabled: bool,
+//!     last: Option<u64>,
 //! }
 //!
-//! // generated code
-//! impl Data {
-//!     pub fn auto_get(&self) -> &usize {
-//!         &self.auto_get
-//!     }
-//!     pub fn can_move(&self) -> &usize {
-//!         &self.can_move
-//!     }
-//!
-//!     pub fn get_can_move(self) -> usize {
-//!         self.can_move
-//!     }
-//! }
+//! const EMPTY: Counter = Counter::zeroed();
 //! ```
 //!
-//! #### `map`
+//! #### `defaults_doc`
 //!
-//! Map a field's value and modify the structure. This does not have structure-level configuration.
+//! Generate `const DEFAULTS_DOC: &[(&str, &str)]`, pairing the name of every field with a default value
+//! with its documentation followed by `[default: <expr>]`, the default expression as written in the source code,
+//! so that command line helps or admin pages can display the effective defaults without repeating them.
+//! Multi-line documentation is joined into one line, and fields without default values are left out.
 //!
-//! **Field Configuration:**
-//! - `#[dfield(map)]` | `#[dfield(map = ture)`: Enable mapping.
-//! - `#[dfield(map = false)]`: Disable mapping.
-//!   Typically, you don't need to explicitly disable mapping since it's the default behavior.
+//! **Struct Configuration:**
+//! - `#[dstruct(defaults_doc)]` | `#[dstruct(defaults_doc = boolean)]`
 //!
 //! **Examples:**
 //!
@@ -346,28 +637,31 @@
 //! use datastruct::DataStruct;
 //!
 //! #[derive(DataStruct)]
-//! struct MapItem {
-//!     #[dfield(map)] // equivalent to `#[dfield(map = true)]
-//!     item: usize,
+//! #[dstruct(defaults_doc)]
+//! struct ServeOptions {
+//!     /// Port to listen on.
+//!     #[dfield(default = "8080")]
+//!     port: u16,
 //! }
 //!
-//! // generated code
-//! impl MapItem {
-//!     pub fn map_item(mut self, f: impl FnOnce(usize) -> usize) -> Self {
-//!         self.item = f(self.item);
-//!         self
-//!     }
-//! }
+//! assert_eq!(ServeOptions::DEFAULTS_DOC, [("port", "Port to listen on. [default: 8080]")]);
 //! ```
 //!
-//! #### `do_with`
+//! #### `fixture`
 //!
-//! Modify a field's value. This does not have structure-level configuration.
+//! Generate `fixture() -> Self` under `#[cfg(test)]`, a deterministic value for the unit tests of the crate,
+//! distinct from the default value, instead of building test values by hand.
+//! Each field is initialized with its `fixture` expression, or its default value without one,
+//! following the same sequence as the `default` implementation, so the expressions can refer to the previous fields.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(fixture)]` | `#[dstruct(fixture = boolean)]`
 //!
 //! **Field Configuration:**
-//! - `#[dfield(do_with)]` | `#[dfield(do_with = ture)`: Enable `do_with`.
-//! - `#[dfield(do_with = false)]`: Disable `do_with`.
-//!   Typically, you don't need to explicitly disable mapping since it's the default behavior.
+//! - `#[dfield(fixture = "expr")]`: The value of the field in `fixture`.
+//!
+//! **Restriction:**
+//! - All fields must be provided with a `fixture` expression or a default value.
 //!
 //! **Examples:**
 //!
@@ -375,42 +669,1808 @@
 //! use datastruct::DataStruct;
 //!
 //! #[derive(DataStruct)]
-//! struct MapItem {
-//!     #[dfield(do_with)] // equivalent to `#[dfield(do_with = true)]
-//!     item: usize,
+//! #[dstruct(default, fixture)]
+//! struct Account {
+//!     #[dfield(default = "0", fixture = "42")]
+//!     id: u64,
+//!     #[dfield(default = "String::new()", fixture = "format!(\"user{}@example.com\", id)")]
+//!     email: String,
+//!     #[dfield(default = "false")]
+//!     admin: bool,
 //! }
 //!
-//! // generated code
-//! impl MapItem {
-//!     pub fn do_with_item(&mut self, f: impl FnOnce(&mut usize)) {
-//!         f(&mut self.item);
-//!     }
+//! #[test]
+//! fn test_login() {
+//!     let account = Account::fixture();
+//!     assert_eq!(account.email, "user42@example.com");
 //! }
 //! ```
 //!
-//! ### Comparison `cmp`
+//! #### `gen_tests`
+//!
+//! Generate `#[cfg(test)] mod __datastruct_tests_<struct>`, smoke tests of the code generated for the structure,
+//! named after the structure in `snake_case` so that several structures can share a module.
+//! Only the tests applying to the enabled options are generated:
+//! - `default_round_trip`: `Default::default()` (`std_default`), `DEFAULT` (`const`), a clone (`clone`)
+//!   and `from_map(&to_map())` (`string_map`) equal `data_default()`.
+//! - `setter_getter_symmetry`: for every field with both a setter and a getter, the getter returns the value given to the setter.
+//!   The values are taken from `fixture()` if `fixture` is enabled, from `data_default()` otherwise.
+//! - `clone_with_default_idempotence`: `clone_with_default` of a value reset by `clone_with_default` is unchanged (`clone_with_default`).
+//! - `ops_identity`: `ZERO` is zero, and adding or subtracting `ZERO`, multiplying or dividing by `ONE` gives back the value (`ops(identity)`).
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(gen_tests)]` | `#[dstruct(gen_tests = boolean)]`
+//!
+//! **Restriction:**
+//! - Requires `default`, with a default value for every field.
+//! - The structure must implement `PartialEq`, and must not be generic.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct, PartialEq)]
+//! #[dstruct(default, std_default, set, get, gen_tests)]
+//! struct Viewport {
+//!     #[dfield(default = "800")]
+//!     width: u32,
+//!     #[dfield(default = "600")]
+//!     height: u32,
+//! }
+//! ```
+//!
+//! ### Builder `builder`
+//!
+//! Generate a companion builder type named `<Struct>Builder`, with the same visibility and generics as the structure.
+//!
+//! The builder has a setter for every field, and `build()` initializes the unset fields with their default values,
+//! following the same sequence as the `default` implementation.
+//! `build()` panics if a field without default value is not set.
+//!
+//! **Syntax:**
+//! - `#[dstruct(builder)]` | `#[dstruct(builder = boolean)]`
+//! - `#[dstruct(builder(typestate))]`: Track the required fields at compile time, see below.
+//! - `#[dstruct(builder(try))]`: `build()` returns a `Result` instead of panicking, see below.
+//! - `#[dstruct(builder(mirror_setters))]`: Name the setters like the setters of the structure, see below.
+//!
+//! **Field Configuration:**
+//! - Inherits from `default`.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(builder)]
+//! struct Connection {
+//!     host: String,
+//!     #[dfield(default = "8080")]
+//!     port: u16,
+//! }
+//!
+//! // generated code
+//! struct ConnectionBuilder {
+//!     host: Option<String>,
+//!     port: Option<u16>,
+//! }
+//!
+//! impl ConnectionBuilder {
+//!     pub fn host(mut self, host: String) -> Self {
+//!         self.host = Some(host);
+//!         self
+//!     }
+//!     pub fn port(mut self, port: u16) -> Self {
+//!         self.port = Some(port);
+//!         self
+//!     }
+//!
+//!     pub fn build(self) -> Connection {
+//!         let host: String = match self.host {
+//!             Some(v) => v,
+//!             None => panic!("field `host` of `Connection` is not set"),
+//!         };
+//!         let port: u16 = match self.port {
+//!             Some(v) => v,
+//!             None => 8080,
+//!         };
+//!         Connection { host, port }
+//!     }
+//! }
+//!
+//! impl Connection {
+//!     pub fn builder() -> ConnectionBuilder {
+//!         Default::default()
+//!     }
+//! }
+//! ```
+//!
+//! #### Seeded Builder
+//!
+//! When `default` is also enabled, the builder gets `from_default()`, setting every field to its value in `data_default()`,
+//! and `builder()` starts from it, so the builder only needs to set the deviations from the defaults.
+//! Unlike the unset fields of `build()`, the defaults referring to the previous fields don't follow the overridden fields.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(default, builder)]
+//! struct Pool {
+//!     #[dfield(default = "4")]
+//!     size: usize,
+//!     #[dfield(default = "size * 2")]
+//!     max_size: usize,
+//! }
+//!
+//! let pool = Pool::builder().size(8).build();
+//! assert_eq!(pool.max_size, 8);
+//! assert_eq!(PoolBuilder::from_default().build().max_size, 8);
+//! ```
+//!
+//! #### Mirrored Setters
+//!
+//! With `builder(mirror_setters)`, the setters of the builder follow the `set` configuration of the fields,
+//! so the builder and the fluent API of the structure share their names:
+//! - `set = "with"` or `set = "full"`: `with_<field>`, with the attributes forwarded by `attrs(with = "...")`.
+//! - `set = "set"`: `set_<field>`, with the attributes forwarded by `attrs(set = "...")`, still taking and returning the builder.
+//! - `set = "no"`: No setter, unless the field has no default value, which is then set by `with_<field>`.
+//!
+//! It can be combined with `typestate` and `try`.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(builder(mirror_setters), set = "with")]
+//! struct Endpoint {
+//!     url: String,
+//!     #[dfield(default = "30")]
+//!     timeout: u32,
+//! }
+//!
+//! let endpoint = Endpoint::builder().with_url(url).build();
+//! let endpoint = endpoint.with_timeout(10);
+//! ```
+//!
+//! #### Typestate Builder
+//!
+//! With `#[dstruct(builder(typestate))]`, every field without default value is tracked by a type parameter of the builder,
+//! which is `()` before the field is set and the field type after. `build()` is only available once all of them are set,
+//! so a missing field becomes a compile error instead of a panic.
+//!
+//! ```rust,ignore
+//! #[derive(DataStruct)]
+//! #[dstruct(builder(typestate))]
+//! struct Connection {
+//!     host: String,
+//!     #[dfield(default = "8080")]
+//!     port: u16,
+//! }
+//!
+//! // generated code (simplified)
+//! struct ConnectionBuilder<__DsHost> {
+//!     host: __DsHost,
+//!     port: Option<u16>,
+//! }
+//!
+//! impl<__DsHost> ConnectionBuilder<__DsHost> {
+//!     pub fn host(self, host: String) -> ConnectionBuilder<String> { /* ... */ }
+//!     pub fn port(mut self, port: u16) -> Self { /* ... */ }
+//! }
+//!
+//! impl ConnectionBuilder<String> {
+//!     pub fn build(self) -> Connection { /* ... */ }
+//! }
+//!
+//! // `Connection::builder().port(80).build()` does not compile.
+//! ```
+//!
+//! #### Fallible Builder
+//!
+//! With `#[dstruct(builder(try))]`, `build()` returns `Result<Struct, <Struct>BuilderError>`.
+//! The generated error enum has a `Missing<Field>` variant for every field without default value,
+//! and implements `Debug`, `Display` and `std::error::Error`.
+//! This cannot be used together with `typestate`.
+//!
+//! ```rust,ignore
+//! #[derive(DataStruct)]
+//! #[dstruct(builder(try))]
+//! struct Account {
+//!     user_name: String,
+//!     #[dfield(default = "false")]
+//!     admin: bool,
+//! }
+//!
+//! // generated code (simplified)
+//! enum AccountBuilderError {
+//!     MissingUserName,
+//! }
+//!
+//! impl AccountBuilder {
+//!     pub fn build(self) -> Result<Account, AccountBuilderError> { /* ... */ }
+//! }
+//! ```
+//!
+//! ### Setter and Getter
+//!
+//! #### `set`
+//!
+//! Generate default setter for fields.
+//!
+//! **Setter Type:**
+//! - `full` | `all`: Both `set` and `with`. (Default.)
+//! - `set`: `set_field_name(&mut self, value)`. Set the structure's value.
+//! - `with`: `with_field_name(self, value) -> Self`. Internally set the structure's value and return itself.
+//! - `no`: Ignore the field.
+//!
+//! **Syntax:**
+//! - `#[dstruct(set)]`: Default setter configuration.
+//! - `#[dstruct(set = "setter_type")]`: Set default setter configuration to `setter_type`.
+//!
+//! **Field Configuration:**
+//! - `#[dfield(set)]`: Inherit the setter configuration from the structure. Typically, you don't need to specify this.
+//! - `#[dfield(set = "setter_type")]`: Override the default setter configuration.
+//!
+//! **Example:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(set)] // equivalent to `set = "full"`
+//! struct Data {
+//!     auto_set: usize,
+//!     #[dfield(set = "no")]
+//!     no_set: usize,
+//!     #[dfield(set = "set")]
+//!     only_set: usize,
+//! }
+//!
+//! // generated code
+//! impl Data {
+//!     pub fn set_auto_set(&mut self, auto_set: usize) {
+//!         self.auto_set = auto_set;
+//!     }
+//!     pub fn set_only_set(&mut self, only_set: usize) {
+//!         self.only_set = only_set;
+//!     }
+//!
+//!     pub fn with_auto_set(mut self, auto_set: usize) -> Self {
+//!         self.auto_set = auto_set;
+//!         self
+//!     }
+//! }
+//! ```
+//!
+//!
+//! #### `get`
+//!
+//! Generate default getter for fields.
+//!
+//! **Setter Type:**
+//! - `full` | `all`: Both `move` and `get`.
+//! - `get`: `field_name(&self) -> &value`. Get the structure's field's reference. (Default.)
+//! - `move`: `get_field_name(self) -> move`. Move the field out of the structure.
+//! - `no`: Ignore the field.
+//!
+//! **Syntax:**
+//! - `#[dstruct(get)]`: Default getter configuration.
+//! - `#[dstruct(get = "getter_type")]`: Set default getter configuration to `getter_type`.
+//!
+//! **Field Configuration:**
+//! - `#[dfield(get)]`: Inherit the getter configuration from the structure. Typically, you don't need to specify this.
+//! - `#[dfield(get = "getter_type")]`: Override the default getter configuration.
+//!
+//! **Example:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(get)] // equivalent to `get = "get"`
+//! struct Data {
+//!     auto_get: usize,
+//!     #[dfield(get = "no")]
+//!     no_get: usize,
+//!     #[dfield(get = "full")]
+//!     can_move: usize,
+//! }
+//!
+//! // generated code
+//! impl Data {
+//!     pub fn auto_get(&self) -> &usize {
+//!         &self.auto_get
+//!     }
+//!     pub fn can_move(&self) -> &usize {
+//!         &self.can_move
+//!     }
+//!
+//!     pub fn get_can_move(self) -> usize {
+//!         self.can_move
+//!     }
+//! }
+//! ```
+//!
+//! #### `map`
+//!
+//! Map a field's value and modify the structure. This does not have structure-level configuration.
+//!
+//! **Field Configuration:**
+//! - `#[dfield(map)]` | `#[dfield(map = ture)`: Enable mapping.
+//! - `#[dfield(map = false)]`: Disable mapping.
+//!   Typically, you don't need to explicitly disable mapping since it's the default behavior.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! struct MapItem {
+//!     #[dfield(map)] // equivalent to `#[dfield(map = true)]
+//!     item: usize,
+//! }
+//!
+//! // generated code
+//! impl MapItem {
+//!     pub fn map_item(mut self, f: impl FnOnce(usize) -> usize) -> Self {
+//!         self.item = f(self.item);
+//!         self
+//!     }
+//! }
+//! ```
+//!
+//! #### `do_with`
+//!
+//! Modify a field's value. This does not have structure-level configuration.
+//!
+//! **Field Configuration:**
+//! - `#[dfield(do_with)]` | `#[dfield(do_with = ture)`: Enable `do_with`.
+//! - `#[dfield(do_with = false)]`: Disable `do_with`.
+//!   Typically, you don't need to explicitly disable mapping since it's the default behavior.
+//! - `#[dfield(do_with = "lock")]`: Enable `do_with` through the lock of a `std::sync::Mutex<T>` or `std::sync::RwLock<T>` field.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! struct MapItem {
+//!     #[dfield(do_with)] // equivalent to `#[dfield(do_with = true)]
+//!     item: usize,
+//! }
+//!
+//! // generated code
+//! impl MapItem {
+//!     pub fn do_with_item(&mut self, f: impl FnOnce(&mut usize)) {
+//!         f(&mut self.item);
+//!     }
+//! }
+//! ```
+//!
+//! With `do_with = "lock"`, `do_with` takes `&self` instead,
+//! locking the field for writing while applying the function to `&mut T`,
+//! so that structures of concurrent state keep the same surface.
+//! `RwLock` fields also get `read_xxx(&self, f: impl FnOnce(&T) -> R) -> R`, which locks the field for reading.
+//! Both panic if the lock is poisoned, like `lock().unwrap()`.
+//! The field must be a `Mutex` or `RwLock` of `std::sync`, either imported or written with its `std::sync` path;
+//! other locks, like the ones of `tokio` or `parking_lot`, are not supported.
+//! Without `lock`, a lock field is an ordinary field, modified through `&mut self`.
+//!
+//! ```rust,ignore
+//! use std::sync::{Mutex, RwLock};
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! struct State {
+//!     #[dfield(do_with = "lock")]
+//!     hits: Mutex<u64>,
+//!     #[dfield(do_with = "lock")]
+//!     names: RwLock<Vec<String>>,
+//! }
+//!
+//! state.do_with_hits(|hits| *hits += 1);
+//! state.do_with_names(|names| names.push("new".to_string()));
+//! let count = state.read_names(|names| names.len());
+//! ```
+//!
+//! #### `delegate`
+//!
+//! Forward methods of a field to the structure. This does not have structure-level configuration.
+//!
+//! Since the macro cannot see the field type's methods, the full signatures must be provided.
+//! All methods must take `self`, `&self` or `&mut self`.
+//!
+//! **Field Configuration:**
+//! - `#[dfield(delegate(methods("signature", ...)))]`: Forward the methods to this field.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! struct Inventory {
+//!     #[dfield(delegate(methods("fn len(&self) -> usize", "fn push(&mut self, value: String)")))]
+//!     items: Vec<String>,
+//!     owner: String,
+//! }
+//!
+//! // generated code
+//! impl Inventory {
+//!     pub fn len(&self) -> usize {
+//!         self.items.len()
+//!     }
+//!     pub fn push(&mut self, value: String) {
+//!         self.items.push(value)
+//!     }
+//! }
+//! ```
+//!
+//! #### Forwarded Attributes `attrs`
+//!
+//! Attach arbitrary attributes, such as `cfg`, `deprecated`, `inline` or `allow`, to specific generated methods.
+//! Each value is parsed as one or more outer attributes, and repeated keys accumulate.
+//!
+//! **Field Configuration:**
+//! - `#[dfield(attrs(get = "#[attr]", move = "...", set = "...", with = "...", do_with = "...", map = "...", delegate = "..."))]`:
+//!   Attach the attributes to the corresponding generated methods. `delegate` applies to every delegated method.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! struct Session {
+//!     #[dfield(get, set, attrs(get = "#[inline]", set = "#[cfg(feature = \"mut\")]"))]
+//!     token: String,
+//! }
+//!
+//! // generated code
+//! impl Session {
+//!     #[inline]
+//!     pub fn token(&self) -> &String {
+//!         &self.token
+//!     }
+//!     #[cfg(feature = "mut")]
+//!     pub fn set_token(&mut self, value: String) {
+//!         self.token = value;
+//!     }
+//! }
+//! ```
+//!
+//! #### C Accessors `ffi`
+//!
+//! Generate `#[no_mangle] pub unsafe extern "C"` shims of the accessors, so that a C API can be derived
+//! from the same annotations as the Rust accessors:
+//! - `<prefix>_<field>(this: *const Struct) -> T` for every field with a getter.
+//! - `<prefix>_set_<field>(this: *mut Struct, value: T)` for every field with a setter.
+//!
+//! The prefix is the name of the structure in `snake_case` by default.
+//! The structure must be `#[repr(C)]` and not generic, and the field types must be `Copy` and FFI-safe.
+//! Types which are certainly not FFI-safe are rejected, like tuples, `char`, or `Option` of anything but a reference, an `extern` function or `NonNull`,
+//! while other types are left to the compiler, as they may be `#[repr(C)]`.
+//! The shims are hidden from the documentation with `doc_hidden`, like the other generated items.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(ffi)]` | `#[dstruct(ffi = boolean)]`
+//! - `#[dstruct(ffi(prefix = "name"))]`: Use another prefix.
+//!
+//! **Field Configuration:**
+//! - `#[dfield(ffi = false)]`: Don't generate shims for the field, which keeps its Rust accessors.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(ffi, get, set = "set")]
+//! #[repr(C)]
+//! pub struct Point {
+//!     x: f64,
+//!     y: f64,
+//! }
+//!
+//! // generated code, with the safety documentation
+//! #[unsafe(no_mangle)]
+//! pub unsafe extern "C" fn point_x(this: *const Point) -> f64 {
+//!     core::ptr::read(core::ptr::addr_of!((*this).x))
+//! }
+//! #[unsafe(no_mangle)]
+//! pub unsafe extern "C" fn point_set_x(this: *mut Point, value: f64) {
+//!     (*this).x = value;
+//! }
+//! // ...
+//! ```
+//!
+//! #### JavaScript Accessors `wasm`
+//!
+//! Expose the accessors to JavaScript with `wasm_bindgen`, as properties named after the fields:
+//! - `js_<field>(&self) -> T`, a `#[wasm_bindgen(getter)]` returning a clone of the field, for every field with a getter.
+//! - `js_set_<field>(&mut self, value: T)`, a `#[wasm_bindgen(setter)]`, for every field with a setter.
+//!
+//! The Rust accessors return references or consume the structure, which can't be exported,
+//! so the properties have their own methods, in a separate `#[wasm_bindgen]` block.
+//! This requires the `wasm` feature of `datastruct`, and `wasm-bindgen` as a dependency.
+//! The structure itself must be annotated with `#[wasm_bindgen]`, and must not be generic.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(wasm)]` | `#[dstruct(wasm = boolean)]`
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//! use wasm_bindgen::prelude::wasm_bindgen;
+//!
+//! #[wasm_bindgen]
+//! #[derive(DataStruct)]
+//! #[dstruct(wasm, get, set = "set")]
+//! pub struct Viewport {
+//!     width: u32,
+//!     height: u32,
+//! }
+//!
+//! // in JavaScript
+//! // viewport.width = 1024;
+//! // console.log(viewport.height);
+//! ```
+//!
+//! ### Reference Traits
+//!
+//! #### `deref`
+//!
+//! Implement `Deref` and `DerefMut` for the structure, targeting a field.
+//! At most one field can be configured. This does not have structure-level configuration.
+//!
+//! **Reference Type:**
+//! - `full` | `all` | `mut`: Both immutable and mutable implementations. (Default.)
+//! - `ref`: Only the immutable implementation.
+//! - `no`: Ignore the field.
+//!
+//! **Field Configuration:**
+//! - `#[dfield(deref)]` | `#[dfield(deref = boolean)]`: Implement both `Deref` and `DerefMut`, or nothing.
+//! - `#[dfield(deref = "reference_type")]`
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! struct Tracked {
+//!     #[dfield(deref)]
+//!     values: Vec<u8>,
+//!     revision: u32,
+//! }
+//!
+//! // generated code
+//! impl ::std::ops::Deref for Tracked {
+//!     type Target = Vec<u8>;
+//!     fn deref(&self) -> &Self::Target {
+//!         &self.values
+//!     }
+//! }
+//! impl ::std::ops::DerefMut for Tracked {
+//!     fn deref_mut(&mut self) -> &mut Self::Target {
+//!         &mut self.values
+//!     }
+//! }
+//! ```
+//!
+//! #### `as_ref`
+//!
+//! Implement `AsRef<FieldType>`, and optionally `AsMut<FieldType>`, for the structure.
+//! Two fields of the same type cannot both be configured, as their implementations would conflict.
+//!
+//! **Field Configuration:**
+//! - `#[dfield(as_ref)]` | `#[dfield(as_ref = boolean)]`: Implement `AsRef` only, or nothing.
+//! - `#[dfield(as_ref = "reference_type")]`: See [`deref`](#deref) for the available reference types.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! struct Document {
+//!     #[dfield(as_ref)]
+//!     title: String,
+//!     #[dfield(as_ref = "mut")]
+//!     body: Vec<u8>,
+//! }
+//! ```
+//!
+//! #### `borrow`
+//!
+//! Implement `Borrow<FieldType>`, and optionally `BorrowMut<FieldType>`, for the structure,
+//! so that it can be looked up by the field in maps and sets.
+//!
+//! `Borrow` requires `Eq`, `Ord` and `Hash` of the structure to be equivalent to those of the borrowed field.
+//! If the structure derives `cmp` implementations, only the borrowed field may take part in them,
+//! otherwise a compile error is raised, as well as for a `hash = "..."` expression on the borrowed field.
+//! A manual `Hash` implementation is not checked and must be kept consistent manually.
+//!
+//! **Field Configuration:**
+//! - `#[dfield(borrow)]` | `#[dfield(borrow = boolean)]`: Implement `Borrow` only, or nothing.
+//! - `#[dfield(borrow = "reference_type")]`: See [`deref`](#deref) for the available reference types.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(cmp(peq, eq))]
+//! struct User {
+//!     #[dfield(borrow)]
+//!     name: String,
+//!     #[dfield(cmp(eq = false))]
+//!     age: u32,
+//! }
+//! ```
+//!
+//! ### Comparison `cmp`
 //!
 //! Macro-generateable comparison traits are `Eq`, `PartialEq`, `Ord` and `PartialOrd`.
 //!
-//! **Syntax:**
+//! **Syntax:**
+//!
+//! All `cmp` configurations must be defined within `cmp(xxx)` field:
+//!
+//! ```rust,ignore
+//! #[dstruct(cmp(<your config>))]
+//! #[dfield(cmp(<your config>))]
+//! ```
+//!
+//! #### `Eq` and `PartialEq`
+//!
+//! **Syntax:**
+//! - `#[dstruct(cmp(eq))]`: Generate `Eq` implementation for the struct.
+//!   Note that this won't implement `PartialEq`, and you must explicitly enable that.
+//! - `#[dfield(cmp(peq))]` | `#[dfield(cmp(partial_eq))]`: Generate `PartialEq` implementation for the struct.
+//! - `#[dstruct(cmp(hash))]`: Generate `Hash` implementation for the struct,
+//!   hashing the same fields as the equality check, so that it's consistent with `Eq`.
+//!
+//! **Field Configuration:**
+//! - `#[dfield(cmp(eq))]`: When checking equality, this field is included. (Default if enabled.)
+//! - `#[dfield(cmp(eq = boolean))]`: Whether to include this field in equality check.
+//! - `#[dfield(hash = "expr")]`: Hash the value of the expression instead of the field, where `$self` refers to the structure.
+//!   Used by both `Hash` and `cache_key`, so they stay consistent with a normalized equality,
+//!   e.g. `hash = "$self.path.to_lowercase()"` for paths compared case-insensitively.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(cmp(eq, peq))]
+//! struct CanEq {
+//!     // #[dfield(cmp(eq))]
+//!     // you don't need to explicitly specify this.
+//!     content: usize,
+//!     #[dfield(cmp(eq = false))]
+//!     do_not_check: usize,
+//! }
+//!
+//! // generated code
+//! impl ::std::cmp::PartialEq for CanEq {
+//!     fn eq(&self, rhs: &Self) -> bool {
+//!       (self.content == rhs.content)
+//!     }
+//! }
+//! impl ::std::cmp::Eq for CanEq {}
+//! ```
+//!
+//! #### `cache_key`
+//!
+//! Generate `cache_key(&self) -> u64`, hashing the fields (and computed fields) included in the equality check
+//! with `datastruct::StableHasher`, a fixed and unkeyed FNV-1a hasher.
+//! Equal structures always have equal keys, so the struct can be used by memoization layers
+//! without implementing `Hash` for the whole struct. Every hashed field must implement `Hash`.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(cache_key)]` | `#[dstruct(cache_key = boolean)]`
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(cache_key)]
+//! struct Query {
+//!     text: String,
+//!     limit: usize,
+//!     #[dfield(cmp(eq = false))]
+//!     elapsed: f64,
+//! }
+//!
+//! let a = Query { text: "rust".to_string(), limit: 10, elapsed: 0.5 };
+//! let b = Query { text: "rust".to_string(), limit: 10, elapsed: 1.5 };
+//! assert_eq!(a.cache_key(), b.cache_key());
+//! ```
+//!
+//! #### `diff_display`
+//!
+//! Generate `diff_display(&self, other: &Self) -> datastruct::DiffDisplay`, listing the fields compared by `eq`
+//! that differ from `other`, with their old and new values, so that config reloads can log what changed.
+//! It displays like `port: 8080 → 9090, verbose: false → true`, or one field per line with `{:#}`,
+//! and provides `is_empty()` and `fields()` to check the changed fields.
+//! Fields configured with `debug = false` are displayed as `secret: changed`, without their values.
+//!
+//! Fields whose types use the type parameters are required to be `PartialEq` and `Debug` only by `diff_display`.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(diff_display)]` | `#[dstruct(diff_display = boolean)]`
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(diff_display)]
+//! struct ServerConfig {
+//!     port: u16,
+//!     #[dfield(debug = false)]
+//!     password: String,
+//!     #[dfield(cmp(eq = false))]
+//!     loaded_at: u64,
+//! }
+//!
+//! let diff = old.diff_display(&new);
+//! if !diff.is_empty() {
+//!     println!("config reloaded: {diff}");
+//! }
+//! ```
+//!
+//! #### `assert_eq_verbose`
+//!
+//! Generate `assert_eq_verbose(&self, other: &Self)`, asserting that the fields compared by `eq` are equal,
+//! and panicking with the differing fields like [`diff_display`](#diff_display) instead of the `Debug` of the whole structures,
+//! so that failed assertions of large structures in tests stay readable.
+//! The `datastruct::assert_fields_eq!(left, right)` macro calls it, like `assert_eq!`.
+//!
+//! This requires the `testing` feature of `datastruct`, usually enabled for the dev-dependency only.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(assert_eq_verbose)]` | `#[dstruct(assert_eq_verbose = boolean)]`
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::{assert_fields_eq, DataStruct};
+//!
+//! #[derive(DataStruct)]
+//! #[cfg_attr(test, dstruct(assert_eq_verbose))]
+//! struct Order {
+//!     id: u32,
+//!     items: Vec<String>,
+//! }
+//!
+//! // panics with:
+//! // assertion `left == right` failed for `Order`, the differing fields are:
+//! // items: ["apple"] → ["apple", "pear"]
+//! assert_fields_eq!(actual, expected);
+//! ```
+//!
+//! #### `Ord` and `PartialOrd`
+//!
+//! **Syntax:**
+//! - `#[dstruct(ord)]`: Implement `Ord` for the struct.
+//! - `#[dstruct(pord)]` | `#[dstruct(partial_ord)]`: Implement `PartialOrd` for the struct.
+//!
+//! **Field Configuration:**
+//! - `Ord`: The configuration key is `cmp` or `ord`. (Disabled by default.)
+//!   - `#[dfield(cmp(ord))]`: Include this field in the `Ord` implementation.
+//!   - `#[dfield(cmp(ord = boolean))]`: Whether to include this field in the `Ord` implementation.
+//!   - `#[dfield(cmp(ord = "isize"))]` | `#[dfield(cmp(ord = isize))]`:
+//!     Set the sequence of the field in the `Ord` implementation.
+//!
+//!     By default, all included fields' comparison results are chained with
+//!     [`Ordering::then_with`](https://doc.rust-lang.org/std/cmp/enum.Ordering.html#method.then_with).
+//!     This configuration can change the index of the field. Negative number is allowed to use.
+//! - `PartialOrd`: The configuration key is `pcmp`, `partial_cmp`, `pord` or `partial_ord`. (Disabled by default.)
+//!   - `#[dfield(cmp(pord))]`: Include this field in the `PartialOrd` implementation.
+//!   - `#[dfield(cmp(pord = boolean))]`: Whether to include this field in the `PartialOrd` implementation.
+//!   - `#[dfield(cmp(pord = "isize"))]` | `#[dfield(cmp(pord = isize))]`:
+//!     Set the sequence of the field in the `PartialOrd` implementation.
+//!
+//!     By default, all included fields' comparison results are chained with
+//!     [`Option::and_then`](https://doc.rust-lang.org/std/option/enum.Option.html#method.and_then) and
+//!     [`Ordering::then_with`](https://doc.rust-lang.org/std/cmp/enum.Ordering.html#method.then_with).
+//!     This configuration can change the index of the field. Negative number is allowed to use.
+//!
+//! **Note:**
+//! - If no field is configured to be included, then `Ord` and `PartialOrd` will not be implemented.
+//! - If both `Ord` and `PartialOrd` are enabled:
+//!   - If only `Ord` is configured, then `PartialOrd` will be simply `Some(Ord)`.
+//!   - If both are configured, Clippy may throw a `clippy::non_canonical_partial_ord_impl`
+//!     (non-canonical implementation of `partial_cmp` on an `Ord` type) warning about the implementation, see
+//!     [Clippy Lint](https://rust-lang.github.io/rust-clippy/master/index.html#non_canonical_partial_ord_impl)
+//!     for more information.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(cmp(eq, peq, ord, pord))]
+//! struct MyComparable {
+//!     #[dfield(cmp(ord))]
+//!     only_ord: usize,
+//!     #[dfield(cmp(pord))]
+//!     only_partial_ord: usize,
+//!     #[dfield(cmp(ord = -1, pord = -1))]
+//!     both_ord: usize,
+//! }
+//!
+//! // generated code (`Eq` and `PartialEq` is omitted).
+//!
+//! impl ::std::cmp::Ord for MyComparable {
+//!     fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+//!         self.both_ord
+//!             .cmp(&other.both_ord)
+//!             .then_with(|| self.only_ord.cmp(&other.only_ord))
+//!     }
+//! }
+//!
+//! impl ::std::cmp::PartialOrd for MyComparable {
+//!     fn partial_cmp(&self, other: &Self) -> ::core::option::Option<::std::cmp::Ordering> {
+//!         self.both_ord
+//!             .partial_cmp(&other.both_ord)
+//!             // the following identifier `__gen_xxx` is generated by the macro
+//!             .and_then(|__gen_dparord| {
+//!                 self.only_partial_ord
+//!                     .partial_cmp(&other.only_partial_ord)
+//!                     .map(|__gen_dparord_self| __gen_dparord.then(__gen_dparord_self))
+//!             })
+//!     }
+//! }
+//! ```
+//!
+//! #### Comparators `comparator`
+//!
+//! Generate `cmp_asc(a: &Self, b: &Self) -> Ordering` and `cmp_desc(a: &Self, b: &Self) -> Ordering`,
+//! comparing by the same fields and sequence as `Ord`, so they can be passed to `sort_by`
+//! without implementing `Ord` for the struct itself.
+//!
+//! **Syntax:**
+//! - `#[dstruct(cmp(comparator))]`
+//!
+//! **Field Configuration:**
+//! - Inherits from `Ord`.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(cmp(comparator))]
+//! struct Task {
+//!     #[dfield(cmp(ord = 1))]
+//!     name: String,
+//!     #[dfield(cmp(ord = 0))]
+//!     priority: u8,
+//! }
+//!
+//! tasks.sort_by(Task::cmp_desc);
+//! ```
+//!
+//! ### Operations `ops`
+//!
+//! Macro-generateable operation traits are `Add +`, `Sub -`, `Mul *`, `Div /`
+//! and their assignable versions `AddAssign +=`, `SubAssign -=`, `MulAssign *=` and `DivAssign /=`.
+//!
+//! **Syntax:**
+//!
+//! All `ops` configurations must be defined within `ops(xxx)` field:
+//!
+//! ```rust,ignore
+//! #[dstruct(ops(<your config>))]
+//! #[dfield(ops(<your config>))]
+//! ```
+//!
+//! Operations definitions on structure-level can be declared by
+//! `#[dstruct(ops(add = "type"))]` (Take `Add/AddAssign` as an example):
+//! - "both" | "all": Generate both assignment and plain operation for all fields by default.
+//! - "assign": Only generate assignment by default.
+//! - "plain" | "default"": Generate plain operation by default. (Default if enabled.)
+//!
+//! An expression template can be applied to every field inheriting the operation by
+//! `#[dstruct(ops(add(expr = "template")))]`, and the type above can be set with `mode = "type"`:
+//!
+//! ```rust,ignore
+//! #[dstruct(ops(add(expr = "$field.saturating_add($rhs_field)")))]
+//! #[dstruct(ops(sub(mode = "both", expr = "$field.saturating_sub($rhs_field)")))]
+//! ```
+//!
+//! Fields with their own expression or ignoring the operation are not affected by the template.
+//! See **About `expression`** for the placeholders.
+//!
+//! **Strict Mode:**
+//!
+//! By default, fields without configuration inherit the operation.
+//! With `#[dstruct(ops(strict))]`, every field must explicitly configure every generated operation
+//! (for example `add = "inherit"` or `add_assign = "ignore"`), otherwise a compile error is reported at the field.
+//! A struct-level expression template counts as an explicit configuration for all fields.
+//!
+//! **Scalar Operations:**
+//!
+//! `Mul` and `Div` can also be implemented with a scalar right-hand side by
+//! `#[dstruct(ops(mul(scalar = "type")))]`, for example `Mul<f32>` and `MulAssign<f32>`.
+//! The scalar implementations follow the `mode` of the operation, and every field is computed like
+//! `self.field * rhs` (or `self.field *= rhs`). Field expressions are not used, but ignored fields are still ignored.
+//!
+//! ```rust,ignore
+//! #[derive(DataStruct)]
+//! #[dstruct(ops(mul(mode = "both", scalar = "f32")))]
+//! struct Size {
+//!     w: f32,
+//!     h: f32,
+//! }
+//!
+//! // `size * 2.0` and `size *= 2.0` are available, besides `size * other` and `size *= other`.
+//! ```
+//!
+//! **Generic Structures:**
+//!
+//! For generic structures, the macro adds bounds like `T: Add<Output = T>` (or `T: AddAssign`)
+//! for every field type that uses a type parameter and inherits the operation.
+//! Fields that are ignored or use an expression are not bounded.
+//! Use `#[dstruct(ops(bound = "where predicates"))]` to replace the inferred bounds:
+//!
+//! ```rust,ignore
+//! #[derive(DataStruct)]
+//! #[dstruct(ops(add, bound = "T: Add<Output = T> + Copy"))]
+//! struct Vec2<T> {
+//!     x: T,
+//!     y: T,
+//! }
+//! ```
+//!
+//! **Field Configuration:**
+//! - Plain operations `+ - * /`: (Take `Add +` as an example:)
+//!   - `#[dfield(ops(add = "type"))]`:
+//!     - "inherit" | "default": Inherit the default configuration declared in the `dstruct` attributes.
+//!     - "ignore" | "no": Ignore this field, that is, `A1 + A2 -> A1`.
+//!   - `#[dfield(ops(add = boolean))]`: Whether to include this field.
+//!   - `#[dfield(ops(add = "expression"))]`: Use your own expression to implement the `Add`.
+//! - Assignment operations `+= -= *= /=`: (Take `AddAssign +=` as an example:)
+//!   - `#[dfield(ops(add_assign = "type"))]`:
+//!     - "inherit" | "default": Inherit the default configuration declared in the `dstruct` attributes.
+//!     - "ignore" | "no": Ignore this field, that is, `self.A <- self.A`.
+//!   - `#[dfield(ops(add_assign = boolean))]`: Whether to include this field.
+//!   - `#[dfield(ops(add_assign = "expression"))]`: Use your own expression to implement the `AddAssign`.
+//!
+//! **About `expression`:**
+//!
+//! You can use your expression to manually implement the operations.
+//! The expression must be wrapped in a literal string.
+//! Use `$self` to refer to the left-hand `self` value, and use `$rhs` to refer to the right-hand `other` value.
+//! 
+//! Use `$field` to refer to the current field of the left-hand value, that is, `$self.field`,
+//! and use `$rhs_field` to refer to the current field of the right-hand value, that is, `$rhs.field`.
+//! This allows one expression to be reused across fields.
+//! Placeholders are matched as tokens, so a `$self` inside a string of the expression is kept as is,
+//! and errors in the expression point at the attribute.
+//!
+//! For example,
+//!
+//! ```rust,ignore
+//! #[dfield(ops(add = "$self.something + $rhs.otherthing"))]
+//! field: SomeType
+//! ```
+//!
+//! will be translated into
+//!
+//! ```rust,ignore
+//! Self {
+//!     field: self.something + other.otherthing,
+//!     ..
+//! }
+//! ```
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(Debug, Clone, Copy, DataStruct)]
+//! #[dstruct(ops(add = "both"))]
+//! struct CanOpsAssign {
+//!     can_add: i8,
+//!     #[dfield(ops(add = "ignore"))]
+//!     no_add: i8,
+//!     #[dfield(ops(add_assign = "std::cmp::max($self.max, $rhs.max)"))]
+//!     max: i8,
+//!     #[dfield(ops(add_assign = "std::cmp::min($self.min, $rhs.min)"))]
+//!     min: i8,
+//! }
+//!
+//! // generated code
+//! impl ::std::ops::Add for CanOpsAssign {
+//!     type Output = Self;
+//!     fn add(self, rhs: Self) -> Self {
+//!         Self {
+//!             can_add: self.can_add + rhs.can_add,
+//!             no_add: self.no_add,
+//!             max: self.max + rhs.max,
+//!             min: self.min + rhs.min,
+//!         }
+//!     }
+//! }
+//!
+//! impl ::std::ops::AddAssign for CanOpsAssign {
+//!     fn add_assign(&mut self, rhs: Self) {
+//!         self.can_add += rhs.can_add;
+//!         self.no_add += rhs.no_add;
+//!         self.max = std::cmp::max(self.max, rhs.max);
+//!         self.min = std::cmp::min(self.min, rhs.min);
+//!     }
+//! }
+//! ```
+//!
+//! #### `identity`
+//!
+//! Generate the identity constants of the structure.
+//!
+//! **Syntax:**
+//! - `#[dstruct(ops(identity))]` | `#[dstruct(ops(identity = boolean))]`
+//!
+//! This will generate `pub const ZERO: Self`, `pub const ONE: Self` and `pub fn is_zero(&self) -> bool`.
+//! `is_zero` requires all fields to implement `PartialEq`.
+//!
+//! **Field Configuration:**
+//! - `#[dfield(ops(zero = "expression"))]`: The zero value of the field. Must be a const expression.
+//! - `#[dfield(ops(one = "expression"))]`: The unit value of the field. Must be a const expression.
+//!
+//! Numeric primitive fields use `0` and `1` by default.
+//! All fields must have a zero value, while `ONE` is only generated if all fields have a unit value.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(ops(add, identity))]
+//! struct Tagged {
+//!     value: u8,
+//!     #[dfield(ops(add = "ignore", zero = "'-'"))]
+//!     tag: char,
+//! }
+//!
+//! // generated code (`Add` is omitted)
+//! impl Tagged {
+//!     pub const ZERO: Self = Self {
+//!         value: 0 as u8,
+//!         tag: '-',
+//!     };
+//!
+//!     pub fn is_zero(&self) -> bool {
+//!         self.value == Self::ZERO.value && self.tag == Self::ZERO.tag
+//!     }
+//! }
+//! ```
+//!
+//! #### `apply_op`
+//!
+//! Generate `pub fn apply_op(&mut self, op: datastruct::DataOp, rhs: &Self)`,
+//! applying the operation selected at runtime in place, for calculators and interpreters
+//! which would otherwise match over the four operator traits.
+//!
+//! **Syntax:**
+//! - `#[dstruct(ops(apply_op))]` | `#[dstruct(ops(apply_op = boolean))]`
+//!
+//! **Restriction:**
+//! - `add`, `sub`, `mul` and `div` must all be enabled.
+//! - The structure must implement `Clone`, as `rhs` is borrowed.
+//!
+//! Each operation uses the assignment operator if it's generated, the plain operator on a clone of `self` otherwise.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::{DataOp, DataStruct};
+//!
+//! #[derive(DataStruct, Clone)]
+//! #[dstruct(ops(add = "both", sub, mul, div, apply_op))]
+//! struct Register {
+//!     value: i64,
+//! }
+//!
+//! let mut register = Register { value: 10 };
+//! register.apply_op(DataOp::Mul, &Register { value: 4 });
+//! assert_eq!(40, register.value);
+//! ```
+//!
+//! ### Combinator `zip_with`
+//!
+//! Generate a `zip_with` method, which combines two values of the structure field by field.
+//! All fields must share the same type.
+//!
+//! **Syntax:**
+//! - `#[dstruct(zip_with)]` | `#[dstruct(zip_with = boolean)]`
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(zip_with)]
+//! struct Bounds {
+//!     min: i32,
+//!     max: i32,
+//! }
+//!
+//! // generated code
+//! impl Bounds {
+//!     pub fn zip_with(self, other: Self, func: impl Fn(i32, i32) -> i32) -> Self {
+//!         Self {
+//!             min: func(self.min, other.min),
+//!             max: func(self.max, other.max),
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! ### Merge `merge`
+//!
+//! Generate a `merge` method, which merges another value of the structure into `self` field by field.
+//! This is useful for layered configurations.
+//!
+//! `datastruct::MergeDataStruct` is implemented with the method, so that layering code can be generic over the structures.
+//!
+//! **Merge Strategy:**
+//! - "overwrite" | "default": `self.field = other.field`. (Default.)
+//! - "keep" | "ignore" | "no": Keep the value of `self`.
+//! - "append" | "extend": Extend `self.field` with `other.field`. The field must implement `Extend` and `other.field` must be `IntoIterator`.
+//! - "merge" | "deep": Merge the nested structure recursively, that is, `self.field.merge(other.field)`.
+//! - "expression": Use your own expression to compute the merged value.
+//!   Use `$self` to refer to the current value, and use `$other` to refer to the merged value.
+//!
+//! **Syntax:**
+//! - `#[dstruct(merge)]` | `#[dstruct(merge = boolean)]`
+//! - `#[dstruct(merge = "strategy")]`: Enable merging, and set the default strategy of all fields.
+//!
+//! **Field Configuration:**
+//! - `#[dfield(merge)]`: Inherit the strategy from the structure. Typically, you don't need to specify this.
+//! - `#[dfield(merge = "strategy")]`: Override the strategy of the field.
+//! - `#[dfield(merge = boolean)]`: The same as "overwrite" or "keep".
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(merge)]
+//! struct Layer {
+//!     #[dfield(merge = "$other.port.or($self.port)")]
+//!     port: Option<u16>,
+//!     #[dfield(merge = "append")]
+//!     plugins: Vec<String>,
+//!     #[dfield(merge = "keep")]
+//!     name: String,
+//! }
+//!
+//! // generated code
+//! impl Layer {
+//!     pub fn merge(&mut self, other: Self) {
+//!         self.port = other.port.or(self.port);
+//!         ::std::iter::Extend::extend(&mut self.plugins, other.plugins);
+//!     }
+//! }
+//!
+//! impl datastruct::MergeDataStruct for Layer {
+//!     fn merge_from(&mut self, other: Self) {
+//!         Self::merge(self, other)
+//!     }
+//! }
+//! ```
+//!
+//! ### Clone `clone`
+//!
+//! Generate an implementation of `Clone`, cloning every field with `Clone::clone` by default.
+//! Unlike `#[derive(Clone)]`, the type parameters are not required to be `Clone`,
+//! so structures holding `Arc<T>`, `Rc<T>` or handles can be cloned for any `T`.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(clone)]` | `#[dstruct(clone = boolean)]`
+//! - `#[dstruct(clone_with_default)]`: Generate `clone_with_default(&self) -> Self`, see below.
+//!
+//! **Field Configuration:**
+//! - `#[dfield(clone = "expr")]`: Clone the field with the expression, where `$self` refers to the structure.
+//! - `#[dfield(clone = "inherit")]`: `Clone::clone`, the default behavior.
+//! - `#[dfield(clone = false)]`: The field cannot be cloned, and is reset to its default value by `clone_with_default`.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use std::sync::Arc;
+//! use datastruct::DataStruct;
+//!
+//! struct Connection;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(clone)]
+//! struct Client<T> {
+//!     #[dfield(clone = "Arc::clone(&$self.connection)")]
+//!     connection: Arc<Connection>,
+//!     #[dfield(clone = "$self.retries.min(3)")]
+//!     retries: u32,
+//!     state: Arc<T>,
+//! }
+//! ```
+//!
+//! #### `clone_with_default`
+//!
+//! Structures holding sockets or handles can't be cloned,
+//! but a copy of the configuration with a fresh connection is often needed.
+//! `clone_with_default` clones every field like `Clone` does, except the fields with `clone = false`,
+//! which are reset to their default values instead.
+//! Their default values may refer to the previous fields, like `default`.
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(clone_with_default)]
+//! struct Session {
+//!     host: String,
+//!     #[dfield(clone = false, default = "None")]
+//!     socket: Option<std::net::TcpStream>,
+//! }
+//!
+//! let copy = session.clone_with_default();
+//! assert!(copy.socket.is_none());
+//! ```
+//!
+//! ### Owned and Borrowed `cow`
+//!
+//! For structures with `Cow` fields, generate the conversions between the owned and the borrowed forms.
+//! The structure must have a lifetime parameter.
+//!
+//! - `pub fn into_owned(self) -> Struct<'static>`: Converts all `Cow` fields into owned values.
+//! - `pub fn to_borrowed(&self) -> Struct<'_>`: Borrows all `Cow` fields from `self`.
+//!   Other fields are cloned, so they must implement `Clone`.
+//!
+//! Fields are recognized as `Cow` by the last segment of their type path, like `Cow<'a, str>` or `std::borrow::Cow<'a, [u8]>`.
+//!
+//! **Syntax:**
+//! - `#[dstruct(cow)]` | `#[dstruct(cow = boolean)]`
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use std::borrow::Cow;
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(cow)]
+//! struct Message<'a> {
+//!     id: u32,
+//!     text: Cow<'a, str>,
+//! }
+//!
+//! // generated code
+//! impl<'a> Message<'a> {
+//!     pub fn into_owned(self) -> Message<'static> {
+//!         Message {
+//!             id: self.id,
+//!             text: ::std::borrow::Cow::Owned(self.text.into_owned()),
+//!         }
+//!     }
+//! }
+//!
+//! impl<'a> Message<'a> {
+//!     pub fn to_borrowed(&self) -> Message<'_> {
+//!         Message {
+//!             id: ::std::clone::Clone::clone(&self.id),
+//!             text: ::std::borrow::Cow::Borrowed(&*self.text),
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! ### Interpolation `lerp`
+//!
+//! Ask the macro to generate an implementation of `datastruct::Lerp`,
+//! which linearly interpolates every field between two values of the structure.
+//!
+//! `datastruct::Lerp` is implemented for all primitive numeric types.
+//! Integers are interpolated as `f64` and then rounded.
+//!
+//! **Syntax:**
+//! - `#[dstruct(lerp)]`
+//!
+//! **Field Configuration:**
+//! - `#[dfield(lerp)]` | `#[dfield(lerp = true)]`: Interpolate the field with `datastruct::Lerp`. (Default.)
+//! - `#[dfield(lerp = "type")]`:
+//!   - "inherit" | "default": The same as `#[dfield(lerp)]`.
+//!   - "ignore" | "no": Ignore this field, that is, keep the value of `self`.
+//! - `#[dfield(lerp = false)]`: Ignore this field.
+//! - `#[dfield(lerp = "expression")]`: Use your own expression to interpolate the field.
+//!   Use `$self` to refer to the start value, `$other` to refer to the end value and `$t` to refer to the factor.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(lerp)]
+//! struct Keyframe {
+//!     x: f32,
+//!     #[dfield(lerp = false)]
+//!     id: u32,
+//!     #[dfield(lerp = "if $t < 0.5 { $self.visible } else { $other.visible }")]
+//!     visible: bool,
+//! }
+//!
+//! // generated code
+//! impl ::datastruct::Lerp for Keyframe {
+//!     fn lerp(self, other: Self, t: f64) -> Self {
+//!         Self {
+//!             x: ::datastruct::Lerp::lerp(self.x, other.x, t),
+//!             id: self.id,
+//!             visible: if t < 0.5 { self.visible } else { other.visible },
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! ### Mapping by Type `map_type`
+//!
+//! Apply a closure to every field of a given type, such as scaling all `f64` fields,
+//! for unit conversions and normalization passes which would otherwise list the fields by hand.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(map_type("Type", ...))]`: The types whose fields can be mapped.
+//!
+//! This implements `datastruct::MapFieldsOfType<Type>` for every listed type,
+//! and generates `pub fn map_fields_of_type<T>(&mut self, func: impl FnMut(&mut T))`, selecting the type by the turbofish.
+//! The fields are visited in the order of their declarations.
+//!
+//! **Restriction:**
+//! - Types are compared as written, so a type alias doesn't match the type it names.
+//! - Every listed type must match at least one field.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(map_type("f64", "Option<f64>"))]
+//! struct Reading {
+//!     celsius: f64,
+//!     peak: Option<f64>,
+//!     samples: u32,
+//!     offset: f64,
+//! }
+//!
+//! let mut reading = Reading { celsius: 20.0, peak: Some(25.0), samples: 3, offset: 0.5 };
+//! reading.map_fields_of_type::<f64>(|value| *value *= 2.0);
+//! reading.map_fields_of_type::<Option<f64>>(|value| *value = value.map(|v| v + 32.0));
+//! assert_eq!((40.0, Some(57.0), 1.0), (reading.celsius, reading.peak, reading.offset));
+//! ```
+//!
+//! ### Patch `patch`
+//!
+//! Generate a companion patch type, whose fields are all optional,
+//! and implement `AddAssign<Patch>` for the structure to apply it.
+//! When applying a patch, `Some` fields overwrite the structure's fields and `None` fields are skipped.
+//!
+//! The patch type is named `<Struct>Patch` and has the same visibility and generics as the structure.
+//! It always implements `Default`, with all fields being `None`.
+//!
+//! **Syntax:**
+//! - `#[dstruct(patch)]` | `#[dstruct(patch = boolean)]`
+//! - `#[dstruct(patch(derive(Trait, ...)))]`: Also forward the derive macros to the patch type.
+//! - `#[dstruct(patch(diff))]`: Also generate `pub fn diff(&self, other: &Self) -> <Struct>Patch`,
+//!   which records the fields of `other` that differ from `self`. All fields must implement `PartialEq` and `Clone`.
+//!   Applying the result to `self` makes it equal to `other`.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(patch(derive(Debug)))]
+//! struct Settings {
+//!     width: u32,
+//!     title: String,
+//! }
+//!
+//! // generated code
+//! #[derive(Debug)]
+//! struct SettingsPatch {
+//!     pub width: Option<u32>,
+//!     pub title: Option<String>,
+//! }
+//!
+//! impl ::std::ops::AddAssign<SettingsPatch> for Settings {
+//!     fn add_assign(&mut self, rhs: SettingsPatch) {
+//!         if let Some(v) = rhs.width {
+//!             self.width = v;
+//!         }
+//!         if let Some(v) = rhs.title {
+//!             self.title = v;
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! ### Dirty Tracking `dirty`
+//!
+//! Generate a `<Struct>Dirty` companion with a public `bool` flag for every field,
+//! and a `set_xxx_tracked(&mut self, value, dirty: &mut <Struct>Dirty)` setter for every field,
+//! which assigns the field and marks it as modified.
+//! Persistence layers can then write only the modified fields.
+//!
+//! The companion also has:
+//! - `is_clean(&self) -> bool`: Whether no field has been modified.
+//! - `clear(&mut self)`: Marks every field as unmodified.
+//! - `changed(&self) -> impl Iterator<Item = &'static str>`: Names of the modified fields.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(dirty)]` | `#[dstruct(dirty = boolean)]`
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(dirty)]
+//! struct Profile {
+//!     name: String,
+//!     age: u8,
+//! }
+//!
+//! let mut dirty = ProfileDirty::default();
+//! profile.set_age_tracked(31, &mut dirty);
+//! for column in dirty.changed() {
+//!     // update the column
+//! }
+//! dirty.clear();
+//! ```
+//!
+//! ### Snapshot `snapshot`
+//!
+//! Generate a `<Struct>Snapshot` holding clones of the fields, with the following methods, for cheap undo support:
+//! - `snapshot(&self) -> <Struct>Snapshot`: Saves the fields.
+//! - `restore(&mut self, snapshot: <Struct>Snapshot)`: Restores the saved fields, the others are kept.
+//!
+//! The fields are cloned like the `clone` option, so `clone = "expr"` applies,
+//! and the fields with `clone = false` are not saved.
+//! Fields whose types use the type parameters are required to be `Clone` only by `snapshot`.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(snapshot)]` | `#[dstruct(snapshot = boolean)]`
+//!
+//! **Field Configuration:**
+//! - `#[dfield(snapshot = false)]`: Don't save the field, like caches or statistics.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(snapshot)]
+//! struct Document {
+//!     text: String,
+//!     cursor: usize,
+//!     #[dfield(snapshot = false)]
+//!     renders: u32,
+//! }
+//!
+//! let mut undo = vec![doc.snapshot()];
+//! doc.text.push_str(" world");
+//! doc.restore(undo.pop().unwrap());
+//! ```
+//!
+//! ### String Maps `string_map`
+//!
+//! Generate the following methods, converting between the structure and the string maps keyed by the field names,
+//! like the environment variables or the properties files:
+//! - `to_map(&self) -> HashMap<String, String>`: Converts each field with `Display`.
+//! - `from_map(map: &HashMap<String, String>) -> Result<Self, FromMapError>`: Parses each field with `FromStr`.
+//!   The missing fields are initialized with their default values, or fail with `FromMapError::Missing` without one.
+//!   A field failing to parse fails with `FromMapError::Invalid`.
+//!
+//! The fields are parsed by the `seq` order, so the default values can refer to the previous fields.
+//! Fields whose types use the type parameters are required to be `Display` and `FromStr` only by the respective methods.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(string_map)]` | `#[dstruct(string_map = boolean)]`
+//!
+//! **Field Configuration:**
+//! - `#[dfield(string_map = false)]`: Skip the field, which is then always initialized with its default value.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use std::collections::HashMap;
+//! use datastruct::{DataStruct, FromMapError};
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(string_map)]
+//! struct ServerConfig {
+//!     host: String,
+//!     #[dfield(default = "8080")]
+//!     port: u16,
+//! }
+//!
+//! let map = HashMap::from([("host".to_string(), "localhost".to_string())]);
+//! let config = ServerConfig::from_map(&map).unwrap();
+//! assert_eq!(config.port, 8080);
+//! assert_eq!(config.to_map()["port"], "8080");
+//! assert_eq!(ServerConfig::from_map(&HashMap::new()).err(), Some(FromMapError::Missing { field: "host" }));
+//! ```
+//!
+//! ### Conversions
+//!
+//! #### `tuple`
+//!
+//! Implement `From<(T1, T2, ...)>` for the structure and `From<Struct>` for the tuple.
+//! The tuple contains the fields in declaration order.
+//!
+//! Fields configured with `tuple = false` are excluded from the tuple, and are initialized with their default values.
+//! Therefore, every excluded field must have a default value.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(tuple)]` | `#[dstruct(tuple = boolean)]`
+//!
+//! **Field Configuration:**
+//! - `#[dfield(tuple = boolean)]`: Whether the field is part of the tuple. Default to `true`.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(tuple)]
+//! struct Row {
+//!     id: u64,
+//!     name: String,
+//!     #[dfield(tuple = false, default = "true")]
+//!     dirty: bool,
+//! }
+//!
+//! let row: Row = (1, "one".to_string()).into();
+//! let (id, name): (u64, String) = row.into();
+//! ```
+//!
+//! #### `into_parts`
+//!
+//! Generate `into_parts(self)`, which takes the ownership of all fields at once,
+//! without exposing them as public fields.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(into_parts)]` | `#[dstruct(into_parts = boolean)]`: Return a tuple of all fields, in declaration order.
+//! - `#[dstruct(into_parts(named))]`: Return a companion `<Struct>Parts` structure with public fields.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(into_parts(named))]
+//! struct Pair<T> {
+//!     left: T,
+//!     right: T,
+//! }
+//!
+//! let PairParts { left, right } = Pair { left: 1, right: 2 }.into_parts();
+//! ```
+//!
+//! #### `convert`
+//!
+//! Implement `From<OtherType>` for the structure, mapping the fields of the same name with `Into::into`.
+//! Fields which cannot be found in the source type should either be converted by a custom expression,
+//! or be initialized with their default values.
+//!
+//! In custom expressions, `$from` refers to the source value.
+//! Custom expressions are evaluated before the other fields are moved out of the source.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(convert(from = "OtherType"))]`: Can be repeated for multiple source types.
+//! - `#[dstruct(convert(try_from = "RawType", error = "ErrorType"))]`: Implement `TryFrom<RawType>` instead. `try_from` can be repeated as well.
+//!
+//! **Field Configuration:**
+//! - `#[dfield(convert = true)]` | `#[dfield(convert = "inherit")]`: Convert from the field of the same name. (Default.)
+//! - `#[dfield(convert = false)]` | `#[dfield(convert = "default")]`: Use the default value of the field.
+//! - `#[dfield(convert = "expr")]`: Use the custom expression.
+//! - `#[dfield(try_convert = "expr")]`: Only for `TryFrom`. Use the custom expression, which evaluates to a `Result`.
+//! - `#[dfield(validate = "expr")]`: Only for `TryFrom`. Check the converted value, referred to as `$value`, with a boolean expression.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! struct ProfileDto {
+//!     name: String,
+//!     age: u32,
+//!     tags: String,
+//! }
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(convert(from = "ProfileDto"))]
+//! struct Profile {
+//!     name: String,
+//!     age: u64,
+//!     #[dfield(convert = "$from.tags.split(',').map(String::from).collect()")]
+//!     tags: Vec<String>,
+//!     #[dfield(convert = false, default = "false")]
+//!     verified: bool,
+//! }
+//! ```
+//!
+//! #### Fallible Conversion
+//!
+//! With `try_from`, the errors of `try_convert` expressions are converted to the error type with `?`,
+//! and failed validations produce the error type from a `String` describing the failed field.
+//! The error type defaults to `Box<dyn Error + Send + Sync>`.
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! struct RawEndpoint {
+//!     host: String,
+//!     port: String,
+//! }
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(convert(try_from = "RawEndpoint"))]
+//! struct Endpoint {
+//!     #[dfield(validate = "!$value.is_empty()")]
+//!     host: String,
+//!     #[dfield(try_convert = "$from.port.parse::<u16>()", validate = "$value != 0")]
+//!     port: u16,
+//! }
+//! ```
+//!
+//! ### Reflection
+//!
+//! #### `reflect`
+//!
+//! Generate constants describing the fields, so that generic tooling can introspect the structure:
+//! - `FIELD_NAMES: &[&str]`: Names of the fields.
+//! - `FIELDS: &[datastruct::FieldInfo]`: Name, type name, whether the field has a default value, and documentation of the fields.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(reflect)]` | `#[dstruct(reflect = boolean)]`
+//! - `#[dstruct(reflect(any))]`: Also generate dynamic access to the fields by name.
+//! - `#[dstruct(reflect(debug))]`: Also implement `datastruct::DataStructFields`, see below.
+//! - `#[dstruct(reflect(compare))]`: Also generate `compare_by`, see below.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(reflect)]
+//! struct ServerConfig {
+//!     /// Address to listen on.
+//!     #[dfield(default = "String::from(\"0.0.0.0\")")]
+//!     address: String,
+//! }
+//!
+//! assert_eq!(&["address"], ServerConfig::FIELD_NAMES);
+//! assert_eq!("Address to listen on.", ServerConfig::FIELDS[0].doc);
+//! ```
+//!
+//! #### Dynamic Access
+//!
+//! With `reflect(any)`, the following methods are generated, matching on the names of the fields:
+//! - `field(&self, name: &str) -> Option<&dyn Any>`
+//! - `field_mut(&mut self, name: &str) -> Option<&mut dyn Any>`
+//! - `set_field(&mut self, name: &str, value: Box<dyn Any>) -> Result<(), datastruct::SetFieldError>`: Fails if there's no such field, or if the value is not of the field's type.
+//!
+//! These methods are only available if the structure is `'static`.
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(reflect(any))]
+//! struct Variables {
+//!     count: u32,
+//! }
+//!
+//! let vars = Variables { count: 1 };
+//! assert_eq!(Some(&1), vars.field("count").and_then(|v| v.downcast_ref::<u32>()));
+//! ```
+//!
+//! #### Generic Access
 //!
-//! All `cmp` configurations must be defined within `cmp(xxx)` field:
+//! With `reflect(debug)`, `datastruct::DataStructFields` is implemented for the structure:
+//! - `const FIELD_NAMES: &'static [&'static str]`
+//! - `fn field_debug(&self, name: &str) -> Option<&dyn Debug>`: `None` if there's no such field, or if it's configured with `debug = false`.
+//!
+//! Unlike the inherent constants, the trait lets table printers and diff tools be generic over the structures.
+//! Every field must be `Debug`, and the bounds of `where(debug = "...")` apply.
+//! Both options can be combined, like `reflect(any, debug)`.
 //!
 //! ```rust,ignore
-//! #[dstruct(cmp(<your config>))]
-//! #[dfield(cmp(<your config>))]
+//! use datastruct::{DataStruct, DataStructFields};
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(reflect(debug))]
+//! struct Limits {
+//!     connections: u32,
+//!     timeout: Option<u64>,
+//! }
+//!
+//! fn print_table<T: DataStructFields>(rows: &[T]) {
+//!     println!("{}", T::FIELD_NAMES.join(" | "));
+//!     for row in rows {
+//!         let cells = T::FIELD_NAMES
+//!             .iter()
+//!             .map(|name| format!("{:?}", row.field_debug(name).unwrap()))
+//!             .collect::<Vec<_>>();
+//!         println!("{}", cells.join(" | "));
+//!     }
+//! }
 //! ```
 //!
-//! #### `Eq` and `PartialEq`
+//! #### Runtime Ordering
 //!
-//! **Syntax:**
-//! - `#[dstruct(cmp(eq))]`: Generate `Eq` implementation for the struct.
-//!   Note that this won't implement `PartialEq`, and you must explicitly enable that.
-//! - `#[dfield(cmp(peq))]` | `#[dfield(cmp(partial_eq))]`: Generate `PartialEq` implementation for the struct.
+//! With `reflect(compare)`, `compare_by(&self, other: &Self, fields: &[(<Struct>Field, datastruct::Order)]) -> Ordering`
+//! is generated, comparing by the fields in the order selected at runtime, like the sorting columns of a table.
+//! The fields are the variants of the `field_enum` enumeration, which is required,
+//! so a column name is looked up with `<Struct>Field::ALL` and `name`.
 //!
-//! **Field Configuration:**
-//! - `#[dfield(cmp(eq))]`: When checking equality, this field is included. (Default if enabled.)
-//! - `#[dfield(cmp(eq = boolean))]`: Whether to include this field in equality check.
+//! Only the fields with `cmp(ord)` or `cmp(pord)` can be compared, using `Ord` or `PartialOrd` respectively,
+//! and incomparable values are considered equal. Comparing by another field panics.
+//! The bounds of `where(cmp = "...")` apply.
+//!
+//! ```rust,ignore
+//! use datastruct::{DataStruct, Order};
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(reflect(compare), field_enum)]
+//! struct Row {
+//!     #[dfield(cmp(ord))]
+//!     name: String,
+//!     #[dfield(cmp(pord))]
+//!     score: f64,
+//! }
+//!
+//! rows.sort_by(|a, b| a.compare_by(b, &[(RowField::Score, Order::Descending), (RowField::Name, Order::Ascending)]));
+//! ```
+//!
+//! #### `debug_fields`
+//!
+//! Generate `debug_fields(&self) -> impl Iterator<Item = (&'static str, &dyn Debug)>`,
+//! iterating over the names and values of the fields, so that structured loggers can walk the fields.
+//! Fields configured with `debug = false` are skipped, like the manual `Debug` implementation.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(debug_fields)]` | `#[dstruct(debug_fields = boolean)]`
 //!
 //! **Examples:**
 //!
@@ -418,59 +2478,60 @@
 //! use datastruct::DataStruct;
 //!
 //! #[derive(DataStruct)]
-//! #[dstruct(cmp(eq, peq))]
-//! struct CanEq {
-//!     // #[dfield(cmp(eq))]
-//!     // you don't need to explicitly specify this.
-//!     content: usize,
-//!     #[dfield(cmp(eq = false))]
-//!     do_not_check: usize,
+//! #[dstruct(debug_fields)]
+//! struct Session {
+//!     id: u32,
+//!     #[dfield(debug = false)]
+//!     secret: String,
 //! }
 //!
-//! // generated code
-//! impl ::std::cmp::PartialEq for CanEq {
-//!     fn eq(&self, rhs: &Self) -> bool {
-//!       (self.content == rhs.content)
-//!     }
+//! for (name, value) in session.debug_fields() {
+//!     println!("{name} = {value:?}");
 //! }
-//! impl ::std::cmp::Eq for CanEq {}
 //! ```
 //!
-//! #### `Ord` and `PartialOrd`
+//! #### Tracing Fields `tracing`
 //!
-//! **Syntax:**
-//! - `#[dstruct(ord)]`: Implement `Ord` for the struct.
-//! - `#[dstruct(pord)]` | `#[dstruct(partial_ord)]`: Implement `PartialOrd` for the struct.
+//! Generate `record_fields(&self, span: &tracing::Span)`, recording the fields into the span by their names with `Debug`,
+//! so that the structures can be attached to spans without recording them key by key.
+//! Fields configured with `debug = false` are skipped, like the manual `Debug` implementation.
 //!
-//! **Field Configuration:**
-//! - `Ord`: The configuration key is `cmp` or `ord`. (Disabled by default.)
-//!   - `#[dfield(cmp(ord))]`: Include this field in the `Ord` implementation.
-//!   - `#[dfield(cmp(ord = boolean))]`: Whether to include this field in the `Ord` implementation.
-//!   - `#[dfield(cmp(ord = "isize"))]` | `#[dfield(cmp(ord = isize))]`:
-//!     Set the sequence of the field in the `Ord` implementation.
+//! The span only keeps the fields it declares, so declare them with `tracing::field::Empty` when creating it.
+//! Fields whose types use the type parameters are required to be `Debug` only by `record_fields`.
+//! This requires the `tracing` feature of `datastruct`, and `tracing` as a dependency.
 //!
-//!     By default, all included fields' comparison results are chained with
-//!     [`Ordering::then_with`](https://doc.rust-lang.org/std/cmp/enum.Ordering.html#method.then_with).
-//!     This configuration can change the index of the field. Negative number is allowed to use.
-//! - `PartialOrd`: The configuration key is `pcmp`, `partial_cmp`, `pord` or `partial_ord`. (Disabled by default.)
-//!   - `#[dfield(cmp(pord))]`: Include this field in the `PartialOrd` implementation.
-//!   - `#[dfield(cmp(pord = boolean))]`: Whether to include this field in the `PartialOrd` implementation.
-//!   - `#[dfield(cmp(pord = "isize"))]` | `#[dfield(cmp(pord = isize))]`:
-//!     Set the sequence of the field in the `PartialOrd` implementation.
+//! **Struct Configuration:**
+//! - `#[dstruct(tracing)]` | `#[dstruct(tracing = boolean)]`
 //!
-//!     By default, all included fields' comparison results are chained with
-//!     [`Option::and_then`](https://doc.rust-lang.org/std/option/enum.Option.html#method.and_then) and
-//!     [`Ordering::then_with`](https://doc.rust-lang.org/std/cmp/enum.Ordering.html#method.then_with).
-//!     This configuration can change the index of the field. Negative number is allowed to use.
+//! **Examples:**
 //!
-//! **Note:**
-//! - If no field is configured to be included, then `Ord` and `PartialOrd` will not be implemented.
-//! - If both `Ord` and `PartialOrd` are enabled:
-//!   - If only `Ord` is configured, then `PartialOrd` will be simply `Some(Ord)`.
-//!   - If both are configured, Clippy may throw a `clippy::non_canonical_partial_ord_impl`
-//!     (non-canonical implementation of `partial_cmp` on an `Ord` type) warning about the implementation, see
-//!     [Clippy Lint](https://rust-lang.github.io/rust-clippy/master/index.html#non_canonical_partial_ord_impl)
-//!     for more information.
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//! use tracing::field::Empty;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(tracing)]
+//! struct Request {
+//!     method: &'static str,
+//!     path: String,
+//!     #[dfield(debug = false)]
+//!     token: String,
+//! }
+//!
+//! let span = tracing::info_span!("request", method = Empty, path = Empty);
+//! request.record_fields(&span);
+//! ```
+//!
+//! #### `visit`
+//!
+//! Generate a companion `<Struct>Visitor` trait, with a `visit_<field>(&mut self, value: &FieldType)` method for every field,
+//! and `accept(&self, visitor: &mut impl <Struct>Visitor)` on the structure, which visits the fields in order.
+//! Every method of the visitor does nothing by default.
+//!
+//! This is a type-safe alternative to the dynamic access of `reflect(any)`.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(visit)]` | `#[dstruct(visit = boolean)]`
 //!
 //! **Examples:**
 //!
@@ -478,137 +2539,309 @@
 //! use datastruct::DataStruct;
 //!
 //! #[derive(DataStruct)]
-//! #[dstruct(cmp(eq, peq, ord, pord))]
-//! struct MyComparable {
-//!     #[dfield(cmp(ord))]
-//!     only_ord: usize,
-//!     #[dfield(cmp(pord))]
-//!     only_partial_ord: usize,
-//!     #[dfield(cmp(ord = -1, pord = -1))]
-//!     both_ord: usize,
+//! #[dstruct(visit)]
+//! struct Metrics {
+//!     requests: u64,
+//!     errors: u64,
 //! }
 //!
-//! // generated code (`Eq` and `PartialEq` is omitted).
+//! struct Sum(u64);
 //!
-//! impl ::std::cmp::Ord for MyComparable {
-//!     fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
-//!         self.both_ord
-//!             .cmp(&other.both_ord)
-//!             .then_with(|| self.only_ord.cmp(&other.only_ord))
+//! impl MetricsVisitor for Sum {
+//!     fn visit_requests(&mut self, value: &u64) {
+//!         self.0 += value;
 //!     }
 //! }
+//! ```
 //!
-//! impl ::std::cmp::PartialOrd for MyComparable {
-//!     fn partial_cmp(&self, other: &Self) -> ::core::option::Option<::std::cmp::Ordering> {
-//!         self.both_ord
-//!             .partial_cmp(&other.both_ord)
-//!             // the following identifier `__gen_xxx` is generated by the macro
-//!             .and_then(|__gen_dparord| {
-//!                 self.only_partial_ord
-//!                     .partial_cmp(&other.only_partial_ord)
-//!                     .map(|__gen_dparord_self| __gen_dparord.then(__gen_dparord_self))
-//!             })
+//! #### `field_enum`
+//!
+//! Generate companion enums for the fields, a type-safe alternative to the dynamic access of `reflect(any)`
+//! for settings UIs and scripting:
+//! - `<Struct>Field`: A unit variant for every field, named in `PascalCase`, with `ALL` listing them
+//!   and `name(self) -> &'static str` returning the field name.
+//! - `<Struct>FieldValue`: A variant holding the value for every field, with `field(&self) -> <Struct>Field`.
+//!
+//! And the following methods on the structure:
+//! - `get(&self, field: <Struct>Field) -> <Struct>FieldValue`: Returns a clone of the field.
+//! - `set(&mut self, value: <Struct>FieldValue)`: Assigns the field of the value.
+//! - `apply_values(&mut self, values: impl IntoIterator<Item = <Struct>FieldValue>)`: Assigns the fields of a batch of values in order,
+//!   like the updates parsed from a form or command line flags.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(field_enum)]` | `#[dstruct(field_enum = boolean)]`
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(field_enum)]
+//! struct Settings {
+//!     volume: u8,
+//!     player_name: String,
+//! }
+//!
+//! let mut settings = Settings { volume: 5, player_name: "guest".to_string() };
+//! settings.set(SettingsFieldValue::Volume(7));
+//! for field in SettingsField::ALL {
+//!     match settings.get(field) {
+//!         SettingsFieldValue::Volume(volume) => println!("{}: {}", field.name(), volume),
+//!         SettingsFieldValue::PlayerName(name) => println!("{}: {}", field.name(), name),
 //!     }
 //! }
 //! ```
 //!
-//! ### Operations `ops`
+//! ### Computed Fields `computed`
 //!
-//! Macro-generateable operation traits are `Add +`, `Sub -`, `Mul *`, `Div /`
-//! and their assignable versions `AddAssign +=`, `SubAssign -=`, `MulAssign *=` and `DivAssign /=`.
+//! Generate getters of values derived from the other fields, so that derived quantities live next to the data definition.
+//! The expression is evaluated in a `&self` method, every time the getter is called.
 //!
-//! **Syntax:**
+//! Computed fields can be included in the manual `Debug` implementation with `debug`,
+//! and in the comparisons with `cmp(...)`, which accepts the same options as [the field configuration](#comparison-cmp).
+//! They are excluded from both by default.
 //!
-//! All `ops` configurations must be defined within `ops(xxx)` field:
+//! **Struct Configuration:**
+//! - `#[dstruct(computed(name = "ident", ty = "Type", expr = "expr"))]`: Can be repeated for multiple computed fields.
+//! - `#[dstruct(computed(..., debug))]`
+//! - `#[dstruct(computed(..., cmp(<your config>)))]`
+//!
+//! **Examples:**
 //!
 //! ```rust,ignore
-//! #[dstruct(ops(<your config>))]
-//! #[dfield(ops(<your config>))]
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(debug, cmp(peq, pord))]
+//! #[dstruct(computed(name = "area", ty = "f64", expr = "self.w * self.h", debug, cmp(pord)))]
+//! struct Rect {
+//!     #[dfield(cmp(eq = false))]
+//!     w: f64,
+//!     #[dfield(cmp(eq = false))]
+//!     h: f64,
+//! }
+//!
+//! let rect = Rect { w: 2.0, h: 3.0 };
+//! assert_eq!(6.0, rect.area());
+//! assert!(rect == Rect { w: 3.0, h: 2.0 });
 //! ```
 //!
-//! Operations definitions on structure-level can be declared by
-//! `#[dstruct(ops(add = "type"))]` (Take `Add/AddAssign` as an example):
-//! - "both" | "all": Generate both assignment and plain operation for all fields by default.
-//! - "assign": Only generate assignment by default.
-//! - "plain" | "default"": Generate plain operation by default. (Default if enabled.)
+//! ### Constants `consts`
 //!
-//! **Field Configuration:**
-//! - Plain operations `+ - * /`: (Take `Add +` as an example:)
-//!   - `#[dfield(ops(add = "type"))]`:
-//!     - "inherit" | "default": Inherit the default configuration declared in the `dstruct` attributes.
-//!     - "ignore" | "no": Ignore this field, that is, `A1 + A2 -> A1`.
-//!   - `#[dfield(ops(add = boolean))]`: Whether to include this field.
-//!   - `#[dfield(ops(add = "expression"))]`: Use your own expression to implement the `Add`.
-//! - Assignment operations `+= -= *= /=`: (Take `AddAssign +=` as an example:)
-//!   - `#[dfield(ops(add_assign = "type"))]`:
-//!     - "inherit" | "default": Inherit the default configuration declared in the `dstruct` attributes.
-//!     - "ignore" | "no": Ignore this field, that is, `self.A <- self.A`.
-//!   - `#[dfield(ops(add_assign = boolean))]`: Whether to include this field.
-//!   - `#[dfield(ops(add_assign = "expression"))]`: Use your own expression to implement the `AddAssign`.
+//! Generate associated constants in the generated inherent implementation.
 //!
-//! **About `expression`:**
+//! The type of a constant is inferred from its literal value, following the language's defaults,
+//! i.e. `i32` and `f64` for unsuffixed numbers, and `&'static str` for strings.
+//! Otherwise, the type must be specified explicitly.
 //!
-//! You can use your expression to manually implement the operations.
-//! The expression must be wrapped in a literal string.
-//! Use `$self` to refer to the left-hand `self` value, and use `$rhs` to refer to the right-hand `other` value.
+//! **Struct Configuration:**
+//! - `#[dstruct(consts(NAME = "literal"))]`
+//! - `#[dstruct(consts(NAME(ty = "Type", value = "expr")))]`
 //!
-//! For example,
+//! **Examples:**
 //!
 //! ```rust,ignore
-//! #[dfield(ops(add = "$self.something + $rhs.otherthing"))]
-//! field: SomeType
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(consts(MAX_SIZE = "1024usize", VERSION = "3"))]
+//! #[dstruct(consts(HEADER(ty = "[u8; 2]", value = "[0xCA, 0xFE]")))]
+//! struct Packet {
+//!     payload: Vec<u8>,
+//! }
+//!
+//! assert_eq!(1024, Packet::MAX_SIZE);
 //! ```
 //!
-//! will be translated into
+//! ### Layout Assertions `assert`
+//!
+//! Assert the size and the alignment of the structure at compile time,
+//! so that a layout regression of a data-heavy structure, such as a new field or a reordered `repr(C)`,
+//! fails the build at the expected value instead of going unnoticed.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(assert(size = n))]`: `size_of::<Self>()` must be `n` bytes.
+//! - `#[dstruct(assert(align = n))]`: `align_of::<Self>()` must be `n` bytes, a power of two.
+//!
+//! **Restriction:**
+//! - The structure must not be generic.
+//!
+//! **Examples:**
 //!
 //! ```rust,ignore
-//! Self {
-//!     field: self.something + other.otherthing,
-//!     ..
+//! use datastruct::DataStruct;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(assert(size = 16, align = 8))]
+//! #[repr(C)]
+//! struct Sample {
+//!     timestamp: u64,
+//!     value: f32,
+//!     channel: u16,
 //! }
+//!
+//! // generated code
+//! const _: () = {
+//!     ::core::assert!(::core::mem::size_of::<Sample>() == 16, "the size of `Sample` is not 16 bytes");
+//!     ::core::assert!(::core::mem::align_of::<Sample>() == 8, "the alignment of `Sample` is not 8 bytes");
+//! };
 //! ```
 //!
+//! ### Invariant `invariant`
+//!
+//! Define an invariant of the structure, a `fn(&Self) -> Result<(), E>`, which guards every generated mutation path:
+//! - `check_invariant(&self) -> Result<(), Error>` is generated to check the invariant manually.
+//! - `try_set_xxx(&mut self, value) -> Result<(), Error>` is generated for every field with a `set` setter.
+//!   The old value is restored if the invariant is violated.
+//! - `try_data_default() -> Result<Self, Error>` is generated if `default` is enabled,
+//!   and `datastruct::TryDataStruct` is implemented with it, so that generic code can require fallible defaults.
+//! - `build()` of the [builder](#builder-builder) returns the `Invariant(Error)` variant of the builder error with `builder(try)`,
+//!   and panics otherwise.
+//! - `TryFrom` [conversions](#convert) return the error, which must be convertible into the conversion's error type.
+//!
+//! The error of the invariant is converted into `Error` with `Into`.
+//! `Error` defaults to `Box<dyn Error + Send + Sync>`.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(invariant = "expr")]`
+//! - `#[dstruct(invariant(check = "expr", error = "Type"))]`
+//!
 //! **Examples:**
 //!
 //! ```rust,ignore
 //! use datastruct::DataStruct;
 //!
-//! #[derive(Debug, Clone, Copy, DataStruct)]
-//! #[dstruct(ops(add = "both"))]
-//! struct CanOpsAssign {
-//!     can_add: i8,
-//!     #[dfield(ops(add = "ignore"))]
-//!     no_add: i8,
-//!     #[dfield(ops(add_assign = "std::cmp::max($self.max, $rhs.max)"))]
-//!     max: i8,
-//!     #[dfield(ops(add_assign = "std::cmp::min($self.min, $rhs.min)"))]
-//!     min: i8,
+//! #[derive(DataStruct)]
+//! #[dstruct(default, set = "set", invariant(check = "Range::check", error = "RangeError"))]
+//! struct Range {
+//!     #[dfield(default = "0")]
+//!     min: i32,
+//!     #[dfield(default = "10")]
+//!     max: i32,
 //! }
 //!
-//! // generated code
-//! impl ::std::ops::Add for CanOpsAssign {
-//!     type Output = Self;
-//!     fn add(self, rhs: Self) -> Self {
-//!         Self {
-//!             can_add: self.can_add + rhs.can_add,
-//!             no_add: self.no_add,
-//!             max: self.max + rhs.max,
-//!             min: self.min + rhs.min,
-//!         }
+//! impl Range {
+//!     fn check(&self) -> Result<(), RangeError> {
+//!         if self.min <= self.max { Ok(()) } else { Err(RangeError) }
 //!     }
 //! }
 //!
-//! impl ::std::ops::AddAssign for CanOpsAssign {
-//!     fn add_assign(&mut self, rhs: Self) {
-//!         self.can_add += rhs.can_add;
-//!         self.no_add += rhs.no_add;
-//!         self.max = std::cmp::max(self.max, rhs.max);
-//!         self.min = std::cmp::min(self.min, rhs.min);
-//!     }
+//! let mut range = Range::try_data_default().unwrap();
+//! assert!(range.try_set_min(20).is_err());
+//! ```
+//!
+//! ### Property Testing `arbitrary`
+//!
+//! Implement `proptest::arbitrary::Arbitrary` and/or `quickcheck::Arbitrary` for the structure.
+//! This requires the `proptest` or `quickcheck` feature of `datastruct`, and the corresponding crate as a dependency.
+//!
+//! Every field is generated by the `Arbitrary` implementation of its type, unless configured otherwise.
+//! Fields configured with `arbitrary(default)` are initialized with their default values, in the `seq` order,
+//! so they may refer to the generated fields.
+//! Type parameters are required to implement `Arbitrary` as well.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(arbitrary)]` | `#[dstruct(arbitrary = boolean)]`: Implement every enabled backend.
+//! - `#[dstruct(arbitrary(proptest, quickcheck))]`: Implement the specified backends.
+//!
+//! **Field Configuration:**
+//! - `#[dfield(arbitrary(range = "0..100"))]`: `proptest` only. Generate the field within the range.
+//! - `#[dfield(arbitrary(strategy = "expr"))]`: `proptest` only. Generate the field with a custom strategy.
+//! - `#[dfield(arbitrary(gen = "expr"))]`: `quickcheck` only. Generate the field with a custom expression, `$g` refers to the `&mut Gen`.
+//! - `#[dfield(arbitrary(default))]` | `#[dfield(arbitrary = false)]`: Use the default value of the field.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//!
+//! #[derive(Debug, Clone, DataStruct)]
+//! #[dstruct(arbitrary(proptest))]
+//! struct Sample {
+//!     #[dfield(arbitrary(range = "1..100"))]
+//!     weight: u8,
+//!     #[dfield(arbitrary(strategy = "\"[a-z]{1,8}\""))]
+//!     name: String,
+//!     #[dfield(default = "weight as u32 * 2", arbitrary(default))]
+//!     doubled: u32,
+//! }
+//! ```
+//!
+//! ### Random Sampling `random`
+//!
+//! Generate `fn random<R: Rng + ?Sized>(rng: &mut R) -> Self`, sampling a random instance of the structure.
+//! This requires the `rand` feature of `datastruct`, and `rand` 0.8 as a dependency.
+//!
+//! Every field is sampled from the `Standard` distribution, unless configured otherwise.
+//! Fields configured with `random(default)` are initialized with their default values, in the `seq` order,
+//! so they may refer to the sampled fields.
+//! Type parameters are required to be sampled from the `Standard` distribution as well.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(random)]` | `#[dstruct(random = boolean)]`
+//!
+//! **Field Configuration:**
+//! - `#[dfield(random(range = "0..100"))]`: Sample the field within the range, with `Rng::gen_range`.
+//! - `#[dfield(random(gen = "expr"))]`: Sample the field with a custom expression, `$rng` refers to the `&mut R`.
+//! - `#[dfield(random(default))]` | `#[dfield(random = false)]`: Use the default value of the field.
+//!
+//! **Examples:**
+//!
+//! ```rust,ignore
+//! use datastruct::DataStruct;
+//! use rand::SeedableRng;
+//!
+//! #[derive(DataStruct)]
+//! #[dstruct(random)]
+//! struct Particle {
+//!     #[dfield(random(range = "0..100"))]
+//!     x: u8,
+//!     #[dfield(random(range = "-1.0..=1.0"))]
+//!     velocity: f64,
+//!     alive: bool,
 //! }
+//!
+//! let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+//! let particle = Particle::random(&mut rng);
+//! assert!(particle.x < 100);
+//! ```
+//!
+//! ### Expansion Dump `dump`
+//!
+//! Print the code generated for the structure to stderr at compile time,
+//! to inspect the expansion without installing `cargo expand`.
+//! The output is formatted with `prettyplease` if the `dump` feature of `datastruct` is enabled,
+//! otherwise the raw token stream is printed.
+//!
+//! The dump can also be requested without touching the source, by setting the `DATASTRUCT_DUMP` environment variable
+//! to a comma-separated list of structure names, or `*` for all of them.
+//! Cargo doesn't track the variable, so the crate may need to be touched to be recompiled.
+//!
+//! **Struct Configuration:**
+//! - `#[dstruct(dump)]` | `#[dstruct(dump = boolean)]`
+//!
+//! **Examples:**
+//!
+//! ```shell
+//! DATASTRUCT_DUMP=Particle cargo build --features datastruct/dump
 //! ```
 
 
+mod defaulted;
+mod diff;
+mod hash;
+mod ops;
+mod reflect;
+mod string_map;
 mod traits;
-pub use traits::{DataStruct, ConstDataStruct};
+pub use defaulted::Defaulted;
+pub use diff::DiffDisplay;
+pub use hash::StableHasher;
+pub use ops::DataOp;
+pub use reflect::{FieldInfo, Order, SetFieldError};
+pub use string_map::FromMapError;
+pub use traits::{
+    const_default, DataStruct, ConstDataStruct, DataStructFields, Lerp, MapFieldsOfType, MergeDataStruct, TryDataStruct,
+};
 pub use datastruct_derive::DataStruct;
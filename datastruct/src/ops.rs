@@ -0,0 +1,13 @@
+/// An arithmetic operation selected at runtime, applied by the `apply_op` method,
+/// generated by the macro's `ops(apply_op)` option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataOp {
+    /// `+`, with `Add` or `AddAssign`.
+    Add,
+    /// `-`, with `Sub` or `SubAssign`.
+    Sub,
+    /// `*`, with `Mul` or `MulAssign`.
+    Mul,
+    /// `/`, with `Div` or `DivAssign`.
+    Div,
+}
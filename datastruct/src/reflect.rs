@@ -0,0 +1,49 @@
+/// Metadata of a field, generated by the macro's `reflect` option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldInfo {
+    /// Name of the field.
+    pub name: &'static str,
+    /// Type of the field, as written in the source code.
+    pub type_name: &'static str,
+    /// Whether the field has a default value.
+    pub has_default: bool,
+    /// Documentation of the field, or an empty string.
+    pub doc: &'static str,
+}
+
+/// Direction of a field in the `compare_by` method, generated by the macro's `reflect(compare)` option.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Order {
+    /// From the least value to the greatest.
+    #[default]
+    Ascending,
+    /// From the greatest value to the least.
+    Descending,
+}
+
+/// Error of the `set_field` method, generated by the macro's `reflect(any)` option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SetFieldError {
+    /// No field has the given name.
+    UnknownField,
+    /// The value is not of the field's type.
+    TypeMismatch {
+        /// Name of the field.
+        field: &'static str,
+        /// Type of the field, as written in the source code.
+        expected: &'static str,
+    },
+}
+
+impl std::fmt::Display for SetFieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownField => f.write_str("unknown field"),
+            Self::TypeMismatch { field, expected } => {
+                write!(f, "field `{}` expects a value of type `{}`", field, expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SetFieldError {}
@@ -0,0 +1,31 @@
+/// Error of the `from_map` method, generated by the macro's `string_map` option.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FromMapError {
+    /// The field is missing from the map, and has no default value.
+    Missing {
+        /// Name of the field.
+        field: &'static str,
+    },
+    /// The value cannot be parsed by the field's `FromStr`.
+    Invalid {
+        /// Name of the field.
+        field: &'static str,
+        /// The value in the map.
+        value: String,
+        /// The error of `FromStr`.
+        message: String,
+    },
+}
+
+impl std::fmt::Display for FromMapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing { field } => write!(f, "missing field `{}`", field),
+            Self::Invalid { field, value, message } => {
+                write!(f, "invalid value `{}` of field `{}`: {}", value, field, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FromMapError {}
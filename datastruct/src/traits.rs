@@ -4,8 +4,87 @@ pub trait DataStruct {
     fn data_default() -> Self;
 }
 
+/// The macro's `invariant` option will implement this trait for the structure if `default` is enabled,
+/// checking the default value against the invariant.
+pub trait TryDataStruct {
+    /// The error returned if the default value is invalid.
+    type Error;
+
+    /// Returns the default value of the structure, or the error if it's invalid.
+    fn try_data_default() -> Result<Self, Self::Error>
+    where
+        Self: Sized;
+}
+
 /// The macro's `const` option will implement this trait for the structure.
 pub trait ConstDataStruct {
     /// The constant default value of the structure.
     const DEFAULT: Self;
 }
+
+/// Returns the constant default value of `T`, usable in const contexts like statics and const generics.
+pub const fn const_default<T: ConstDataStruct>() -> T {
+    T::DEFAULT
+}
+
+/// The constant default value of a type, like `const_default!(Config)`,
+/// without naming the `ConstDataStruct::DEFAULT` path.
+#[macro_export]
+macro_rules! const_default {
+    ($ty:ty) => {
+        <$ty as $crate::ConstDataStruct>::DEFAULT
+    };
+}
+
+/// The macro's `merge` option will implement this trait for the structure.
+pub trait MergeDataStruct {
+    /// Merges `other` into `self` field by field, according to the merge strategy of each field.
+    fn merge_from(&mut self, other: Self);
+}
+
+/// The macro's `reflect(debug)` option will implement this trait for the structure,
+/// so that table printers and diff tools can be generic over the structures.
+pub trait DataStructFields {
+    /// Names of the fields.
+    const FIELD_NAMES: &'static [&'static str];
+
+    /// Returns a field by its name, or `None` if there's no such field or it's configured with `debug = false`.
+    fn field_debug(&self, name: &str) -> Option<&dyn std::fmt::Debug>;
+}
+
+/// The macro's `map_type` option will implement this trait for the structure, once for every listed type.
+pub trait MapFieldsOfType<T> {
+    /// Applies `func` to every field of type `T`, in the order of their declarations.
+    fn map_fields_of_type(&mut self, func: impl FnMut(&mut T));
+}
+
+/// The macro's `lerp` option will implement this trait for the structure.
+pub trait Lerp {
+    /// Linearly interpolates between `self` and `other` by `t`.
+    ///
+    /// `t = 0.0` returns `self` and `t = 1.0` returns `other`.
+    fn lerp(self, other: Self, t: f64) -> Self;
+}
+
+macro_rules! __impl_lerp_float {
+    ($($ty:ty),+ $(,)?) => {
+        $(impl Lerp for $ty {
+            fn lerp(self, other: Self, t: f64) -> Self {
+                self + (other - self) * t as $ty
+            }
+        })+
+    };
+}
+
+macro_rules! __impl_lerp_int {
+    ($($ty:ty),+ $(,)?) => {
+        $(impl Lerp for $ty {
+            fn lerp(self, other: Self, t: f64) -> Self {
+                (self as f64 + (other as f64 - self as f64) * t).round() as $ty
+            }
+        })+
+    };
+}
+
+__impl_lerp_float!(f32, f64);
+__impl_lerp_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
@@ -0,0 +1,223 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens};
+use syn::Lit;
+
+use crate::generate::RichStructContent;
+use crate::utils::synerr::{ResultExt, SynErrorExt};
+
+/// Implement the reference traits (`Deref`, `AsRef`, ...) with or without their mutable versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RefType {
+    /// Both immutable and mutable.
+    #[default]
+    Full,
+    /// Immutable only.
+    Ref,
+    No,
+}
+
+impl RefType {
+    pub fn from_lit(lit: &Lit) -> syn::Result<Self> {
+        match lit {
+            Lit::Str(s) => match s.value().as_str() {
+                "full" | "all" | "mut" => Ok(Self::Full),
+                "ref" => Ok(Self::Ref),
+                "no" => Ok(Self::No),
+                _ => Err(syn::Error::new(lit.span(), "unknown reference type")),
+            },
+            Lit::Bool(b) => {
+                if b.value {
+                    Ok(Self::Full)
+                } else {
+                    Ok(Self::No)
+                }
+            }
+            _ => Err(syn::Error::new(lit.span(), "invalid reference type")),
+        }
+    }
+}
+
+pub fn impl_deref(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+    let mut targets = syntax
+        .fields
+        .iter()
+        .filter(|f| f.config.deref != RefType::No);
+    let field = match targets.next() {
+        Some(field) => field,
+        None => return Ok(Default::default()),
+    };
+
+    let mut err: Option<syn::Error> = None;
+    for other in targets {
+        err.update_or_combine(syn::Error::new(
+            other.ident.span(),
+            "at most one field can be `deref`ed",
+        ));
+    }
+    err.ok_or(()).swap()?;
+
+    let ident = &syntax.ident;
+    let field_ident = &field.ident;
+    let ty = &field.field_type;
+    let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+
+    let deref_mut = if field.config.deref == RefType::Full {
+        quote! {
+            impl #impl_g ::std::ops::DerefMut for #ident #type_g #where_clause {
+                fn deref_mut(&mut self) -> &mut Self::Target {
+                    &mut self.#field_ident
+                }
+            }
+        }
+    } else {
+        Default::default()
+    };
+
+    Ok(quote! {
+        impl #impl_g ::std::ops::Deref for #ident #type_g #where_clause {
+            type Target = #ty;
+
+            fn deref(&self) -> &Self::Target {
+                &self.#field_ident
+            }
+        }
+
+        #deref_mut
+    })
+}
+
+pub fn impl_as_ref(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+    let ident = &syntax.ident;
+    let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+    let mut ts = TokenStream2::new();
+    let mut err: Option<syn::Error> = None;
+    let mut seen: Vec<String> = Vec::new();
+
+    for field in syntax.fields.iter().filter(|f| f.config.as_ref != RefType::No) {
+        let field_ident = &field.ident;
+        let ty = &field.field_type;
+
+        let ty_str = ty.to_token_stream().to_string();
+        if seen.contains(&ty_str) {
+            err.update_or_combine(syn::Error::new(
+                field_ident.span(),
+                format!("another field already implements `AsRef<{}>`", ty_str),
+            ));
+            continue;
+        }
+        seen.push(ty_str);
+
+        ts.extend(quote! {
+            impl #impl_g ::std::convert::AsRef<#ty> for #ident #type_g #where_clause {
+                fn as_ref(&self) -> &#ty {
+                    &self.#field_ident
+                }
+            }
+        });
+        if field.config.as_ref == RefType::Full {
+            ts.extend(quote! {
+                impl #impl_g ::std::convert::AsMut<#ty> for #ident #type_g #where_clause {
+                    fn as_mut(&mut self) -> &mut #ty {
+                        &mut self.#field_ident
+                    }
+                }
+            });
+        }
+    }
+
+    err.ok_or(()).swap()?;
+
+    Ok(ts)
+}
+
+/// `Borrow<T>` requires `Eq`, `Ord` and `Hash` of the structure to behave identically to
+/// those of the borrowed field, so the comparison configuration must only involve that field.
+pub fn impl_borrow(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+    let ident = &syntax.ident;
+    let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+    let mut ts = TokenStream2::new();
+    let mut err: Option<syn::Error> = None;
+    let mut seen: Vec<String> = Vec::new();
+    let cmp = &syntax.config.cmp;
+
+    for field in syntax.fields.iter().filter(|f| f.config.borrow != RefType::No) {
+        let field_ident = &field.ident;
+        let ty = &field.field_type;
+
+        let ty_str = ty.to_token_stream().to_string();
+        if seen.contains(&ty_str) {
+            err.update_or_combine(syn::Error::new(
+                field_ident.span(),
+                format!("another field already implements `Borrow<{}>`", ty_str),
+            ));
+            continue;
+        }
+        seen.push(ty_str);
+
+        if cmp.partial_eq || cmp.eq {
+            let consistent = syntax
+                .fields
+                .iter()
+                .all(|f| f.config.cmp.eq == (f.ident == *field_ident))
+                && syntax
+                    .config
+                    .computed
+                    .iter()
+                    .all(|c| c.cmp.is_none_or(|cmp| !cmp.eq));
+            if !consistent {
+                err.update_or_combine(syn::Error::new(
+                    field_ident.span(),
+                    "`borrow` requires the structure to only compare this field for equality, \
+                    mark other fields with `cmp(eq = false)`",
+                ));
+            }
+        }
+        // `Hash for Self` must hash like `Hash for T`
+        if cmp.hash && field.config.hash.is_some() {
+            err.update_or_combine(syn::Error::new(
+                field.option_span("hash"),
+                "`borrow` requires the structure to hash this field as is, remove `hash = \"...\"`",
+            ));
+        }
+        if cmp.ord || cmp.partial_ord {
+            let computed_ord = syntax.config.computed.iter().any(|c| {
+                c.cmp
+                    .is_some_and(|cmp| cmp.ord.is_some() || cmp.partial_ord.is_some())
+            });
+            let consistent = !computed_ord && syntax.fields.iter().all(|f| {
+                let is_self = f.ident == *field_ident;
+                (!cmp.ord || f.config.cmp.ord.is_some() == is_self)
+                    && (!cmp.partial_ord
+                        || cmp.ord
+                        || f.config.cmp.partial_ord.is_some() == is_self)
+            });
+            if !consistent {
+                err.update_or_combine(syn::Error::new(
+                    field_ident.span(),
+                    "`borrow` requires the structure to only order by this field",
+                ));
+            }
+        }
+
+        ts.extend(quote! {
+            impl #impl_g ::std::borrow::Borrow<#ty> for #ident #type_g #where_clause {
+                fn borrow(&self) -> &#ty {
+                    &self.#field_ident
+                }
+            }
+        });
+        if field.config.borrow == RefType::Full {
+            ts.extend(quote! {
+                impl #impl_g ::std::borrow::BorrowMut<#ty> for #ident #type_g #where_clause {
+                    fn borrow_mut(&mut self) -> &mut #ty {
+                        &mut self.#field_ident
+                    }
+                }
+            });
+        }
+    }
+
+    err.ok_or(()).swap()?;
+
+    Ok(ts)
+}
@@ -0,0 +1,293 @@
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::meta::ParseNestedMeta;
+use syn::{parse_quote, Expr, Generics, LitStr, WherePredicate};
+
+use crate::generate::RichStructContent;
+use crate::utils::collect_meta::{is_flag, is_value, parse_expr, parse_nested_options, str_value};
+use crate::utils::hygiene::{local, parse_template};
+use crate::utils::synerr::{ResultExt, SynErrorExt};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StructArbitraryConfig {
+    pub proptest: bool,
+    pub quickcheck: bool,
+}
+
+impl StructArbitraryConfig {
+    /// Every backend whose feature is enabled.
+    pub fn enabled_features() -> Self {
+        Self {
+            proptest: cfg!(feature = "proptest"),
+            quickcheck: cfg!(feature = "quickcheck"),
+        }
+    }
+
+    pub fn from_meta(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut config: Self = Default::default();
+
+        parse_nested_options(meta, |meta| {
+            if meta.path.is_ident("proptest") && is_flag(&meta) {
+                config.proptest = true;
+            } else if meta.path.is_ident("quickcheck") && is_flag(&meta) {
+                config.quickcheck = true;
+            } else {
+                return Err(meta.error("invalid `arbitrary` argument, expect `proptest` or `quickcheck`"));
+            }
+            Ok(())
+        })?;
+
+        Ok(config)
+    }
+
+    fn check_features(&self, span: Span) -> syn::Result<()> {
+        let mut err: Option<syn::Error> = None;
+        if !self.proptest && !self.quickcheck {
+            err.update_or_combine(syn::Error::new(
+                span,
+                "`arbitrary` requires the `proptest` or `quickcheck` feature of `datastruct`",
+            ));
+        }
+        if self.proptest && !cfg!(feature = "proptest") {
+            err.update_or_combine(syn::Error::new(
+                span,
+                "`arbitrary(proptest)` requires the `proptest` feature of `datastruct`",
+            ));
+        }
+        if self.quickcheck && !cfg!(feature = "quickcheck") {
+            err.update_or_combine(syn::Error::new(
+                span,
+                "`arbitrary(quickcheck)` requires the `quickcheck` feature of `datastruct`",
+            ));
+        }
+        err.ok_or(()).swap()
+    }
+}
+
+#[derive(Default)]
+pub struct FieldArbitraryConfig {
+    /// Use the default value of the field instead.
+    pub default: bool,
+    /// A range used as the `proptest` strategy.
+    pub range: Option<Expr>,
+    /// A custom `proptest` strategy.
+    pub strategy: Option<Expr>,
+    /// A custom `quickcheck` generator, `$g` refers to the `&mut Gen`.
+    pub gen: Option<LitStr>,
+}
+
+impl FieldArbitraryConfig {
+    pub fn from_meta(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut config: Self = Default::default();
+
+        parse_nested_options(meta, |meta| {
+            let invalid = || meta.error("invalid `arbitrary` argument, see the documentation for more information");
+            if meta.path.is_ident("default") && is_flag(&meta) {
+                config.default = true;
+            } else if meta.path.is_ident("range") && is_value(&meta) {
+                config.range = Some(parse_expr(&meta)?);
+            } else if meta.path.is_ident("strategy") && is_value(&meta) {
+                config.strategy = Some(parse_expr(&meta)?);
+            } else if meta.path.is_ident("gen") && is_value(&meta) {
+                config.gen = Some(str_value(&meta).map_err(|_| invalid())?);
+            } else {
+                return Err(invalid());
+            }
+            Ok(())
+        })?;
+
+        if config.range.is_some() && config.strategy.is_some() {
+            return Err(meta.error("`range` and `strategy` cannot be used together"));
+        }
+
+        Ok(config)
+    }
+}
+
+fn bounded_generics(syntax: &RichStructContent, bound: TokenStream2) -> Generics {
+    let mut generics = syntax.generics.clone();
+    let predicates = syntax
+        .generics
+        .type_params()
+        .map(|t| {
+            let ident = &t.ident;
+            let predicate: WherePredicate = parse_quote! { #ident: #bound };
+            predicate
+        })
+        .collect::<Vec<_>>();
+    if !predicates.is_empty() {
+        generics.make_where_clause().predicates.extend(predicates);
+    }
+    generics
+}
+
+// Fields are sorted by `seq`, so defaults may refer to the previous fields, and every generated field.
+fn default_stmts(syntax: &RichStructContent) -> syn::Result<Vec<TokenStream2>> {
+    let mut stmts = Vec::new();
+    let mut err: Option<syn::Error> = None;
+
+    for field in syntax.fields.iter().filter(|f| f.config.arbitrary.default) {
+        let ident = &field.ident;
+        let ty = &field.field_type;
+        match &field.config.default_value {
+            Some(default_expr) => stmts.push(quote! { let #ident: #ty = #default_expr; }),
+            None => err.update_or_combine(syn::Error::new(
+                field.option_span("arbitrary"),
+                "`arbitrary(default)` requires a default value",
+            )),
+        }
+    }
+
+    err.ok_or(()).swap()?;
+
+    Ok(stmts)
+}
+
+fn impl_proptest(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+    let ident = &syntax.ident;
+    let generics = bounded_generics(
+        syntax,
+        quote! { ::proptest::arbitrary::Arbitrary + 'static },
+    );
+    let (impl_g, type_g, where_clause) = generics.split_for_impl();
+
+    let mut strategies = Vec::new();
+    let mut err: Option<syn::Error> = None;
+    for field in syntax.fields.iter().filter(|f| !f.config.arbitrary.default) {
+        let config = &field.config.arbitrary;
+        let ty = &field.field_type;
+        match (&config.range, &config.strategy, &config.gen) {
+            (Some(Expr::Range(range)), _, _) => {
+                // the bounds are annotated, or unsuffixed integers would be inferred as `i32`
+                let bound = |b: &Option<Box<Expr>>| {
+                    b.as_ref().map(|b| quote! { { let bound: #ty = #b; bound } })
+                };
+                let (from, limits, to) = (bound(&range.start), &range.limits, bound(&range.end));
+                strategies.push((field, quote! { #from #limits #to }))
+            }
+            (Some(expr), _, _) => err.update_or_combine(syn::Error::new(
+                expr.span(),
+                "`range` expects a range expression, e.g. `0..100`",
+            )),
+            (_, Some(expr), _) => strategies.push((field, quote! { #expr })),
+            (None, None, Some(_)) => err.update_or_combine(syn::Error::new(
+                field.option_span("arbitrary"),
+                "`gen` only applies to `quickcheck`, use `range` or `strategy` for `proptest`",
+            )),
+            (None, None, None) => strategies.push((
+                field,
+                quote! { ::proptest::arbitrary::any::<#ty>() },
+            )),
+        }
+    }
+    err.ok_or(()).swap()?;
+
+    // nested pairs, as tuples of strategies have a limited length
+    let (strategy, pattern, types) = strategies.iter().rev().fold(
+        (
+            quote! { ::proptest::strategy::Just(()) },
+            quote! { () },
+            quote! { () },
+        ),
+        |(strategy, pattern, types), (field, current)| {
+            let ident = &field.ident;
+            let ty = &field.field_type;
+            (
+                quote! { (#current, #strategy) },
+                quote! { (#ident, #pattern) },
+                quote! { (#ty, #types) },
+            )
+        },
+    );
+    let defaults = default_stmts(syntax)?;
+    let construct = syntax.construct(syntax.fields.iter().map(|f| {
+        let ident = &f.ident;
+        quote! { #ident }
+    }));
+
+    Ok(quote! {
+        impl #impl_g ::proptest::arbitrary::Arbitrary for #ident #type_g #where_clause {
+            type Parameters = ();
+            type Strategy = ::proptest::strategy::BoxedStrategy<Self>;
+
+            fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+                ::proptest::strategy::Strategy::boxed(::proptest::strategy::Strategy::prop_map(
+                    #strategy,
+                    |#pattern: #types| {
+                        #(#defaults)*
+                        #construct
+                    },
+                ))
+            }
+        }
+    })
+}
+
+fn impl_quickcheck(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+    let ident = &syntax.ident;
+    let generics = bounded_generics(syntax, quote! { ::quickcheck::Arbitrary });
+    let (impl_g, type_g, where_clause) = generics.split_for_impl();
+
+    let gen = local("__ds_gen");
+    let mut stmts = Vec::new();
+    let mut err: Option<syn::Error> = None;
+    for field in syntax.fields.iter().filter(|f| !f.config.arbitrary.default) {
+        let config = &field.config.arbitrary;
+        let field_ident = &field.ident;
+        let ty = &field.field_type;
+        match (&config.gen, config.range.is_some() || config.strategy.is_some()) {
+            (Some(s), _) => match parse_template(s, &[("g", &gen)]) {
+                Ok(expr) => stmts.push(quote! { let #field_ident: #ty = #expr; }),
+                Err(e) => err.update_or_combine(e),
+            },
+            (None, true) => err.update_or_combine(syn::Error::new(
+                field.option_span("arbitrary"),
+                "`range` and `strategy` only apply to `proptest`, use `gen` for `quickcheck`",
+            )),
+            (None, false) => stmts.push(quote! {
+                let #field_ident: #ty = <#ty as ::quickcheck::Arbitrary>::arbitrary(#gen);
+            }),
+        }
+    }
+    err.ok_or(()).swap()?;
+
+    let defaults = default_stmts(syntax)?;
+    let construct = syntax.construct(syntax.fields.iter().map(|f| {
+        let ident = &f.ident;
+        quote! { #ident }
+    }));
+
+    Ok(quote! {
+        impl #impl_g ::quickcheck::Arbitrary for #ident #type_g #where_clause {
+            fn arbitrary(#gen: &mut ::quickcheck::Gen) -> Self {
+                #(#stmts)*
+                #(#defaults)*
+                #construct
+            }
+        }
+    })
+}
+
+pub fn impl_arbitrary(syntax: &RichStructContent, config: &StructArbitraryConfig) -> syn::Result<TokenStream2> {
+    config.check_features(syntax.option_span("arbitrary"))?;
+
+    let mut ts = TokenStream2::new();
+    let mut err: Option<syn::Error> = None;
+    if config.proptest {
+        match impl_proptest(syntax) {
+            Ok(v) => ts.extend(v),
+            Err(e) => err.update_or_combine(e),
+        }
+    }
+    if config.quickcheck {
+        match impl_quickcheck(syntax) {
+            Ok(v) => ts.extend(v),
+            Err(e) => err.update_or_combine(e),
+        }
+    }
+
+    err.ok_or(()).swap()?;
+
+    Ok(ts)
+}
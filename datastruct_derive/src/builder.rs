@@ -0,0 +1,456 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{Attribute, GenericParam, Ident};
+
+use crate::config::builder_config::StructBuilderConfig;
+use crate::config::field_config::SetterType;
+use crate::generate::{RichStructContent, StructFieldContent};
+use crate::utils::generics::generic_args;
+use crate::utils::hygiene::local;
+
+impl StructBuilderConfig {
+    pub fn builder_ident(syntax: &RichStructContent) -> Ident {
+        format_ident!("{}Builder", syntax.ident)
+    }
+
+    pub fn error_ident(syntax: &RichStructContent) -> Ident {
+        format_ident!("{}BuilderError", syntax.ident)
+    }
+
+    fn missing_variant(field: &StructFieldContent) -> Ident {
+        format_ident!("Missing{}", camel_case(&field.ident.unraw().to_string()))
+    }
+
+    // With `mirror_setters`, a field set by `with_xxx` or `set_xxx` on the structure is set by the same name on the builder,
+    // with the same forwarded attributes, and a field with `set = "no"` can't be set unless it's required.
+    fn setter(syntax: &RichStructContent, field: &StructFieldContent) -> Option<(Ident, Vec<Attribute>)> {
+        if !syntax.config.builder.mirror_setters {
+            return Some((field.ident.clone(), Vec::new()));
+        }
+        let attrs = &field.config.attrs;
+        match field.config.auto_set {
+            SetterType::Full | SetterType::With => Some((field.accessors.with.clone(), attrs.with.clone())),
+            SetterType::Set => Some((field.accessors.set.clone(), attrs.set.clone())),
+            SetterType::No if field.config.default_value.is_none() => Some((field.accessors.with.clone(), Vec::new())),
+            SetterType::No => None,
+        }
+    }
+
+    fn impl_error(syntax: &RichStructContent) -> TokenStream2 {
+        let error_ident = Self::error_ident(syntax);
+        let vis = &syntax.vis;
+        let required = syntax
+            .fields
+            .iter()
+            .filter(|f| f.config.default_value.is_none())
+            .collect::<Vec<_>>();
+        let variants = required.iter().map(|f| {
+            let variant = Self::missing_variant(f);
+            let doc = format!("Field `{}` is not set.", f.ident.unraw());
+            quote! {
+                #[doc = #doc]
+                #variant
+            }
+        });
+        let messages = required.iter().map(|f| {
+            let variant = Self::missing_variant(f);
+            let msg = format!("field `{}` of `{}` is not set", f.ident.unraw(), syntax.ident);
+            quote! { Self::#variant => f.write_str(#msg) }
+        });
+        let doc = format!("Error of [`{}Builder::build`].", syntax.ident);
+        // the invariant error is not necessarily `Copy` nor comparable
+        let (derive, invariant, invariant_msg) = match &syntax.config.invariant {
+            Some(config) => {
+                let error_ty = config.error_type();
+                let error = local("error");
+                let msg = format!("invariant of `{}` is violated: {{}}", syntax.ident);
+                (
+                    quote! { #[derive(Debug)] },
+                    quote! {
+                        /// The invariant of the structure is violated.
+                        Invariant(#error_ty),
+                    },
+                    quote! { Self::Invariant(ref #error) => ::core::write!(f, #msg, #error), },
+                )
+            }
+            None => (
+                quote! { #[derive(Debug, Clone, Copy, PartialEq, Eq)] },
+                Default::default(),
+                Default::default(),
+            ),
+        };
+
+        quote! {
+            #[doc = #doc]
+            #derive
+            #vis enum #error_ident {
+                #(#variants,)*
+                #invariant
+            }
+
+            impl ::std::fmt::Display for #error_ident {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    match *self {
+                        #(#messages,)*
+                        #invariant_msg
+                    }
+                }
+            }
+
+            impl ::std::error::Error for #error_ident {}
+        }
+    }
+
+    pub fn impl_builder(syntax: &RichStructContent) -> TokenStream2 {
+        if syntax.config.builder.typestate {
+            return Self::impl_typestate_builder(syntax);
+        }
+
+        let ident = &syntax.ident;
+        let builder_ident = Self::builder_ident(syntax);
+        let error_ident = Self::error_ident(syntax);
+        let try_build = syntax.config.builder.try_build;
+        let vis = &syntax.vis;
+        let generics = &syntax.generics;
+        let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+
+        let fields = syntax.fields.iter().map(|field| {
+            let ident = &field.ident;
+            let ty = &field.field_type;
+            quote! { #ident: ::core::option::Option<#ty> }
+        });
+        let idents = syntax.fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+        let construct = syntax.construct(idents.iter().map(|i| quote! { #i }));
+        let value = local("value");
+
+        let setters = syntax.fields.iter().filter_map(|field| {
+            let ident = &field.ident;
+            let ty = &field.field_type;
+            let (setter, attrs) = Self::setter(syntax, field)?;
+            Some(quote! {
+                #(#attrs)*
+                pub fn #setter(mut self, #ident: #ty) -> Self {
+                    self.#ident = ::core::option::Option::Some(#ident);
+                    self
+                }
+            })
+        });
+
+        // fields are sorted by `seq`, so defaults may refer to the previous fields
+        let stmt = syntax.fields.iter().map(|field| {
+            let ident = &field.ident;
+            let ty = &field.field_type;
+            match &field.config.default_value {
+                Some(_) => Self::default_stmt(field),
+                None if try_build => {
+                    let variant = Self::missing_variant(field);
+                    quote! {
+                        let #ident: #ty = match self.#ident {
+                            ::core::option::Option::Some(#value) => #value,
+                            ::core::option::Option::None => {
+                                return ::core::result::Result::Err(#error_ident::#variant)
+                            }
+                        };
+                    }
+                }
+                None => {
+                    let msg = format!("field `{}` of `{}` is not set", ident.unraw(), syntax.ident);
+                    quote! {
+                        let #ident: #ty = match self.#ident {
+                            ::core::option::Option::Some(#value) => #value,
+                            ::core::option::Option::None => ::core::panic!(#msg),
+                        };
+                    }
+                }
+            }
+        });
+
+        let check_invariant = Self::check_invariant(syntax, try_build);
+        let build = if try_build {
+            quote! {
+                /// Builds the structure, unset fields are initialized with their default values.
+                pub fn build(self) -> ::core::result::Result<#ident #type_g, #error_ident> {
+                    #(#stmt)*
+
+                    let #value = #construct;
+                    #check_invariant
+                    ::core::result::Result::Ok(#value)
+                }
+            }
+        } else {
+            quote! {
+                /// Builds the structure, unset fields are initialized with their default values.
+                ///
+                /// # Panics
+                ///
+                /// Panics if a field without default value is not set.
+                pub fn build(self) -> #ident #type_g {
+                    #(#stmt)*
+
+                    let #value = #construct;
+                    #check_invariant
+                    #value
+                }
+            }
+        };
+        let error = if try_build {
+            Self::impl_error(syntax)
+        } else {
+            Default::default()
+        };
+
+        let doc = format!("Builder of [`{}`].", ident);
+        let entry = Self::impl_entry(syntax, &quote! { #builder_ident #type_g });
+
+        quote! {
+            #error
+
+            #[doc = #doc]
+            #vis struct #builder_ident #generics #where_clause {
+                #(#fields),*
+            }
+
+            impl #impl_g ::std::default::Default for #builder_ident #type_g #where_clause {
+                fn default() -> Self {
+                    Self {
+                        #(#idents: ::core::option::Option::None),*
+                    }
+                }
+            }
+
+            impl #impl_g #builder_ident #type_g #where_clause {
+                #(#setters)*
+
+                #build
+            }
+
+            #entry
+        }
+    }
+
+    // `builder()` starts from `data_default` with `from_default` when `DataStruct` is derived,
+    // so that every field is initialized like `data_default`, even if the defaults refer to the previous fields.
+    fn impl_entry(syntax: &RichStructContent, builder: &TokenStream2) -> TokenStream2 {
+        let ident = &syntax.ident;
+        let seeded = syntax.config.generate_default
+            && syntax.fields.iter().all(|f| f.config.default_value.is_some());
+        if !seeded {
+            let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+            return quote! {
+                impl #impl_g #ident #type_g #where_clause {
+                    pub fn builder() -> #builder {
+                        ::std::default::Default::default()
+                    }
+                }
+            };
+        }
+
+        let krate = &syntax.config.crate_path;
+        let generics = syntax.generics_for(&syntax.config.where_bounds.default);
+        let (impl_g, type_g, where_clause) = generics.split_for_impl();
+        let value = local("value");
+        let idents = syntax.fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+
+        quote! {
+            impl #impl_g #builder #where_clause {
+                /// Creates a builder with every field set to its value in `data_default`,
+                /// so that only the deviations from the defaults need to be set.
+                pub fn from_default() -> Self {
+                    let #value = <#ident #type_g as #krate::DataStruct>::data_default();
+                    Self {
+                        #(#idents: ::core::option::Option::Some(#value.#idents)),*
+                    }
+                }
+            }
+
+            impl #impl_g #ident #type_g #where_clause {
+                pub fn builder() -> #builder {
+                    <#builder>::from_default()
+                }
+            }
+        }
+    }
+
+    // Checks the built `value`, returning an error or panicking.
+    fn check_invariant(syntax: &RichStructContent, try_build: bool) -> TokenStream2 {
+        if syntax.config.invariant.is_none() {
+            return Default::default();
+        }
+        let (value, error) = (local("value"), local("error"));
+        if try_build {
+            let error_ident = Self::error_ident(syntax);
+            quote! {
+                if let ::core::result::Result::Err(#error) = #value.check_invariant() {
+                    return ::core::result::Result::Err(#error_ident::Invariant(#error));
+                }
+            }
+        } else {
+            let msg = format!("invariant of `{}` is violated: {{}}", syntax.ident);
+            quote! {
+                if let ::core::result::Result::Err(#error) = #value.check_invariant() {
+                    ::core::panic!(#msg, #error);
+                }
+            }
+        }
+    }
+
+    fn default_stmt(field: &StructFieldContent) -> TokenStream2 {
+        let ident = &field.ident;
+        let ty = &field.field_type;
+        // SAFETY: Caller-guaranteed
+        let default_expr = field.config.default_value.as_ref().unwrap();
+        let value = local("value");
+        quote_spanned! {
+            default_expr.span() => let #ident: #ty = match self.#ident {
+                ::core::option::Option::Some(#value) => #value,
+                ::core::option::Option::None => #default_expr,
+            };
+        }
+    }
+
+    // Required fields are stored as type parameters: `()` if unset, and the field type once set.
+    fn impl_typestate_builder(syntax: &RichStructContent) -> TokenStream2 {
+        let check_invariant = Self::check_invariant(syntax, false);
+        let ident = &syntax.ident;
+        let builder_ident = Self::builder_ident(syntax);
+        let vis = &syntax.vis;
+        let where_clause = &syntax.generics.where_clause;
+        let user_args = generic_args(&syntax.generics);
+
+        let state = syntax
+            .fields
+            .iter()
+            .filter(|f| f.config.default_value.is_none())
+            .map(|f| (&f.ident, format_ident!("__Ds{}", camel_case(&f.ident.unraw().to_string()))))
+            .collect::<Vec<_>>();
+        let state_of = |field: &StructFieldContent| {
+            state.iter().find(|(i, _)| **i == field.ident).map(|(_, p)| p)
+        };
+        let state_params = state.iter().map(|(_, p)| p).collect::<Vec<_>>();
+
+        let mut full_generics = syntax.generics.clone();
+        full_generics
+            .params
+            .extend(state_params.iter().map(|p| -> GenericParam { syn::parse_quote! { #p } }));
+        let (full_impl_g, _, _) = full_generics.split_for_impl();
+        let (impl_g, type_g, _) = syntax.generics.split_for_impl();
+
+        let fields = syntax.fields.iter().map(|field| {
+            let ident = &field.ident;
+            let ty = &field.field_type;
+            match state_of(field) {
+                Some(p) => quote! { #ident: #p },
+                None => quote! { #ident: ::core::option::Option<#ty> },
+            }
+        });
+        let idents = syntax.fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+        let construct = syntax.construct(idents.iter().map(|i| quote! { #i }));
+        let value = local("value");
+        let init = syntax.fields.iter().map(|field| {
+            let ident = &field.ident;
+            match state_of(field) {
+                Some(_) => quote! { #ident: () },
+                None => quote! { #ident: ::core::option::Option::None },
+            }
+        });
+        let unset = state.iter().map(|_| quote! { () }).collect::<Vec<_>>();
+        let complete = syntax
+            .fields
+            .iter()
+            .filter(|f| state_of(f).is_some())
+            .map(|f| &f.field_type)
+            .collect::<Vec<_>>();
+
+        let setters = syntax.fields.iter().filter_map(|field| {
+            let field_ident = &field.ident;
+            let ty = &field.field_type;
+            let (setter, attrs) = Self::setter(syntax, field)?;
+            Some(match state_of(field) {
+                None => quote! {
+                    #(#attrs)*
+                    pub fn #setter(mut self, #field_ident: #ty) -> Self {
+                        self.#field_ident = ::core::option::Option::Some(#field_ident);
+                        self
+                    }
+                },
+                Some(param) => {
+                    let next = state_params
+                        .iter()
+                        .map(|p| if *p == param { quote! { #ty } } else { quote! { #p } });
+                    let moved = idents
+                        .iter()
+                        .filter(|i| **i != field_ident)
+                        .map(|i| quote! { #i: self.#i });
+                    quote! {
+                        #(#attrs)*
+                        pub fn #setter(self, #field_ident: #ty) -> #builder_ident<#(#user_args,)* #(#next),*> {
+                            #builder_ident {
+                                #field_ident,
+                                #(#moved),*
+                            }
+                        }
+                    }
+                }
+            })
+        });
+
+        let stmt = syntax.fields.iter().map(|field| {
+            let ident = &field.ident;
+            let ty = &field.field_type;
+            match state_of(field) {
+                Some(_) => quote! { let #ident: #ty = self.#ident; },
+                None => Self::default_stmt(field),
+            }
+        });
+
+        let doc = format!("Builder of [`{}`].", ident);
+        let entry = Self::impl_entry(syntax, &quote! { #builder_ident<#(#user_args,)* #(#unset),*> });
+
+        quote! {
+            #[doc = #doc]
+            #vis struct #builder_ident #full_impl_g #where_clause {
+                #(#fields),*
+            }
+
+            impl #impl_g ::std::default::Default for #builder_ident<#(#user_args,)* #(#unset),*> #where_clause {
+                fn default() -> Self {
+                    Self {
+                        #(#init),*
+                    }
+                }
+            }
+
+            impl #full_impl_g #builder_ident<#(#user_args,)* #(#state_params),*> #where_clause {
+                #(#setters)*
+            }
+
+            impl #impl_g #builder_ident<#(#user_args,)* #(#complete),*> #where_clause {
+                /// Builds the structure, unset fields are initialized with their default values.
+                pub fn build(self) -> #ident #type_g {
+                    #(#stmt)*
+
+                    let #value = #construct;
+                    #check_invariant
+                    #value
+                }
+            }
+
+            #entry
+        }
+    }
+}
+
+fn camel_case(s: &str) -> String {
+    s.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|c| c.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
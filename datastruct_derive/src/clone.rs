@@ -0,0 +1,130 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{Ident, Lit, LitStr};
+
+use crate::generate::RichStructContent;
+use crate::utils::hygiene::parse_template;
+use crate::utils::synerr::{ResultExt, SynErrorExt};
+
+#[derive(Default)]
+pub enum FieldCloneConfig {
+    /// `Clone::clone` of the field.
+    #[default]
+    Inherit,
+    /// A custom expression, `$self` refers to the structure.
+    Manual(LitStr),
+    /// The field cannot be cloned, and is reset to its default value by `clone_with_default`.
+    No,
+}
+
+impl FieldCloneConfig {
+    pub fn from_lit(lit: &Lit) -> syn::Result<Self> {
+        match lit {
+            Lit::Str(lit_str) => match lit_str.value().as_str() {
+                "inherit" | "default" => Ok(Self::Inherit),
+                "no" => Ok(Self::No),
+                _ => Ok(Self::Manual(lit_str.clone())),
+            },
+            Lit::Bool(lit_bool) => {
+                if lit_bool.value {
+                    Ok(Self::Inherit)
+                } else {
+                    Ok(Self::No)
+                }
+            }
+            _ => Err(syn::Error::new(lit.span(), "invalid `clone` value")),
+        }
+    }
+
+    /// `None` if the field cannot be cloned.
+    pub fn impl_clone(&self, ident: &Ident) -> syn::Result<Option<TokenStream2>> {
+        match self {
+            Self::Inherit => Ok(Some(quote! { ::core::clone::Clone::clone(&self.#ident) })),
+            Self::Manual(s) => {
+                let token = parse_template(s, &[("self", &quote! { self })])?;
+                Ok(Some(quote! { #token }))
+            }
+            Self::No => Ok(None),
+        }
+    }
+}
+
+pub fn impl_clone(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+    let mut fields = Vec::with_capacity(syntax.fields.len());
+    let mut err: Option<syn::Error> = None;
+
+    for field in &syntax.fields {
+        match field.config.clone.impl_clone(&field.ident) {
+            Ok(Some(expr)) => {
+                let ident = &field.ident;
+                fields.push(quote! { #ident: #expr })
+            }
+            Ok(None) => err.update_or_combine(syn::Error::new(
+                field.option_span("clone"),
+                "`Clone` cannot skip the field, use `clone_with_default` to reset it instead",
+            )),
+            Err(e) => err.update_or_combine(e),
+        }
+    }
+
+    err.ok_or(()).swap()?;
+
+    let construct = syntax.construct(fields);
+    let ident = &syntax.ident;
+    let generics = syntax.generics_for(&syntax.config.where_bounds.clone);
+    let (impl_g, type_g, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_g ::core::clone::Clone for #ident #type_g #where_clause {
+            fn clone(&self) -> Self {
+                #construct
+            }
+        }
+    })
+}
+
+// Fields are sorted by `seq`, so defaults may refer to the previous fields, either cloned or reset.
+pub fn impl_clone_with_default(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+    let mut stmts = Vec::with_capacity(syntax.fields.len());
+    let mut err: Option<syn::Error> = None;
+
+    for field in &syntax.fields {
+        let ident = &field.ident;
+        let ty = &field.field_type;
+        match field.config.clone.impl_clone(ident) {
+            Ok(Some(expr)) => stmts.push(quote! { let #ident: #ty = #expr; }),
+            Ok(None) => match &field.config.default_value {
+                Some(default_expr) => stmts.push(quote_spanned! {
+                    default_expr.span() => let #ident: #ty = #default_expr;
+                }),
+                None => err.update_or_combine(syn::Error::new(
+                    field.option_span("clone"),
+                    "`clone = false` requires a default value to reset the field",
+                )),
+            },
+            Err(e) => err.update_or_combine(e),
+        }
+    }
+
+    err.ok_or(()).swap()?;
+
+    let ident = &syntax.ident;
+    let vis = &syntax.vis;
+    let idents = syntax.fields.iter().map(|f| &f.ident);
+    let generics = syntax.generics_for(&syntax.config.where_bounds.clone);
+    let (impl_g, type_g, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_g #ident #type_g #where_clause {
+            /// Clones the structure, resetting the fields with `clone = false` to their default values.
+            #vis fn clone_with_default(&self) -> Self {
+                #(#stmts)*
+
+                Self {
+                    #(#idents),*
+                }
+            }
+        }
+    })
+}
@@ -1,18 +1,14 @@
 use itertools::Itertools;
-use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::{quote, quote_spanned};
-use syn::{Lit, MetaList};
+use syn::ext::IdentExt;
+use syn::{parse_quote, Ident, WherePredicate};
 
+use crate::config::cmp_config::{FieldCmpConfig, StructCmpConfig};
 use crate::generate::RichStructContent;
-use crate::utils::collect_meta::collect_meta_map;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
-pub struct StructCmpConfig {
-    pub partial_eq: bool,
-    pub eq: bool,
-    pub partial_ord: bool,
-    pub ord: bool,
-}
+use crate::utils::generics::type_uses_params;
+use crate::utils::hygiene::{local, parse_template};
+use crate::utils::synerr::{ResultExt, SynErrorExt};
 
 impl StructCmpConfig {
     pub fn impl_cmp(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
@@ -26,38 +22,271 @@ impl StructCmpConfig {
             ts.extend(Self::impl_eq(syntax))
         }
 
+        if syntax.config.cmp.hash {
+            ts.extend(Self::impl_hash(syntax)?)
+        }
+
         ts.extend(Self::impl_rich_ord(syntax)?);
 
+        if syntax.config.cmp.comparator {
+            ts.extend(Self::impl_comparator(syntax)?)
+        }
+
         Ok(ts)
     }
 
-    fn impl_partial_eq(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
-        let ident = &syntax.ident;
-        let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+    // Fields are accessed as `self.field`, and computed fields as `self.field()`.
+    fn cmp_keys(syntax: &RichStructContent) -> Vec<(TokenStream2, Span, FieldCmpConfig)> {
+        let fields = syntax.fields.iter().map(|field| {
+            let ident = &field.ident;
+            (quote! { #ident }, ident.span(), field.config.cmp)
+        });
+        let computed = syntax.config.computed.iter().filter_map(|computed| {
+            let name = &computed.name;
+            computed
+                .cmp
+                .map(|cmp| (quote! { #name() }, name.span(), cmp))
+        });
+        fields.chain(computed).collect()
+    }
 
-        let equations = syntax
-            .fields
+    // References to the hashed values of the keys compared by `eq`, in the order of `cmp_keys`,
+    // with the fields' `hash` expressions in place of the fields.
+    fn hash_keys(syntax: &RichStructContent) -> syn::Result<Vec<(TokenStream2, Span)>> {
+        let mut keys = Vec::new();
+        let mut err: Option<syn::Error> = None;
+        for field in syntax.fields.iter().filter(|f| f.config.cmp.eq) {
+            let ident = &field.ident;
+            match &field.config.hash {
+                Some(s) => match parse_template(s, &[("self", &quote! { self })]) {
+                    Ok(expr) => keys.push((quote! { &(#expr) }, ident.span())),
+                    Err(e) => err.update_or_combine(e),
+                },
+                None => keys.push((quote! { &self.#ident }, ident.span())),
+            }
+        }
+        err.ok_or(()).swap()?;
+        let computed = syntax
+            .config
+            .computed
             .iter()
-            .filter(|s| s.config.cmp.eq)
-            .map(|field| {
-                let ident = &field.ident;
+            .filter(|computed| computed.cmp.is_some_and(|cmp| cmp.eq))
+            .map(|computed| {
+                let name = &computed.name;
+                (quote! { &self.#name() }, name.span())
+            });
+        keys.extend(computed);
+        Ok(keys)
+    }
+
+    fn impl_partial_eq(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+        let ident = &syntax.ident;
+        let generics = syntax.generics_for(&syntax.config.where_bounds.cmp);
+        let (impl_g, type_g, where_clause) = generics.split_for_impl();
+        let rhs = local("rhs");
+
+        let equations = Self::cmp_keys(syntax)
+            .into_iter()
+            .filter(|(_, _, cmp)| cmp.eq)
+            .map(|(key, span, _)| {
                 quote_spanned! {
-                    ident.span() => (self.#ident == rhs.#ident)
+                    span => self.#key == #rhs.#key
                 }
             });
 
         Ok(quote! {
             impl #impl_g ::std::cmp::PartialEq for #ident #type_g #where_clause {
-                fn eq(&self, rhs: &Self) -> bool {
+                fn eq(&self, #rhs: &Self) -> bool {
                     #(#equations)&&*
                 }
             }
         })
     }
 
+    // Hashes the same keys as `PartialEq`, so `Hash` stays consistent with `Eq`.
+    fn impl_hash(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+        let ident = &syntax.ident;
+        let generics = syntax.generics_for(&syntax.config.where_bounds.cmp);
+        let (impl_g, type_g, where_clause) = generics.split_for_impl();
+
+        let state = local("state");
+        let keys = Self::hash_keys(syntax)?
+            .into_iter()
+            .map(|(key, span)| {
+                quote_spanned! {
+                    span => ::core::hash::Hash::hash(#key, #state);
+                }
+            });
+
+        Ok(quote! {
+            impl #impl_g ::core::hash::Hash for #ident #type_g #where_clause {
+                fn hash<__DsHasher: ::core::hash::Hasher>(&self, #state: &mut __DsHasher) {
+                    #(#keys)*
+                }
+            }
+        })
+    }
+
+    // Hashes the same keys as `PartialEq`, so equal structures have equal keys.
+    pub fn impl_cache_key(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+        let ident = &syntax.ident;
+        let generics = syntax.generics_for(&syntax.config.where_bounds.cmp);
+        let (impl_g, type_g, where_clause) = generics.split_for_impl();
+
+        let hasher = local("hasher");
+        let keys = Self::hash_keys(syntax)?
+            .into_iter()
+            .map(|(key, span)| {
+                quote_spanned! {
+                    span => ::core::hash::Hash::hash(#key, &mut #hasher);
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if keys.is_empty() {
+            return Err(syn::Error::new(
+                syntax.option_span("cache_key"),
+                "at least one field should be compared by `eq` if you want to derive `cache_key`",
+            ));
+        }
+
+        let krate = &syntax.config.crate_path;
+
+        Ok(quote! {
+            impl #impl_g #ident #type_g #where_clause {
+                /// Returns a stable hash of the fields compared by `eq`.
+                pub fn cache_key(&self) -> u64 {
+                    let mut #hasher = #krate::StableHasher::new();
+                    #(#keys)*
+                    ::core::hash::Hasher::finish(&#hasher)
+                }
+            }
+        })
+    }
+
+    // Pushes the fields compared by `eq` that differ from `other` into `changes`, for `DiffDisplay`,
+    // hiding the values of the fields with `debug = false`.
+    fn diff_changes(
+        syntax: &RichStructContent,
+        other: &Ident,
+        changes: &Ident,
+    ) -> (Vec<TokenStream2>, Vec<WherePredicate>) {
+        let mut bounds: Vec<WherePredicate> = Vec::new();
+        let pushes = syntax
+            .fields
+            .iter()
+            .filter(|field| field.config.cmp.eq)
+            .map(|field| {
+                let field_ident = &field.ident;
+                let ty = &field.field_type;
+                let name = field_ident.unraw().to_string();
+                let uses_params = type_uses_params(ty, &syntax.generics);
+                if uses_params {
+                    bounds.push(parse_quote! { #ty: ::core::cmp::PartialEq });
+                }
+                let change = if field.config.no_debug {
+                    quote! { ::core::option::Option::None }
+                } else {
+                    if uses_params {
+                        bounds.push(parse_quote! { #ty: ::core::fmt::Debug });
+                    }
+                    quote! {
+                        ::core::option::Option::Some((
+                            &self.#field_ident as &dyn ::core::fmt::Debug,
+                            &#other.#field_ident as &dyn ::core::fmt::Debug,
+                        ))
+                    }
+                };
+                quote_spanned! { field_ident.span() =>
+                    if self.#field_ident != #other.#field_ident {
+                        #changes.push((#name, #change));
+                    }
+                }
+            })
+            .collect();
+        (pushes, bounds)
+    }
+
+    pub fn impl_diff_display(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+        let krate = &syntax.config.crate_path;
+        let ident = &syntax.ident;
+        let vis = &syntax.vis;
+        let (other, changes) = (local("other"), local("changes"));
+
+        let (pushes, bounds) = Self::diff_changes(syntax, &other, &changes);
+        if pushes.is_empty() {
+            return Err(syn::Error::new(
+                syntax.option_span("diff_display"),
+                "at least one field should be compared by `eq` if you want to derive `diff_display`",
+            ));
+        }
+
+        let mut generics = syntax.generics_for(&syntax.config.where_bounds.cmp);
+        if !bounds.is_empty() {
+            generics.make_where_clause().predicates.extend(bounds);
+        }
+        let (impl_g, type_g, where_clause) = generics.split_for_impl();
+
+        Ok(quote! {
+            impl #impl_g #ident #type_g #where_clause {
+                /// Lists the fields compared by `eq` that differ from `other`, with their old and new values.
+                #vis fn diff_display<'a>(&'a self, #other: &'a Self) -> #krate::DiffDisplay<'a> {
+                    let mut #changes = ::std::vec::Vec::new();
+                    #(#pushes)*
+                    #krate::DiffDisplay::new(#changes)
+                }
+            }
+        })
+    }
+
+    // Reports the differing fields like `diff_display`, instead of the `Debug` of the whole structures.
+    #[cfg(feature = "testing")]
+    pub fn impl_assert_eq_verbose(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+        let krate = &syntax.config.crate_path;
+        let ident = &syntax.ident;
+        let vis = &syntax.vis;
+        let (other, changes) = (local("other"), local("changes"));
+        let name = ident.unraw().to_string();
+
+        let (pushes, bounds) = Self::diff_changes(syntax, &other, &changes);
+        if pushes.is_empty() {
+            return Err(syn::Error::new(
+                syntax.option_span("assert_eq_verbose"),
+                "at least one field should be compared by `eq` if you want to derive `assert_eq_verbose`",
+            ));
+        }
+
+        let mut generics = syntax.generics_for(&syntax.config.where_bounds.cmp);
+        if !bounds.is_empty() {
+            generics.make_where_clause().predicates.extend(bounds);
+        }
+        let (impl_g, type_g, where_clause) = generics.split_for_impl();
+
+        Ok(quote! {
+            impl #impl_g #ident #type_g #where_clause {
+                /// Asserts that the fields compared by `eq` equal those of `other`,
+                /// panicking with the differing fields, as `left → right`.
+                #[track_caller]
+                #vis fn assert_eq_verbose(&self, #other: &Self) {
+                    let mut #changes = ::std::vec::Vec::new();
+                    #(#pushes)*
+                    let #changes = #krate::DiffDisplay::new(#changes);
+                    if !#changes.is_empty() {
+                        ::core::panic!(
+                            "assertion `left == right` failed for `{}`, the differing fields are:\n{:#}",
+                            #name,
+                            #changes,
+                        );
+                    }
+                }
+            }
+        })
+    }
+
     fn impl_eq(syntax: &RichStructContent) -> TokenStream2 {
         let ident = &syntax.ident;
-        let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+        let generics = syntax.generics_for(&syntax.config.where_bounds.cmp);
+        let (impl_g, type_g, where_clause) = generics.split_for_impl();
         quote! { impl #impl_g ::std::cmp::Eq for #ident #type_g #where_clause {} }
     }
 
@@ -73,18 +302,19 @@ impl StructCmpConfig {
 
         if syntax.config.cmp.ord
             && syntax.config.cmp.partial_ord
-            && syntax
-                .fields
+            && Self::cmp_keys(syntax)
                 .iter()
-                .all(|x| x.config.cmp.partial_ord.is_none())
+                .all(|(_, _, cmp)| cmp.partial_ord.is_none())
         {
             let ident = &syntax.ident;
-            let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+            let generics = syntax.generics_for(&syntax.config.where_bounds.cmp);
+            let (impl_g, type_g, where_clause) = generics.split_for_impl();
 
+            let rhs = local("rhs");
             ts.extend(quote! {
                 impl #impl_g ::std::cmp::PartialOrd for #ident #type_g #where_clause {
-                    fn partial_cmp(&self, rhs: &Self) -> ::core::option::Option<::std::cmp::Ordering> {
-                        ::core::option::Option::Some(self.cmp(rhs))
+                    fn partial_cmp(&self, #rhs: &Self) -> ::core::option::Option<::std::cmp::Ordering> {
+                        ::core::option::Option::Some(::std::cmp::Ord::cmp(self, #rhs))
                     }
                 }
             });
@@ -95,64 +325,96 @@ impl StructCmpConfig {
         Ok(ts)
     }
 
-    fn impl_ord(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
-        let mut cmp_seq = syntax
-            .fields
-            .iter()
-            .filter_map(|x| x.config.cmp.ord.map(|d| (x, d)))
+    // Compares `lhs` and `rhs` by the `ord` keys, or `None` if there's no such key.
+    fn ord_chain(syntax: &RichStructContent, lhs: &TokenStream2, rhs: &TokenStream2) -> Option<TokenStream2> {
+        let mut cmp_seq = Self::cmp_keys(syntax)
+            .into_iter()
+            .filter_map(|(key, _, cmp)| cmp.ord.map(|d| (key, d)))
             .sorted_by_key(|(_, x)| *x)
-            .map(|(field, _)| {
-                let ident = &field.ident;
+            .map(|(key, _)| {
                 quote! {
-                    self.#ident.cmp(&other.#ident)
+                    #lhs.#key.cmp(&#rhs.#key)
                 }
             })
             .peekable();
 
-        let base = cmp_seq.next().ok_or_else(|| {
+        let base = cmp_seq.next()?;
+        if cmp_seq.peek().is_none() {
+            Some(base)
+        } else {
+            Some(quote! {
+                #base #(.then_with(|| #cmp_seq))*
+            })
+        }
+    }
+
+    fn impl_ord(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+        let other = local("other");
+        let cmp = Self::ord_chain(syntax, &quote! { self }, &quote! { #other }).ok_or_else(|| {
             syn::Error::new(
-                syntax.ident.span(),
+                syntax.option_span("cmp(ord)"),
                 "at least one field can be `ord`ed if you want to derive `cmp.ord`",
             )
         })?;
 
-        let cmp = if cmp_seq.peek().is_none() {
-            base
-        } else {
-            quote! {
-                #base #(.then_with(|| #cmp_seq))*
+        let generics = syntax.generics_for(&syntax.config.where_bounds.cmp);
+        let (impl_g, type_g, where_clause) = generics.split_for_impl();
+        let ident = &syntax.ident;
+
+        Ok(quote! {
+            impl #impl_g ::std::cmp::Ord for #ident #type_g #where_clause {
+                fn cmp(&self, #other: &Self) -> ::std::cmp::Ordering {
+                    #cmp
+                }
             }
-        };
+        })
+    }
+
+    fn impl_comparator(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+        let (a, b) = (local("a"), local("b"));
+        let cmp = Self::ord_chain(syntax, &quote! { #a }, &quote! { #b }).ok_or_else(|| {
+            syn::Error::new(
+                syntax.option_span("cmp(comparator)"),
+                "at least one field can be `ord`ed if you want to derive `cmp.comparator`",
+            )
+        })?;
 
-        let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+        let generics = syntax.generics_for(&syntax.config.where_bounds.cmp);
+        let (impl_g, type_g, where_clause) = generics.split_for_impl();
         let ident = &syntax.ident;
+        let vis = &syntax.vis;
 
         Ok(quote! {
-            impl #impl_g ::std::cmp::Ord for #ident #type_g #where_clause {
-                fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+            impl #impl_g #ident #type_g #where_clause {
+                /// Compares by the `ord` fields in ascending order, e.g. for `sort_by`.
+                #vis fn cmp_asc(#a: &Self, #b: &Self) -> ::std::cmp::Ordering {
                     #cmp
                 }
+
+                /// Compares by the `ord` fields in descending order, e.g. for `sort_by`.
+                #vis fn cmp_desc(#a: &Self, #b: &Self) -> ::std::cmp::Ordering {
+                    Self::cmp_asc(#b, #a)
+                }
             }
         })
     }
 
     fn impl_partial_ord(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
-        let mut cmp_seq = syntax
-            .fields
-            .iter()
-            .filter_map(|x| x.config.cmp.partial_ord.map(|d| (x, d)))
+        let other = local("other");
+        let mut cmp_seq = Self::cmp_keys(syntax)
+            .into_iter()
+            .filter_map(|(key, _, cmp)| cmp.partial_ord.map(|d| (key, d)))
             .sorted_by_key(|(_, x)| *x)
-            .map(|(field, _)| {
-                let ident = &field.ident;
+            .map(|(key, _)| {
                 quote! {
-                    self.#ident.partial_cmp(&other.#ident)
+                    self.#key.partial_cmp(&#other.#key)
                 }
             })
             .peekable();
 
         let base = cmp_seq.next().ok_or_else(|| {
             syn::Error::new(
-                syntax.ident.span(),
+                syntax.option_span("cmp(partial_ord)"),
                 "at least one field can be `partial_ord`ed if you want to derive `cmp.partial_ord`",
             )
         })?;
@@ -160,125 +422,25 @@ impl StructCmpConfig {
         let par_cmp = if cmp_seq.peek().is_none() {
             base
         } else {
+            let (ord, ord_self) = (local("__gen_dparord"), local("__gen_dparord_self"));
             quote! {
                 #base
-                    #(.and_then(|__gen_dparord|
-                        #cmp_seq.map(|__gen_dparord_self| __gen_dparord.then(__gen_dparord_self)))
+                    #(.and_then(|#ord|
+                        #cmp_seq.map(|#ord_self| #ord.then(#ord_self)))
                     )*
             }
         };
 
-        let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+        let generics = syntax.generics_for(&syntax.config.where_bounds.cmp);
+        let (impl_g, type_g, where_clause) = generics.split_for_impl();
         let ident = &syntax.ident;
 
         Ok(quote! {
             impl #impl_g ::std::cmp::PartialOrd for #ident #type_g #where_clause {
-                fn partial_cmp(&self, other: &Self) -> ::core::option::Option<::std::cmp::Ordering> {
+                fn partial_cmp(&self, #other: &Self) -> ::core::option::Option<::std::cmp::Ordering> {
                     #par_cmp
                 }
             }
         })
     }
 }
-
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub struct FieldCmpConfig {
-    pub eq: bool,
-    pub ord: Option<isize>,
-    pub partial_ord: Option<isize>,
-}
-
-impl Default for FieldCmpConfig {
-    fn default() -> Self {
-        Self {
-            eq: true,
-            ord: None,
-            partial_ord: None,
-        }
-    }
-}
-
-impl FieldCmpConfig {
-    pub fn from_meta(meta_list: &MetaList) -> syn::Result<Self> {
-        let mut config: Self = Default::default();
-
-        collect_meta_map(meta_list, |idx, k, v| {
-            match k.to_string().as_str() {
-                "eq" | "peq" => match v {
-                    Some(Lit::Bool(lit)) => config.eq = lit.value,
-                    Some(Lit::Str(lit)) => match lit.value().parse::<bool>() {
-                        Ok(val) => config.eq = val,
-                        Err(e) => {
-                            return Err(syn::Error::new(
-                                lit.span(),
-                                format!("cannot parse `eq` value: {:?}", e),
-                            ));
-                        }
-                    },
-                    None => config.eq = true,
-                    _ => {
-                        return Err(syn::Error::new(
-                            k.span(),
-                            "invalid `eq` value, see the documentation for more information",
-                        ));
-                    }
-                },
-                "cmp" | "ord" => match v {
-                    Some(Lit::Bool(lit)) => {
-                        if lit.value {
-                            config.ord = Some(idx as isize)
-                        } else {
-                            config.ord = None
-                        }
-                    }
-                    Some(Lit::Str(lit)) => match lit.value().parse::<isize>() {
-                        Ok(val) => config.ord = Some(val),
-                        Err(e) => {
-                            return Err(syn::Error::new(
-                                lit.span(),
-                                format!("cannot parse `cmp` value: {:?}", e),
-                            ));
-                        }
-                    },
-                    Some(Lit::Int(lit)) => config.ord = Some(lit.base10_parse()?),
-                    None => config.ord = Some(idx as isize),
-                    _ => {
-                        return Err(syn::Error::new(
-                            k.span(),
-                            "invalid `cmp` value, see the documentation for more information",
-                        ));
-                    }
-                },
-                "partial_cmp" | "pcmp" | "partial_ord" | "pord" => match v {
-                    Some(Lit::Bool(lit)) => {
-                        if lit.value {
-                            config.partial_ord = Some(idx as isize)
-                        } else {
-                            config.partial_ord = None
-                        }
-                    }
-                    Some(Lit::Str(lit)) => match lit.value().parse::<isize>() {
-                        Ok(val) => config.partial_ord = Some(val),
-                        Err(e) => {
-                            return Err(syn::Error::new(
-                                lit.span(),
-                                format!("cannot parse `partial_cmp` value: {:?}", e),
-                            ));
-                        }
-                    },
-                    Some(Lit::Int(lit)) => config.partial_ord = Some(lit.base10_parse()?),
-                    None => config.partial_ord = Some(idx as isize),
-                    _ => return Err(syn::Error::new(
-                        k.span(),
-                        "invalid `partial_cmp` value, see the documentation for more information",
-                    )),
-                },
-                _ => {}
-            };
-
-            Ok(((), ()))
-        })?;
-
-        Ok(config)
-    }
-}
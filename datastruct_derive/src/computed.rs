@@ -0,0 +1,95 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::{Expr, Ident, Type};
+
+use crate::config::cmp_config::FieldCmpConfig;
+use crate::generate::RichStructContent;
+use crate::utils::collect_meta::{is_flag, is_list, is_value, parse_expr, parse_nested_options, str_value};
+use crate::utils::synerr::{ResultExt, SynErrorExt};
+
+/// A getter derived from the other fields, e.g. `computed(name = "area", ty = "f64", expr = "self.w * self.h")`.
+pub struct ComputedField {
+    pub name: Ident,
+    pub ty: Type,
+    pub expr: Expr,
+    /// Included in the manual `Debug` implementation.
+    pub debug: bool,
+    /// Takes part in the comparisons only if configured.
+    pub cmp: Option<FieldCmpConfig>,
+}
+
+impl ComputedField {
+    pub fn from_meta(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut name = None;
+        let mut ty = None;
+        let mut expr = None;
+        let mut debug = false;
+        let mut cmp = None;
+        let mut err: Option<syn::Error> = None;
+
+        let res = parse_nested_options(meta, |meta| {
+            let invalid = || meta.error("invalid `computed` argument, see the documentation for more information");
+            if meta.path.is_ident("name") && is_value(&meta) {
+                name = Some(str_value(&meta).map_err(|_| invalid())?.parse::<Ident>()?);
+            } else if meta.path.is_ident("ty") && is_value(&meta) {
+                ty = Some(str_value(&meta).map_err(|_| invalid())?.parse::<Type>()?);
+            } else if meta.path.is_ident("expr") && is_value(&meta) {
+                expr = Some(parse_expr(&meta)?);
+            } else if meta.path.is_ident("debug") && is_flag(&meta) {
+                debug = true;
+            } else if meta.path.is_ident("cmp") && is_list(&meta) {
+                if !cfg!(feature = "cmp") {
+                    return Err(meta.error("`cmp` requires the `cmp` feature of `datastruct`"));
+                }
+                cmp = Some(FieldCmpConfig::from_meta(&meta)?);
+            } else {
+                return Err(invalid());
+            }
+            Ok(())
+        });
+        if let Err(e) = res {
+            err.update_or_combine(e);
+        }
+
+        for (value, key) in [(name.is_none(), "name"), (ty.is_none(), "ty"), (expr.is_none(), "expr")] {
+            if value {
+                err.update_or_combine(meta.error(format!("`computed` requires `{}`", key)));
+            }
+        }
+
+        err.ok_or(()).swap()?;
+
+        Ok(Self {
+            // SAFETY: checked above
+            name: name.unwrap(),
+            ty: ty.unwrap(),
+            expr: expr.unwrap(),
+            debug,
+            cmp,
+        })
+    }
+}
+
+pub fn impl_computed(syntax: &RichStructContent) -> TokenStream2 {
+    let ident = &syntax.ident;
+    let vis = &syntax.vis;
+    let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+
+    let getters = syntax.config.computed.iter().map(|computed| {
+        let ComputedField { name, ty, expr, .. } = computed;
+        let doc = format!("Computed `{}`, derived from the other fields.", name);
+        quote! {
+            #[doc = #doc]
+            #vis fn #name(&self) -> #ty {
+                #expr
+            }
+        }
+    });
+
+    quote! {
+        impl #impl_g #ident #type_g #where_clause {
+            #(#getters)*
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::parse::{ParseStream, Parser};
+use syn::spanned::Spanned;
+use syn::{Ident, LitBool, Path, Token};
+
+/// A renamed option, still accepted with a deprecation warning.
+pub struct Alias {
+    /// The old name of the option.
+    pub old: &'static str,
+    /// The name replacing it.
+    pub new: &'static str,
+    /// Whether the flag is negated by the renaming, like `no_debug` becoming `debug = false`.
+    pub negated: bool,
+}
+
+/// Renamed `dstruct` options.
+pub const STRUCT_ALIASES: &[Alias] = &[];
+
+/// Renamed `dfield` options.
+pub const FIELD_ALIASES: &[Alias] = &[Alias {
+    old: "no_debug",
+    new: "debug",
+    negated: true,
+}];
+
+/// Rewrites a renamed option into its replacement, and records a deprecation warning for it.
+///
+/// `value` holds the tokens after the path of the option, like `= true`.
+/// Returns `None` if the option isn't renamed.
+pub fn resolve_alias(
+    path: &Path,
+    value: &TokenStream2,
+    aliases: &[Alias],
+    warnings: &mut Vec<(Span, String)>,
+) -> Option<TokenStream2> {
+    let alias = aliases.iter().find(|a| path.is_ident(a.old))?;
+    let new = Ident::new(alias.new, path.span());
+    let flag = |value: bool, span: Span| {
+        let lit = LitBool::new(value != alias.negated, span);
+        quote! { #new = #lit }
+    };
+    let bool_value = |input: ParseStream| {
+        input.parse::<Token![=]>()?;
+        input.parse::<LitBool>()
+    };
+    let resolved = if !alias.negated {
+        quote! { #new #value }
+    } else if value.is_empty() {
+        flag(true, path.span())
+    } else if let Ok(lit) = bool_value.parse2(value.clone()) {
+        flag(lit.value, lit.span())
+    } else {
+        quote! { #new #value }
+    };
+    warnings.push((
+        path.span(),
+        format!("`{}` is deprecated, use `{}` instead", alias.old, resolved),
+    ));
+    Some(resolved)
+}
@@ -0,0 +1,44 @@
+use syn::meta::ParseNestedMeta;
+
+use crate::utils::collect_meta::{is_flag, parse_nested_options};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StructBuilderConfig {
+    pub enabled: bool,
+    /// Track the required fields with type parameters, so `build` only compiles once they are all set.
+    pub typestate: bool,
+    /// `build` returns a `Result` instead of panicking.
+    pub try_build: bool,
+    /// The setters follow the fields' `set` configuration, like `with_xxx`, instead of the field names.
+    pub mirror_setters: bool,
+}
+
+impl StructBuilderConfig {
+    pub fn from_meta(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut config = Self {
+            enabled: true,
+            typestate: false,
+            try_build: false,
+            mirror_setters: false,
+        };
+
+        parse_nested_options(meta, |meta| {
+            if meta.path.is_ident("typestate") && is_flag(&meta) {
+                config.typestate = true;
+            } else if meta.path.is_ident("try") && is_flag(&meta) {
+                config.try_build = true;
+            } else if meta.path.is_ident("mirror_setters") && is_flag(&meta) {
+                config.mirror_setters = true;
+            } else {
+                return Err(meta.error("invalid `builder` argument, see the documentation for more information"));
+            }
+            Ok(())
+        })?;
+
+        if config.typestate && config.try_build {
+            return Err(meta.error("`typestate` and `try` builders cannot be used together"));
+        }
+
+        Ok(config)
+    }
+}
@@ -0,0 +1,128 @@
+use syn::meta::ParseNestedMeta;
+use syn::Lit;
+
+use crate::utils::collect_meta::collect_meta_map;
+use crate::utils::suggest::unknown_option;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct StructCmpConfig {
+    pub partial_eq: bool,
+    pub eq: bool,
+    pub partial_ord: bool,
+    pub ord: bool,
+    pub hash: bool,
+    /// `cmp_asc` and `cmp_desc`, ordering by the same fields as `Ord`.
+    pub comparator: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FieldCmpConfig {
+    pub eq: bool,
+    pub ord: Option<isize>,
+    pub partial_ord: Option<isize>,
+}
+
+impl Default for FieldCmpConfig {
+    fn default() -> Self {
+        Self {
+            eq: true,
+            ord: None,
+            partial_ord: None,
+        }
+    }
+}
+
+const FIELD_CMP_OPTIONS: &[&str] = &[
+    "eq", "peq", "cmp", "ord", "partial_cmp", "pcmp", "partial_ord", "pord",
+];
+
+impl FieldCmpConfig {
+    pub fn from_meta(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut config: Self = Default::default();
+        config.extend_from_meta(meta)?;
+        Ok(config)
+    }
+
+    /// Overrides the keys listed in `cmp(...)`, the others are kept.
+    pub fn extend_from_meta(&mut self, meta: &ParseNestedMeta) -> syn::Result<()> {
+
+        collect_meta_map(meta, |idx, k, v| {
+            match k.to_string().as_str() {
+                "eq" | "peq" => match v {
+                    Some(Lit::Bool(lit)) => self.eq = lit.value,
+                    Some(Lit::Str(lit)) => match lit.value().parse::<bool>() {
+                        Ok(val) => self.eq = val,
+                        Err(e) => {
+                            return Err(syn::Error::new(
+                                lit.span(),
+                                format!("cannot parse `eq` value: {:?}", e),
+                            ));
+                        }
+                    },
+                    None => self.eq = true,
+                    _ => {
+                        return Err(syn::Error::new(
+                            k.span(),
+                            "invalid `eq` value, see the documentation for more information",
+                        ));
+                    }
+                },
+                "cmp" | "ord" => match v {
+                    Some(Lit::Bool(lit)) => {
+                        if lit.value {
+                            self.ord = Some(idx as isize)
+                        } else {
+                            self.ord = None
+                        }
+                    }
+                    Some(Lit::Str(lit)) => match lit.value().parse::<isize>() {
+                        Ok(val) => self.ord = Some(val),
+                        Err(e) => {
+                            return Err(syn::Error::new(
+                                lit.span(),
+                                format!("cannot parse `cmp` value: {:?}", e),
+                            ));
+                        }
+                    },
+                    Some(Lit::Int(lit)) => self.ord = Some(lit.base10_parse()?),
+                    None => self.ord = Some(idx as isize),
+                    _ => {
+                        return Err(syn::Error::new(
+                            k.span(),
+                            "invalid `cmp` value, see the documentation for more information",
+                        ));
+                    }
+                },
+                "partial_cmp" | "pcmp" | "partial_ord" | "pord" => match v {
+                    Some(Lit::Bool(lit)) => {
+                        if lit.value {
+                            self.partial_ord = Some(idx as isize)
+                        } else {
+                            self.partial_ord = None
+                        }
+                    }
+                    Some(Lit::Str(lit)) => match lit.value().parse::<isize>() {
+                        Ok(val) => self.partial_ord = Some(val),
+                        Err(e) => {
+                            return Err(syn::Error::new(
+                                lit.span(),
+                                format!("cannot parse `partial_cmp` value: {:?}", e),
+                            ));
+                        }
+                    },
+                    Some(Lit::Int(lit)) => self.partial_ord = Some(lit.base10_parse()?),
+                    None => self.partial_ord = Some(idx as isize),
+                    _ => return Err(syn::Error::new(
+                        k.span(),
+                        "invalid `partial_cmp` value, see the documentation for more information",
+                    )),
+                },
+                name => return Err(unknown_option("cmp", name, k.span(), FIELD_CMP_OPTIONS)),
+            };
+
+            Ok(((), ()))
+        })?;
+
+        Ok(())
+    }
+}
@@ -1,194 +1,548 @@
-use crate::cmp::FieldCmpConfig;
-use crate::ops::FieldOpsConfig;
+use crate::access::RefType;
+use crate::arbitrary::FieldArbitraryConfig;
+use crate::clone::FieldCloneConfig;
+use crate::config::cmp_config::FieldCmpConfig;
+use crate::convert::FieldConvertConfig;
+use crate::lerp::FieldLerpConfig;
+use crate::merge::MergeStrategy;
+use crate::config::ops_config::FieldOpsConfig;
+use crate::config::alias::{resolve_alias, FIELD_ALIASES};
+use crate::generate::AccessorIdents;
+use crate::config::precedence::{Declarations, Precedence};
+use crate::config::options::{self, flag_option, OptionError, OptionResult, OptionSpec};
+use crate::utils::collect_meta::{
+    is_flag, is_list, is_value, list_tokens, option_tokens, parse_nested_options, parse_options, skip_option,
+    str_value,
+};
+use crate::utils::suggest::unknown_option;
+use crate::utils::synerr::{ResultExt, SynErrorExt};
+use crate::random::FieldRandomConfig;
 
 use proc_macro2::{Span, TokenStream as TokenStream2};
-use quote::quote;
+use quote::{quote, ToTokens};
 use syn::spanned::Spanned;
-use syn::{Attribute, Expr, Lit, Meta, MetaNameValue, NestedMeta, Type};
+use syn::meta::ParseNestedMeta;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Expr, Ident, Lit, LitBool, LitInt, LitStr, Meta, Signature, Token, Type};
+
+/// Every `dfield` option.
+const FIELD_OPTIONS: &[OptionSpec<FieldConfig>] = &[
+    OptionSpec {
+        name: "default",
+        synonyms: &[],
+        usage: &["default = \"expr\"", "default = expr"],
+        parse: |config, meta| {
+            if !is_value(meta) {
+                return Err(OptionError::Malformed);
+            }
+            let input = meta.value()?;
+            if !input.peek(LitStr) {
+                config.default_value = Some(input.parse()?);
+                config.default_text = None;
+                return Ok(());
+            }
+            let lit: LitStr = input.parse()?;
+            if lit.value().is_empty() {
+                return Err(syn::Error::new(lit.span(), "`default` value should not be empty").into());
+            }
+            config.default_value = Some(lit.parse().map_err(|mut e| {
+                e.extend(syn::Error::new(
+                    lit.span(),
+                    "`default` value should be a valid expression",
+                ));
+                e
+            })?);
+            config.default_text = Some(lit.value());
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "env",
+        synonyms: &[],
+        usage: &["env = \"NAME\""],
+        parse: |config, meta| {
+            config.env = Some(options::value(meta)?);
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "seq",
+        synonyms: &["sequence"],
+        usage: &["seq = 1"],
+        parse: |config, meta| {
+            config.init_seq = Some(options::value::<LitInt>(meta)?.base10_parse()?);
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "get",
+        synonyms: &[],
+        usage: &["get", "get = \"move\""],
+        parse: |config, meta| {
+            config.auto_get = options::choice(meta, |s| GetterType::from_str(s))?;
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "set",
+        synonyms: &[],
+        usage: &["set", "set = \"with\""],
+        parse: |config, meta| {
+            config.auto_set = options::choice(meta, |s| SetterType::from_str(s))?;
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "do_with",
+        synonyms: &[],
+        usage: &["do_with", "do_with = false", "do_with = \"lock\""],
+        parse: |config, meta| {
+            if is_flag(meta) {
+                config.do_with = true;
+                return Ok(());
+            }
+            match options::value::<Lit>(meta)? {
+                Lit::Bool(lit) => config.do_with = lit.value,
+                Lit::Str(lit) if lit.value() == "lock" => (config.do_with, config.do_with_lock) = (true, true),
+                _ => return Err(OptionError::Malformed),
+            }
+            Ok(())
+        },
+    },
+    flag_option!("tuple" => tuple),
+    OptionSpec {
+        name: "convert",
+        synonyms: &[],
+        usage: &["convert = false", "convert = \"same\""],
+        parse: |config, meta| {
+            config.convert = FieldConvertConfig::from_lit(&options::value(meta)?)?;
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "try_convert",
+        synonyms: &[],
+        usage: &["try_convert = \"expr\""],
+        parse: |config, meta| {
+            config.try_convert = Some(options::value(meta)?);
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "validate",
+        synonyms: &[],
+        usage: &["validate = \"expr\""],
+        parse: |config, meta| {
+            config.validate = Some(options::value(meta)?);
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "arbitrary",
+        synonyms: &[],
+        usage: &["arbitrary = false", "arbitrary(...)"],
+        parse: |config, meta| {
+            if is_list(meta) {
+                config.arbitrary = FieldArbitraryConfig::from_meta(meta)?;
+            } else {
+                config.arbitrary.default = !options::value::<LitBool>(meta)?.value;
+            }
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "random",
+        synonyms: &[],
+        usage: &["random = false", "random(...)"],
+        parse: |config, meta| {
+            options::require_feature(meta, "rand", cfg!(feature = "rand"))?;
+            if is_list(meta) {
+                config.random = FieldRandomConfig::from_meta(meta)?;
+            } else {
+                config.random.default = !options::value::<LitBool>(meta)?.value;
+            }
+            Ok(())
+        },
+    },
+    flag_option!("map" => map),
+    flag_option!("snapshot" => snapshot),
+    flag_option!("string_map" => string_map),
+    flag_option!("ffi" => ffi),
+    OptionSpec {
+        name: "debug",
+        synonyms: &[],
+        usage: &["debug", "debug = false"],
+        parse: |config, meta| {
+            config.no_debug = !options::flag(meta)?;
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "lerp",
+        synonyms: &[],
+        usage: &["lerp", "lerp = false", "lerp = \"ignore\""],
+        parse: |config, meta| {
+            config.lerp = if is_flag(meta) {
+                Default::default()
+            } else {
+                FieldLerpConfig::from_lit(&options::value(meta)?)?
+            };
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "fixture",
+        synonyms: &[],
+        usage: &["fixture = \"expr\""],
+        parse: |config, meta| {
+            config.fixture = Some(options::value::<LitStr>(meta)?.parse()?);
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "clone",
+        synonyms: &[],
+        usage: &["clone = \"expr\"", "clone = false"],
+        parse: |config, meta| {
+            config.clone = FieldCloneConfig::from_lit(&options::value(meta)?)?;
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "merge",
+        synonyms: &[],
+        usage: &["merge", "merge = \"strategy\""],
+        parse: |config, meta| {
+            config.merge = if is_flag(meta) {
+                None
+            } else {
+                Some(MergeStrategy::from_lit(&options::value(meta)?)?)
+            };
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "delegate",
+        synonyms: &[],
+        usage: &["delegate(\"fn len(&self) -> usize\")"],
+        parse: |config, meta| {
+            options::list(meta)?;
+            config.delegate.extend(parse_delegate(meta)?);
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "deref",
+        synonyms: &[],
+        usage: &["deref", "deref = \"mut\""],
+        parse: |config, meta| {
+            config.deref = if is_flag(meta) {
+                Default::default()
+            } else {
+                RefType::from_lit(&options::value(meta)?)?
+            };
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "as_ref",
+        synonyms: &[],
+        usage: &["as_ref", "as_ref = false", "as_ref = \"mut\""],
+        parse: |config, meta| {
+            config.as_ref = parse_ref_flag(meta)?;
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "borrow",
+        synonyms: &[],
+        usage: &["borrow", "borrow = false", "borrow = \"mut\""],
+        parse: |config, meta| {
+            config.borrow = parse_ref_flag(meta)?;
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "cmp",
+        synonyms: &[],
+        usage: &["cmp(...)"],
+        parse: |config, meta| {
+            options::require_feature(meta, "cmp", cfg!(feature = "cmp"))?;
+            options::list(meta)?;
+            config.cmp.extend_from_meta(meta)?;
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "hash",
+        synonyms: &[],
+        usage: &["hash = \"expr\""],
+        parse: |config, meta| {
+            options::require_feature(meta, "cmp", cfg!(feature = "cmp"))?;
+            config.hash = Some(options::value(meta)?);
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "ops",
+        synonyms: &[],
+        usage: &["ops(...)"],
+        parse: |config, meta| {
+            options::require_feature(meta, "ops", cfg!(feature = "ops"))?;
+            options::list(meta)?;
+            config.ops.mut_and(FieldOpsConfig::from_meta(meta)?);
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "attrs",
+        synonyms: &[],
+        usage: &["attrs(get = \"#[inline]\")"],
+        parse: |config, meta| {
+            options::list(meta)?;
+            config.attrs.extend_from_meta(meta)?;
+            Ok(())
+        },
+    },
+];
+
+/// `dfield` options which accumulate instead of being overridden.
+const FIELD_REPEATABLE_OPTIONS: &[&str] = &["cmp", "ops", "delegate", "attrs"];
 
-#[derive(Clone)]
 pub struct FieldConfig {
     pub default_value: Option<Expr>,
+    /// The default value as written in `default = "..."`.
+    pub default_text: Option<String>,
+    /// The environment variable overriding the default value.
+    pub env: Option<LitStr>,
+    /// The value of the field in `fixture`, the default value if not set.
+    pub fixture: Option<Expr>,
     pub init_seq: Option<isize>,
     pub auto_set: SetterType,
     pub auto_get: GetterType,
     pub no_debug: bool,
     /// `do_with_xxx(&mut self, f: impl FnOnce(&mut value))`
     pub do_with: bool,
+    /// `do_with = "lock"`, locking a `Mutex` or `RwLock` field through `&self`.
+    pub do_with_lock: bool,
     /// `map_xxx(mut self, f: impl FnOnce(value) -> value) -> Self`
     pub map: bool,
     pub cmp: FieldCmpConfig,
+    /// Value hashed by `Hash` and `cache_key` instead of the field, `$self` refers to the structure.
+    pub hash: Option<LitStr>,
     pub ops: FieldOpsConfig,
+    pub lerp: FieldLerpConfig,
+    pub clone: FieldCloneConfig,
+    /// Whether the field is saved by `snapshot`, true by default.
+    pub snapshot: bool,
+    /// Whether the field is converted by `to_map` and `from_map`, true by default.
+    pub string_map: bool,
+    /// Whether the accessors of the field have `ffi` shims, true by default.
+    pub ffi: bool,
+    /// Inherits the structure's strategy if `None`.
+    pub merge: Option<MergeStrategy>,
+    /// Methods forwarded to the field.
+    pub delegate: Vec<Signature>,
+    pub deref: RefType,
+    pub as_ref: RefType,
+    pub borrow: RefType,
+    /// Whether the field is part of the tuple conversions.
+    pub tuple: bool,
+    pub convert: FieldConvertConfig,
+    /// Fallible conversion used by `TryFrom`, evaluating to a `Result`.
+    pub try_convert: Option<LitStr>,
+    /// Predicate checked by `TryFrom` after the conversion.
+    pub validate: Option<LitStr>,
+    pub arbitrary: FieldArbitraryConfig,
+    pub random: FieldRandomConfig,
+    /// Attributes attached to the generated methods.
+    pub attrs: ForwardedAttrs,
+    /// Name and span of every option, in declaration order.
+    pub spans: Vec<(String, Span)>,
+    pub declarations: Declarations,
 }
 
 impl FieldConfig {
+    /// Span of the last declaration of the option.
+    pub fn span_of(&self, name: &str) -> Option<Span> {
+        self.spans.iter().rev().find(|(n, _)| n == name).map(|(_, span)| *span)
+    }
+
     pub fn from_attribute(
         attrs: Vec<Attribute>,
         default_set: SetterType,
         default_get: GetterType,
+        precedence: Precedence,
     ) -> syn::Result<(Self, Vec<Attribute>)> {
         let mut avec: Vec<Attribute> = Vec::with_capacity(attrs.len());
         let mut config = Self {
             default_value: None,
+            default_text: None,
+            env: None,
+            fixture: None,
             init_seq: None,
             auto_set: default_set,
             auto_get: default_get,
             no_debug: false,
             do_with: false,
+            do_with_lock: false,
             map: false,
             cmp: Default::default(),
+            hash: None,
             ops: Default::default(),
+            lerp: Default::default(),
+            clone: Default::default(),
+            snapshot: true,
+            string_map: true,
+            ffi: true,
+            merge: None,
+            delegate: Vec::new(),
+            deref: RefType::No,
+            as_ref: RefType::No,
+            borrow: RefType::No,
+            tuple: true,
+            convert: Default::default(),
+            try_convert: None,
+            validate: None,
+            arbitrary: Default::default(),
+            random: Default::default(),
+            attrs: Default::default(),
+            spans: Vec::new(),
+            declarations: Default::default(),
         };
 
+        let mut err: Option<syn::Error> = None;
         for attr in attrs {
-            if let Ok(Meta::List(meta_list)) = attr.parse_meta() {
-                if meta_list.path.is_ident("dfield") {
-                    for meta in meta_list.nested {
-                        if let NestedMeta::Meta(meta) = meta {
-                            if meta.path().is_ident("default") {
-                                match meta {
-                                    Meta::NameValue(
-                                        MetaNameValue {
-                                            lit: Lit::Str(lit), ..
-                                        }) => {
-                                        if lit.value().is_empty() {
-                                            return Err(syn::Error::new(
-                                                lit.span(),
-                                                "`default` value should not be empty",
-                                            ));
-                                        }
-                                        config.default_value = Some(syn::parse_str(&lit.value()).map_err(|mut e| {
-                                            e.extend(syn::Error::new(
-                                                lit.span(),
-                                                "`default` value should be a valid expression",
-                                            ));
-                                            e
-                                        })?);
-                                        continue;
-                                    }
-                                    _ => return Err(syn::Error::new(
-                                        meta.span(),
-                                        "invalid `default` value, see the documentation for more information",
-                                    ))
-                                }
-                            } else if meta.path().is_ident("seq")
-                                || meta.path().is_ident("sequence")
-                            {
-                                match meta {
-                                    Meta::NameValue(
-                                        MetaNameValue {
-                                            lit: Lit::Int(lit), ..
-                                        }) => {
-                                        let value: isize = lit.base10_parse()?;
-                                        config.init_seq = Some(value);
-                                        continue;
-                                    }
-                                    _ => return Err(syn::Error::new(
-                                        meta.span(),
-                                        "invalid `seq` value, see the documentation for more information",
-                                    ))
-                                }
-                            } else if meta.path().is_ident("get") {
-                                match meta {
-                                    Meta::Path(_) => config.auto_get = Default::default(),
-                                    Meta::NameValue(
-                                        MetaNameValue {
-                                            lit: Lit::Str(lit), ..
-                                        }) => {
-                                        config.auto_get = GetterType::from_str(lit.value())
-                                            .ok_or_else(|| syn::Error::new(lit.span(), "unknown `get` type"))?;
-                                        continue;
-                                    }
-                                    _ => return Err(syn::Error::new(
-                                        meta.span(),
-                                        "invalid `get` value, see the documentation for more information",
-                                    ))
-                                }
-                            } else if meta.path().is_ident("set") {
-                                match meta {
-                                    Meta::Path(_) => config.auto_set = Default::default(),
-                                    Meta::NameValue(
-                                        MetaNameValue {
-                                            lit: Lit::Str(lit), ..
-                                        }) => {
-                                        config.auto_set = SetterType::from_str(lit.value())
-                                            .ok_or_else(|| syn::Error::new(lit.span(), "unknown `set` type"))?;
-                                        continue;
-                                    }
-                                    _ => return Err(syn::Error::new(
-                                        meta.span(),
-                                        "invalid `set` value, see the documentation for more information",
-                                    ))
-                                }
-                            } else if meta.path().is_ident("do_with") {
-                                match meta {
-                                    Meta::Path(_) => config.do_with = true,
-                                    Meta::NameValue(
-                                        MetaNameValue {
-                                            lit: Lit::Bool(lit), ..
-                                        }) => {
-                                        config.do_with = lit.value
-                                    }
-                                    _ => return Err(syn::Error::new(
-                                        meta.span(),
-                                        "invalid `do_with` value, see the documentation for more information",
-                                    ))
-                                }
-                            } else if meta.path().is_ident("map") {
-                                match meta {
-                                    Meta::Path(_) => config.map = true,
-                                    Meta::NameValue(
-                                        MetaNameValue {
-                                            lit: Lit::Bool(lit), ..
-                                        }) => {
-                                        config.map = lit.value
-                                    }
-                                    _ => return Err(syn::Error::new(
-                                        meta.span(),
-                                        "invalid `map` value, see the documentation for more information",
-                                    ))
-                                }
-                            } else if meta.path().is_ident("no_debug") {
-                                match meta {
-                                    Meta::Path(_) => config.no_debug = true,
-                                    Meta::NameValue(
-                                        MetaNameValue {
-                                            lit: Lit::Bool(lit), ..
-                                        }) => {
-                                        config.no_debug = lit.value
-                                    }
-                                    _ => return Err(syn::Error::new(
-                                        meta.span(),
-                                        "invalid `no_debug` value, see the documentation for more information",
-                                    ))
-                                }
-                            } else if meta.path().is_ident("cmp") {
-                                if let Meta::List(ml) = meta {
-                                    let cmp_cfg = FieldCmpConfig::from_meta(&ml)?;
-                                    config.cmp = cmp_cfg;
-                                } else {
-                                    return Err(syn::Error::new(meta.span(), "invalid `cmp` value, see the documentation for more information"));
-                                }
-                            } else if meta.path().is_ident("ops") {
-                                if let Meta::List(ml) = meta {
-                                    let ops_cfg = FieldOpsConfig::from_meta(&ml)?;
-                                    config.ops = ops_cfg;
-                                } else {
-                                    return Err(syn::Error::new(meta.span(), "invalid `ops` value, see the documentation for more information"));
-                                }
-                            }
+            // options inherited from groups can be overridden silently
+            let inherited = attr.path().is_ident("dfield_group");
+            if attr.path().is_ident("dfield") || inherited {
+                match &attr.meta {
+                    Meta::List(ml) => {
+                        let res = parse_options(ml.tokens.clone(), |meta| {
+                            config.parse_declared(meta, precedence, inherited)
+                        });
+                        if let Err(e) = res {
+                            err.update_or_combine(e);
                         }
                     }
+                    _ => err.update_or_combine(syn::Error::new(
+                        attr.span(),
+                        "`dfield` attribute should be like `#[dfield(...)]`",
+                    )),
                 }
             }
 
             avec.push(attr)
         }
+        err.ok_or(()).swap()?;
 
         Ok((config, avec))
     }
+
+    /// Resolves renamed and repeated options before parsing them.
+    fn parse_declared(&mut self, meta: ParseNestedMeta, precedence: Precedence, inherited: bool) -> syn::Result<()> {
+        let value = option_tokens(meta.input);
+        if let Some(resolved) = resolve_alias(&meta.path, &value, FIELD_ALIASES, &mut self.declarations.warnings) {
+            skip_option(meta.input)?;
+            return parse_options(resolved, |meta| self.parse_declared(meta, precedence, inherited));
+        }
+        if !self.declarations.accept(&meta.path, &value, precedence, FIELD_REPEATABLE_OPTIONS, inherited) {
+            return skip_option(meta.input);
+        }
+        self.parse_option(meta)
+    }
+
+    /// Parses one option of `#[dfield(...)]` into the configuration.
+    fn parse_option(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        self.spans.push((meta.path.to_token_stream().to_string(), meta.path.span()));
+        options::parse_option(self, &meta, "dfield", FIELD_OPTIONS)
+    }
 }
 
+/// `as_ref` and `borrow`, where a flag means [`RefType::Ref`].
+fn parse_ref_flag(meta: &ParseNestedMeta) -> OptionResult<RefType> {
+    if is_flag(meta) {
+        return Ok(RefType::Ref);
+    }
+    match options::value(meta)? {
+        Lit::Bool(lit) => Ok(if lit.value { RefType::Ref } else { RefType::No }),
+        lit => Ok(RefType::from_lit(&lit)?),
+    }
+}
+
+fn parse_delegate(meta: &ParseNestedMeta) -> syn::Result<Vec<Signature>> {
+    let mut sigs = Vec::new();
+    parse_nested_options(meta, |meta| {
+        if !meta.path.is_ident("methods") || !is_list(&meta) {
+            return Err(meta.error("invalid `delegate` value, expect `methods(\"...\")`"));
+        }
+        let methods = Punctuated::<LitStr, Token![,]>::parse_terminated
+            .parse2(list_tokens(&meta)?)
+            .map_err(|e| syn::Error::new(e.span(), "delegated method should be a string literal"))?;
+        for lit in methods {
+            let sig: Signature = lit.parse().map_err(|_| {
+                syn::Error::new(
+                    lit.span(),
+                    "delegated method should be a signature like `fn len(&self) -> usize`",
+                )
+            })?;
+            if sig.receiver().is_none() {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    "delegated method should take `self`, `&self` or `&mut self`",
+                ));
+            }
+            sigs.push(sig);
+        }
+        Ok(())
+    })?;
+    Ok(sigs)
+}
+
+/// Attributes attached to the generated methods of a field, by the kind of the method.
+#[derive(Default)]
+pub struct ForwardedAttrs {
+    /// `xxx(&self)`
+    pub get: Vec<Attribute>,
+    /// `get_xxx(self)`
+    pub r#move: Vec<Attribute>,
+    /// `set_xxx(&mut self, value)`
+    pub set: Vec<Attribute>,
+    /// `with_xxx(self, value)`
+    pub with: Vec<Attribute>,
+    pub do_with: Vec<Attribute>,
+    pub map: Vec<Attribute>,
+    /// Every delegated method.
+    pub delegate: Vec<Attribute>,
+}
+
+impl ForwardedAttrs {
+    pub fn extend_from_meta(&mut self, meta: &ParseNestedMeta) -> syn::Result<()> {
+        parse_nested_options(meta, |meta| {
+            let target = match meta.path.to_token_stream().to_string().as_str() {
+                "get" => &mut self.get,
+                "move" => &mut self.r#move,
+                "set" => &mut self.set,
+                "with" => &mut self.with,
+                "do_with" => &mut self.do_with,
+                "map" => &mut self.map,
+                "delegate" => &mut self.delegate,
+                name => return Err(unknown_option("attrs", name, meta.path.span(), FORWARD_TARGETS)),
+            };
+            let lit = str_value(&meta)
+                .map_err(|_| meta.error("forwarded attributes should be like `get = \"#[inline]\"`"))?;
+            let attrs = lit
+                .parse_with(Attribute::parse_outer)
+                .map_err(|e| syn::Error::new(lit.span(), e))?;
+            target.extend(attrs);
+            Ok(())
+        })
+    }
+}
+
+const FORWARD_TARGETS: &[&str] = &["get", "move", "set", "with", "do_with", "map", "delegate"];
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum SetterType {
     /// Both `Set` and `With`
@@ -212,20 +566,18 @@ impl SetterType {
         }
     }
 
-    fn set(ident: &str, ty: &Type, span: &Span) -> TokenStream2 {
-        let func_name = proc_macro2::Ident::new(&format!("set_{ident}"), *span);
-        let ident = proc_macro2::Ident::new(ident, *span);
+    fn set(ident: &Ident, func_name: &Ident, ty: &Type, attrs: &[Attribute]) -> TokenStream2 {
         quote! {
+            #(#attrs)*
             pub fn #func_name(&mut self, #ident: #ty) {
                 self.#ident = #ident;
             }
         }
     }
 
-    fn with(ident: &str, ty: &Type, span: &Span) -> TokenStream2 {
-        let func_name = proc_macro2::Ident::new(&format!("with_{ident}"), *span);
-        let ident = proc_macro2::Ident::new(ident, *span);
+    fn with(ident: &Ident, func_name: &Ident, ty: &Type, attrs: &[Attribute]) -> TokenStream2 {
         quote! {
+            #(#attrs)*
             pub fn #func_name(mut self, #ident: #ty) -> Self {
                 self.#ident = #ident;
                 self
@@ -233,11 +585,19 @@ impl SetterType {
         }
     }
 
-    pub fn to_code(self, ident: &str, ty: &Type, span: &Span) -> Vec<TokenStream2> {
+    pub fn to_code(
+        self,
+        ident: &Ident,
+        accessors: &AccessorIdents,
+        ty: &Type,
+        attrs: &ForwardedAttrs,
+    ) -> Vec<TokenStream2> {
+        let set = || Self::set(ident, &accessors.set, ty, &attrs.set);
+        let with = || Self::with(ident, &accessors.with, ty, &attrs.with);
         match self {
-            Self::Full => vec![Self::set(ident, ty, span), Self::with(ident, ty, span)],
-            Self::Set => vec![Self::set(ident, ty, span)],
-            Self::With => vec![Self::with(ident, ty, span)],
+            Self::Full => vec![set(), with()],
+            Self::Set => vec![set()],
+            Self::With => vec![with()],
             Self::No => vec![],
         }
     }
@@ -266,31 +626,37 @@ impl GetterType {
         }
     }
 
-    fn get(ident: &str, ty: &Type, span: &Span) -> TokenStream2 {
-        let func_name = proc_macro2::Ident::new(ident, *span);
-        let ident = proc_macro2::Ident::new(ident, *span);
+    fn get(ident: &Ident, ty: &Type, attrs: &[Attribute]) -> TokenStream2 {
         quote! {
-            pub fn #func_name(&self) -> &#ty {
+            #(#attrs)*
+            pub fn #ident(&self) -> &#ty {
                 &self.#ident
             }
         }
     }
 
-    fn r#move(ident: &str, ty: &Type, span: &Span) -> TokenStream2 {
-        let func_name = proc_macro2::Ident::new(&format!("get_{ident}"), *span);
-        let ident = proc_macro2::Ident::new(ident, *span);
+    fn r#move(ident: &Ident, func_name: &Ident, ty: &Type, attrs: &[Attribute]) -> TokenStream2 {
         quote! {
+            #(#attrs)*
             pub fn #func_name(self) -> #ty {
                 self.#ident
             }
         }
     }
 
-    pub fn to_code(self, ident: &str, ty: &Type, span: &Span) -> Vec<TokenStream2> {
+    pub fn to_code(
+        self,
+        ident: &Ident,
+        accessors: &AccessorIdents,
+        ty: &Type,
+        attrs: &ForwardedAttrs,
+    ) -> Vec<TokenStream2> {
+        let get = || Self::get(ident, ty, &attrs.get);
+        let r#move = || Self::r#move(ident, &accessors.r#move, ty, &attrs.r#move);
         match self {
-            Self::Full => vec![Self::get(ident, ty, span), Self::r#move(ident, ty, span)],
-            Self::Get => vec![Self::get(ident, ty, span)],
-            Self::Move => vec![Self::r#move(ident, ty, span)],
+            Self::Full => vec![get(), r#move()],
+            Self::Get => vec![get()],
+            Self::Move => vec![r#move()],
             Self::No => vec![],
         }
     }
@@ -1,2 +1,11 @@
+pub mod alias;
+pub mod builder_config;
+pub mod cmp_config;
 pub mod struct_config;
 pub mod field_config;
+pub mod ops_config;
+pub mod options;
+pub mod precedence;
+pub mod reflect_config;
+pub mod validate;
+pub mod where_config;
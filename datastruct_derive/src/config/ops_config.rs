@@ -0,0 +1,270 @@
+use syn::meta::ParseNestedMeta;
+use syn::{Expr, Lit, LitStr};
+
+use crate::utils::collect_meta::{
+    is_flag, is_list, is_value, lit_value, parse_expr, parse_flag, parse_nested_options, str_value,
+};
+
+#[derive(Clone, Default)]
+pub struct StructOpsConfig {
+    pub add: Option<OpsAssignableType>,
+    pub sub: Option<OpsAssignableType>,
+    pub mul: Option<OpsAssignableType>,
+    pub div: Option<OpsAssignableType>,
+    /// Expression templates applied to every field inheriting the operation.
+    pub add_expr: Option<LitStr>,
+    pub sub_expr: Option<LitStr>,
+    pub mul_expr: Option<LitStr>,
+    pub div_expr: Option<LitStr>,
+    /// Scalar types the structure can be scaled by.
+    pub mul_scalar: Option<String>,
+    pub div_scalar: Option<String>,
+    pub identity: Option<bool>,
+    /// `apply_op`, selecting the operation at runtime.
+    pub apply_op: Option<bool>,
+    /// Requires every field to configure every generated operation explicitly.
+    pub strict: Option<bool>,
+    /// Replaces the inferred bounds of the generated impls.
+    pub bound: Option<String>,
+}
+
+impl StructOpsConfig {
+    pub fn mut_and(&mut self, other: Self) {
+        macro_rules! __impl_override {
+            ($self:ident, $other:ident, $($ident:ident),+ $(,)?) => {
+                $(if let Some(v) = $other.$ident {
+                    $self.$ident = Some(v)
+                })+
+            };
+        }
+
+        __impl_override!(
+            self, other, add, sub, mul, div, add_expr, sub_expr, mul_expr, div_expr, mul_scalar, div_scalar,
+            identity, apply_op, strict, bound
+        );
+    }
+
+    pub fn from_meta(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut config: StructOpsConfig = Default::default();
+
+        parse_nested_options(meta, |meta| {
+            if meta.path.is_ident("bound") {
+                let lit = str_value(&meta)
+                    .map_err(|_| meta.error("`bound` argument should be like `bound = \"T: Add<Output = T>\"`"))?;
+                config.bound = Some(lit.value());
+                return Ok(());
+            }
+
+            if meta.path.is_ident("identity") {
+                config.identity = Some(parse_flag(&meta)?);
+                return Ok(());
+            }
+
+            if meta.path.is_ident("apply_op") {
+                config.apply_op = Some(parse_flag(&meta)?);
+                return Ok(());
+            }
+
+            if meta.path.is_ident("strict") {
+                config.strict = Some(parse_flag(&meta)?);
+                return Ok(());
+            }
+
+            let ops_type = meta
+                .path
+                .get_ident()
+                .and_then(|i| OpsType::from_str(i.to_string()))
+                .ok_or_else(|| meta.error("invalid ops type"))?;
+            let mut template: Option<LitStr> = None;
+            let mut scalar: Option<String> = None;
+            let val: Option<OpsAssignableType> = if is_flag(&meta) {
+                Some(Default::default())
+            } else if is_list(&meta) {
+                let mut val = OpsAssignableType::default();
+                parse_nested_options(&meta, |item| {
+                    let invalid = || item.error("invalid ops argument, expect `expr = \"...\"` or `mode = \"...\"`");
+                    let is_scalar = item.path.is_ident("scalar") && matches!(ops_type, OpsType::Mul | OpsType::Div);
+                    if !item.path.is_ident("expr") && !item.path.is_ident("mode") && !is_scalar {
+                        return Err(invalid());
+                    }
+                    let s = str_value(&item).map_err(|_| invalid())?;
+                    if item.path.is_ident("expr") {
+                        template = Some(s);
+                    } else if is_scalar {
+                        scalar = Some(s.value());
+                    } else {
+                        val = OpsAssignableType::from_str(s.value())
+                            .ok_or_else(|| syn::Error::new(s.span(), "invalid ops operation type"))?;
+                    }
+                    Ok(())
+                })?;
+                Some(val)
+            } else {
+                match lit_value(&meta) {
+                    Ok(Lit::Str(s)) => Some(
+                        OpsAssignableType::from_str(s.value())
+                            .ok_or_else(|| syn::Error::new(s.span(), "invalid ops operation type"))?,
+                    ),
+                    Ok(Lit::Bool(b)) => b.value.then(Default::default),
+                    _ => return Err(meta.error("invalid ops operation type")),
+                }
+            };
+
+            match ops_type {
+                OpsType::Add => (config.add, config.add_expr) = (val, template),
+                OpsType::Sub => (config.sub, config.sub_expr) = (val, template),
+                OpsType::Mul => {
+                    (config.mul, config.mul_expr, config.mul_scalar) = (val, template, scalar)
+                }
+                OpsType::Div => {
+                    (config.div, config.div_expr, config.div_scalar) = (val, template, scalar)
+                }
+            }
+            Ok(())
+        })?;
+
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum OpsType {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl OpsType {
+    pub fn from_str(s: impl AsRef<str>) -> Option<Self> {
+        match s.as_ref() {
+            "add" => Some(OpsType::Add),
+            "sub" => Some(OpsType::Sub),
+            "mul" => Some(OpsType::Mul),
+            "div" => Some(OpsType::Div),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum OpsAssignableType {
+    Both,
+    Assign,
+    #[default]
+    Plain,
+}
+
+impl OpsAssignableType {
+    pub fn from_str(s: impl AsRef<str>) -> Option<Self> {
+        match s.as_ref() {
+            "both" | "all" => Some(Self::Both),
+            "assign" => Some(Self::Assign),
+            "plain" | "default" => Some(Self::Plain),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct FieldOpsConfig {
+    pub add: Option<OpsOperationType>,
+    pub sub: Option<OpsOperationType>,
+    pub mul: Option<OpsOperationType>,
+    pub div: Option<OpsOperationType>,
+    pub add_assign: Option<OpsOperationType>,
+    pub sub_assign: Option<OpsOperationType>,
+    pub mul_assign: Option<OpsOperationType>,
+    pub div_assign: Option<OpsOperationType>,
+    pub zero: Option<Expr>,
+    pub one: Option<Expr>,
+}
+
+macro_rules! __help_impl_field_config_match {
+    ($meta:ident, $config:ident: $($ident:ident;)+) => {
+        $(if $meta.path.is_ident(stringify!($ident)) {
+            if is_flag(&$meta) {
+                $config.$ident = Some(Default::default());
+            } else {
+                let lit = lit_value(&$meta)
+                    .map_err(|_| $meta.error(concat!("invalid ops `", stringify!($ident), "` type")))?;
+                $config.$ident = Some(OpsOperationType::from_lit(&lit)?);
+            }
+            return Ok(());
+        })+
+        return Err($meta.error("invalid ops type"));
+    };
+}
+
+impl FieldOpsConfig {
+    pub fn mut_and(&mut self, other: Self) {
+        macro_rules! __impl_override {
+            ($self:ident, $other:ident, $($ident:ident),+ $(,)?) => {
+                $(if let Some(v) = $other.$ident {
+                    $self.$ident = Some(v)
+                })+
+            };
+        }
+
+        __impl_override!(
+            self, other, add, sub, mul, div, add_assign, sub_assign, mul_assign, div_assign, zero, one
+        );
+    }
+
+    pub fn from_meta(meta: &ParseNestedMeta) -> syn::Result<FieldOpsConfig> {
+        let mut config: FieldOpsConfig = Default::default();
+
+        parse_nested_options(meta, |meta| {
+            if meta.path.is_ident("zero") || meta.path.is_ident("one") {
+                if !is_value(&meta) {
+                    return Err(meta.error("identity value should be like `zero = \"expression\"`"));
+                }
+                let expr = parse_expr(&meta)?;
+                if meta.path.is_ident("zero") {
+                    config.zero = Some(expr);
+                } else {
+                    config.one = Some(expr);
+                }
+                return Ok(());
+            }
+
+            __help_impl_field_config_match! {
+                meta, config:
+                add; sub; mul; div;
+                add_assign; sub_assign; mul_assign; div_assign;
+            }
+        })?;
+
+        Ok(config)
+    }
+}
+
+#[derive(Clone, Default)]
+pub enum OpsOperationType {
+    // The template is only expanded by the `ops` generation.
+    #[cfg_attr(not(feature = "ops"), allow(dead_code))]
+    Manual(LitStr),
+    #[default]
+    Inherit,
+    Ignore,
+}
+
+impl OpsOperationType {
+    pub fn from_lit(lit: &Lit) -> syn::Result<Self> {
+        match lit {
+            Lit::Str(lit_str) => match lit_str.value().as_str() {
+                "inherit" | "default" => Ok(Self::Inherit),
+                "ignore" | "no" => Ok(Self::Ignore),
+                _ => Ok(Self::Manual(lit_str.clone())),
+            },
+            Lit::Bool(lit_bool) => {
+                if lit_bool.value {
+                    Ok(Self::Inherit)
+                } else {
+                    Ok(Self::Ignore)
+                }
+            }
+            _ => Err(syn::Error::new(lit.span(), "invalid ops operation type")),
+        }
+    }
+}
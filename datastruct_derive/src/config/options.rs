@@ -0,0 +1,165 @@
+use quote::ToTokens;
+use syn::meta::ParseNestedMeta;
+use syn::parse::Parse;
+use syn::spanned::Spanned;
+use syn::{LitBool, LitStr};
+
+use crate::utils::collect_meta::{is_flag, is_list, is_value};
+use crate::utils::suggest::unknown_option;
+
+/// One option of `#[dstruct(...)]` or `#[dfield(...)]`, parsed into the configuration `C`.
+pub struct OptionSpec<C> {
+    pub name: &'static str,
+    /// Other names of the option, like `sequence` for `seq`.
+    pub synonyms: &'static [&'static str],
+    /// The accepted forms of the option, listed when it's malformed.
+    pub usage: &'static [&'static str],
+    pub parse: fn(&mut C, &ParseNestedMeta) -> OptionResult,
+}
+
+impl<C> OptionSpec<C> {
+    fn matches(&self, meta: &ParseNestedMeta) -> bool {
+        meta.path.is_ident(self.name) || self.synonyms.iter().any(|s| meta.path.is_ident(s))
+    }
+
+    fn malformed(&self, meta: &ParseNestedMeta) -> syn::Error {
+        let usage = self.usage.iter().map(|u| format!("`{}`", u)).collect::<Vec<_>>();
+        let usage = match usage.split_last() {
+            Some((last, [])) => last.clone(),
+            Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+            None => String::new(),
+        };
+        meta.error(format!("`{}` argument should be like {}", self.name, usage))
+    }
+}
+
+/// Error of an option parser.
+pub enum OptionError {
+    /// The option matches none of its forms, and is reported with its usage.
+    Malformed,
+    Error(syn::Error),
+}
+
+impl From<syn::Error> for OptionError {
+    fn from(err: syn::Error) -> Self {
+        Self::Error(err)
+    }
+}
+
+pub type OptionResult<T = ()> = Result<T, OptionError>;
+
+/// Parses the option with its entry in the table.
+pub fn parse_option<C>(
+    config: &mut C,
+    meta: &ParseNestedMeta,
+    attr: &str,
+    table: &[OptionSpec<C>],
+) -> syn::Result<()> {
+    let spec = match table.iter().find(|spec| spec.matches(meta)) {
+        Some(spec) => spec,
+        None => {
+            let names = table
+                .iter()
+                .flat_map(|spec| std::iter::once(spec.name).chain(spec.synonyms.iter().copied()))
+                .collect::<Vec<_>>();
+            return Err(unknown_option(
+                attr,
+                &meta.path.to_token_stream().to_string(),
+                meta.path.span(),
+                &names,
+            ));
+        }
+    };
+    (spec.parse)(config, meta).map_err(|err| match err {
+        OptionError::Malformed => spec.malformed(meta),
+        OptionError::Error(err) => err,
+    })
+}
+
+/// `x` or `x = false`.
+pub fn flag(meta: &ParseNestedMeta) -> OptionResult<bool> {
+    if is_flag(meta) {
+        return Ok(true);
+    }
+    value::<LitBool>(meta).map(|lit| lit.value)
+}
+
+/// `x = value`.
+pub fn value<T: Parse>(meta: &ParseNestedMeta) -> OptionResult<T> {
+    if !is_value(meta) {
+        return Err(OptionError::Malformed);
+    }
+    meta.value()?.parse().map_err(|_| OptionError::Malformed)
+}
+
+/// `x(...)`, whose content is left to the caller.
+pub fn list(meta: &ParseNestedMeta) -> OptionResult {
+    if is_list(meta) {
+        Ok(())
+    } else {
+        Err(OptionError::Malformed)
+    }
+}
+
+/// `x = "name"`, where the name is parsed by `from_str`.
+pub fn named<T>(meta: &ParseNestedMeta, from_str: fn(&str) -> Option<T>) -> OptionResult<T> {
+    let lit: LitStr = value(meta)?;
+    from_str(&lit.value()).ok_or_else(|| {
+        let msg = format!(
+            "unknown `{}` value `{}`",
+            meta.path.to_token_stream(),
+            lit.value()
+        );
+        syn::Error::new(lit.span(), msg).into()
+    })
+}
+
+/// `x` for the default value, or `x = "name"`.
+pub fn choice<T: Default>(meta: &ParseNestedMeta, from_str: fn(&str) -> Option<T>) -> OptionResult<T> {
+    if is_flag(meta) {
+        return Ok(T::default());
+    }
+    named(meta, from_str)
+}
+
+/// Rejects the option if the cargo feature generating its code is disabled.
+pub fn require_feature(meta: &ParseNestedMeta, feature: &str, enabled: bool) -> OptionResult {
+    if enabled {
+        return Ok(());
+    }
+    let msg = format!(
+        "`{}` requires the `{}` feature of `datastruct`",
+        meta.path.to_token_stream(),
+        feature
+    );
+    Err(syn::Error::new(meta.path.span(), msg).into())
+}
+
+/// An entry setting a `bool`, like `debug` or `debug = false`.
+macro_rules! flag_option {
+    ($name:literal => $($field:ident).+) => {
+        $crate::config::options::OptionSpec {
+            name: $name,
+            synonyms: &[],
+            usage: &[$name, concat!($name, " = false")],
+            parse: |config, meta| {
+                config.$($field).+ = $crate::config::options::flag(meta)?;
+                Ok(())
+            },
+        }
+    };
+    ($name:literal => $($field:ident).+, feature = $feature:literal) => {
+        $crate::config::options::OptionSpec {
+            name: $name,
+            synonyms: &[],
+            usage: &[$name, concat!($name, " = false")],
+            parse: |config, meta| {
+                $crate::config::options::require_feature(meta, $feature, cfg!(feature = $feature))?;
+                config.$($field).+ = $crate::config::options::flag(meta)?;
+                Ok(())
+            },
+        }
+    };
+}
+
+pub(crate) use flag_option;
@@ -0,0 +1,150 @@
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use syn::parse::{ParseStream, Parser};
+use syn::spanned::Spanned;
+use syn::{Attribute, Lit, Meta, Path, Token};
+
+use crate::utils::collect_meta::{parse_options, skip_option, str_value};
+use crate::utils::synerr::{ResultExt, SynErrorExt};
+
+/// Which declaration wins when an option is declared more than once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Precedence {
+    /// The last declaration overrides the previous ones.
+    #[default]
+    Last,
+    /// The first declaration is kept, the following ones are ignored.
+    First,
+}
+
+impl Precedence {
+    pub fn from_str(s: impl AsRef<str>) -> Option<Self> {
+        match s.as_ref() {
+            "last" => Some(Self::Last),
+            "first" => Some(Self::First),
+            _ => None,
+        }
+    }
+
+    /// Looks up `#[dstruct(precedence = "...")]` ahead of the other options, as it affects all of them.
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut precedence = Self::default();
+        let mut err: Option<syn::Error> = None;
+        let lists = attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("dstruct"))
+            .filter_map(|attr| match &attr.meta {
+                Meta::List(ml) => Some(ml.tokens.clone()),
+                _ => None,
+            });
+        for tokens in lists {
+            // the other options are reported when they're parsed
+            let _ = parse_options(tokens, |meta| {
+                if meta.path.is_ident("precedence") {
+                    let parsed = str_value(&meta)
+                        .map_err(|_| {
+                            meta.error("`precedence` argument should be like `precedence = \"first\"`")
+                        })
+                        .and_then(|lit| {
+                            Self::from_str(lit.value()).ok_or_else(|| {
+                                syn::Error::new(lit.span(), "unknown precedence, expect `last` or `first`")
+                            })
+                        });
+                    match parsed {
+                        Ok(parsed) => precedence = parsed,
+                        Err(e) => err.update_or_combine(e),
+                    }
+                }
+                skip_option(meta.input)
+            });
+        }
+        err.ok_or(()).swap()?;
+        Ok(precedence)
+    }
+}
+
+/// The value of a declared option, compared regardless of how it's written,
+/// e.g. `debug` is `debug = true`, and `seq = 0x10` is `seq = 16`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DeclaredValue {
+    Bool(bool),
+    Str(String),
+    /// Digits in base 10, and the suffix.
+    Int(String, String),
+    Float(String, String),
+    /// Other values, like lists or expressions, by their tokens.
+    Tokens(String),
+}
+
+impl DeclaredValue {
+    /// Parses the tokens following the name of the option.
+    fn parse(tokens: &TokenStream2) -> Self {
+        let parser = |input: ParseStream| {
+            if input.is_empty() {
+                return Ok(Self::Bool(true));
+            }
+            input.parse::<Token![=]>()?;
+            Ok(match input.parse::<Lit>()? {
+                Lit::Bool(lit) => Self::Bool(lit.value),
+                Lit::Str(lit) => Self::Str(lit.value()),
+                Lit::Int(lit) => Self::Int(lit.base10_digits().to_string(), lit.suffix().to_string()),
+                Lit::Float(lit) => Self::Float(lit.base10_digits().to_string(), lit.suffix().to_string()),
+                lit => return Err(syn::Error::new(lit.span(), "not a plain literal")),
+            })
+        };
+        parser
+            .parse2(tokens.clone())
+            .unwrap_or_else(|_| Self::Tokens(tokens.to_string()))
+    }
+}
+
+/// The options declared so far, to resolve the repeated ones by the [`Precedence`].
+#[derive(Debug, Clone, Default)]
+pub struct Declarations {
+    /// Name and value of every option, and whether it's inherited.
+    options: Vec<(String, DeclaredValue, bool)>,
+    /// Repeated options declared with different values.
+    pub warnings: Vec<(Span, String)>,
+}
+
+impl Declarations {
+    /// Whether the option should be applied.
+    ///
+    /// Options in `repeatable` accumulate, so they are always applied.
+    /// Inherited options are not reported when they're overridden.
+    pub fn accept(
+        &mut self,
+        path: &Path,
+        value: &TokenStream2,
+        precedence: Precedence,
+        repeatable: &[&str],
+        inherited: bool,
+    ) -> bool {
+        // other paths are unknown options, reported when they're parsed
+        let name = match path.get_ident() {
+            Some(ident) => ident.to_string(),
+            None => return true,
+        };
+        if repeatable.contains(&name.as_str()) {
+            return true;
+        }
+        let value = DeclaredValue::parse(value);
+        let conflicting = !inherited
+            && self
+                .options
+                .iter()
+                .any(|(n, v, i)| !i && *n == name && *v != value);
+        let declared = self.options.iter().any(|(n, _, _)| *n == name);
+        if conflicting {
+            let used = match precedence {
+                Precedence::Last => "the last declaration is used",
+                Precedence::First => "the first declaration is used",
+            };
+            self.warnings.push((
+                path.span(),
+                format!("`{}` is declared more than once with different values, {}", name, used),
+            ));
+        }
+        self.options.push((name, value, inherited));
+        precedence == Precedence::Last || !declared
+    }
+}
@@ -0,0 +1,40 @@
+use syn::meta::ParseNestedMeta;
+
+use crate::utils::collect_meta::{is_flag, parse_nested_options};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StructReflectConfig {
+    pub enabled: bool,
+    /// Dynamic access to the fields by name, via `dyn Any`.
+    pub any: bool,
+    /// Implement `DataStructFields`, via `dyn Debug`.
+    pub debug: bool,
+    /// `compare_by`, ordering by the fields selected at runtime.
+    pub compare: bool,
+}
+
+impl StructReflectConfig {
+    pub fn from_meta(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut config = Self {
+            enabled: true,
+            any: false,
+            debug: false,
+            compare: false,
+        };
+
+        parse_nested_options(meta, |meta| {
+            if meta.path.is_ident("any") && is_flag(&meta) {
+                config.any = true;
+            } else if meta.path.is_ident("debug") && is_flag(&meta) {
+                config.debug = true;
+            } else if meta.path.is_ident("compare") && is_flag(&meta) {
+                config.compare = true;
+            } else {
+                return Err(meta.error("invalid `reflect` argument, see the documentation for more information"));
+            }
+            Ok(())
+        })?;
+
+        Ok(config)
+    }
+}
@@ -1,205 +1,650 @@
-use crate::cmp::StructCmpConfig;
+use crate::arbitrary::StructArbitraryConfig;
+use crate::config::builder_config::StructBuilderConfig;
+use crate::config::cmp_config::StructCmpConfig;
+use crate::computed::ComputedField;
+use crate::consts::StructConst;
+use crate::convert::{PartsType, StructConvertConfig};
+use crate::group::FieldGroup;
+use crate::config::alias::{resolve_alias, STRUCT_ALIASES};
 use crate::config::field_config::{GetterType, SetterType};
-use crate::utils::collect_meta::collect_meta_set;
-use crate::ops::StructOpsConfig;
+use crate::config::precedence::{Declarations, Precedence};
+use crate::config::where_config::{parse_predicates, StructWhereConfig};
+use crate::config::options::{self, flag_option, OptionSpec};
+use crate::utils::collect_meta::{
+    collect_meta_set, is_flag, is_list, is_value, list_tokens, option_tokens, parse_nested_options, parse_options,
+    skip_option, str_value,
+};
+use crate::utils::suggest::unknown_option;
+use crate::utils::synerr::{ResultExt, SynErrorExt};
+use crate::invariant::StructInvariantConfig;
+use crate::merge::MergeStrategy;
+use crate::config::ops_config::StructOpsConfig;
+use crate::patch::StructPatchConfig;
+use crate::config::reflect_config::StructReflectConfig;
+use crate::ffi::StructFfiConfig;
+use crate::env::StructEnvConfig;
+use crate::layout::StructLayoutConfig;
 
 use proc_macro2::Span;
+use quote::ToTokens;
 use syn::spanned::Spanned;
-use syn::{Attribute, Lit, Meta, MetaList, MetaNameValue, NestedMeta};
+use syn::meta::ParseNestedMeta;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{parse_quote, Attribute, Lit, LitStr, Meta, Path, Token, Type};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StructConfig {
     pub generate_default: bool,
+    /// `data_default_from_env`, with `default(env_overrides)`.
+    pub env_overrides: StructEnvConfig,
     pub const_default: bool,
+    /// `const(check)`, a test comparing `DEFAULT` with the defaults evaluated at runtime.
+    pub const_check: bool,
     pub impl_std_default: bool,
     pub partial_default: bool,
+    pub zeroed: bool,
+    pub random: bool,
+    pub cache_key: bool,
+    /// `diff_display`, listing the fields differing by `eq`.
+    pub diff_display: bool,
+    /// `assert_eq_verbose`, for tests.
+    pub assert_eq_verbose: bool,
+    pub doc_hidden: bool,
+    /// `assert(size = n, align = n)`, const assertions on the layout.
+    pub layout: StructLayoutConfig,
+    pub dump: bool,
+    pub construct: ConstructType,
+    pub where_bounds: StructWhereConfig,
+    /// Name and span of every option, in declaration order.
+    pub spans: Vec<(String, Span)>,
+    pub precedence: Precedence,
+    pub declarations: Declarations,
+    pub serde_defaults: bool,
+    pub serde_is_default: bool,
     pub manual_debug: bool,
+    pub debug_fields: bool,
+    /// `DEFAULTS_DOC`, the default values for help texts.
+    pub defaults_doc: bool,
+    /// `fixture`, the value for tests.
+    pub fixture: bool,
+    /// `__datastruct_tests_<struct>`, smoke tests of the generated code.
+    pub gen_tests: bool,
+    pub lerp: bool,
+    /// Types of `map_type(...)`, each getting a `MapFieldsOfType` implementation.
+    pub map_types: Vec<Type>,
+    /// `Clone`, with the fields' `clone` expressions.
+    pub clone: bool,
+    /// `clone_with_default`, resetting the fields with `clone = false`.
+    pub clone_with_default: bool,
+    /// `<Struct>Dirty` and the tracked setters.
+    pub dirty: bool,
+    /// `<Struct>Snapshot` with `snapshot` and `restore`.
+    pub snapshot: bool,
+    /// `extern "C"` shims of the accessors.
+    pub ffi: StructFfiConfig,
+    /// JavaScript accessors with `wasm_bindgen`.
+    pub wasm: bool,
+    /// `record_fields` into `tracing` spans.
+    pub tracing: bool,
+    /// `to_map` and `from_map`.
+    pub string_map: bool,
+    pub zip_with: bool,
+    pub cow: bool,
+    pub tuple: bool,
+    pub into_parts: PartsType,
+    pub convert: StructConvertConfig,
+    pub reflect: StructReflectConfig,
+    pub visit: bool,
+    /// `<Struct>Field` and `<Struct>FieldValue` enums.
+    pub field_enum: bool,
+    pub computed: Vec<ComputedField>,
+    pub consts: Vec<StructConst>,
+    /// Field configuration shared by several fields.
+    pub groups: Vec<FieldGroup>,
+    pub invariant: Option<StructInvariantConfig>,
+    pub arbitrary: Option<StructArbitraryConfig>,
+    pub merge: bool,
+    /// The strategy of fields without `merge` configuration.
+    pub merge_default: MergeStrategy,
     pub override_auto_get: GetterType,
     pub override_auto_set: SetterType,
     pub cmp: StructCmpConfig,
-    pub ops: StructOpsConfig
+    pub ops: StructOpsConfig,
+    pub patch: StructPatchConfig,
+    pub builder: StructBuilderConfig,
+    /// Path of the runtime crate used by the generated code.
+    pub crate_path: Path,
 }
 
 impl StructConfig {
     pub fn from_attribute(
         attrs: Vec<Attribute>,
-        parent_span: Span,
     ) -> Result<(Self, Vec<Attribute>), syn::Error> {
         let mut config = StructConfig {
             generate_default: false,
+            env_overrides: Default::default(),
             const_default: false,
+            const_check: false,
             impl_std_default: false,
             partial_default: false,
+            zeroed: false,
+            random: false,
+            cache_key: false,
+            diff_display: false,
+            assert_eq_verbose: false,
+            doc_hidden: false,
+            layout: Default::default(),
+            dump: false,
+            construct: ConstructType::Literal,
+            where_bounds: Default::default(),
+            spans: Vec::new(),
+            precedence: Default::default(),
+            declarations: Default::default(),
+            serde_defaults: false,
+            serde_is_default: false,
             manual_debug: false,
+            debug_fields: false,
+            defaults_doc: false,
+            fixture: false,
+            gen_tests: false,
+            lerp: false,
+            map_types: Vec::new(),
+            clone: false,
+            clone_with_default: false,
+            dirty: false,
+            snapshot: false,
+            ffi: Default::default(),
+            wasm: false,
+            tracing: false,
+            string_map: false,
+            zip_with: false,
+            cow: false,
+            tuple: false,
+            into_parts: PartsType::No,
+            convert: Default::default(),
+            reflect: Default::default(),
+            visit: false,
+            field_enum: false,
+            computed: Vec::new(),
+            consts: Vec::new(),
+            groups: Vec::new(),
+            invariant: None,
+            arbitrary: None,
+            merge: false,
+            merge_default: Default::default(),
             override_auto_get: GetterType::No,
             override_auto_set: SetterType::No,
             cmp: Default::default(),
             ops: Default::default(),
+            patch: Default::default(),
+            builder: Default::default(),
+            crate_path: parse_quote! { ::datastruct },
         };
 
         let mut avec = Vec::with_capacity(attrs.len());
+        let mut err: Option<syn::Error> = None;
+        match Precedence::from_attrs(&attrs) {
+            Ok(precedence) => config.precedence = precedence,
+            Err(e) => err.update_or_combine(e),
+        }
         for attr in attrs {
-            if let Ok(Some(ml)) = meta_list_from_attr(&attr) {
-                for meta in ml.nested {
-                    if let NestedMeta::Meta(meta) = meta {
-                        if meta.path().is_ident("default") {
-                            match meta {
-                                Meta::Path(_) => config.generate_default = true,
-                                Meta::NameValue(
-                                    MetaNameValue {
-                                        lit: Lit::Bool(lit),
-                                        ..
-                                    }) => config.generate_default = lit.value,
-                                _ => return Err(syn::Error::new(
-                                    meta.span(),
-                                    "`default` argument should be like `default = true` or simply `default`",
-                                ))
-                            };
-                            continue;
-                        } else if meta.path().is_ident("const") {
-                            match meta {
-                                Meta::Path(_) => config.const_default = true,
-                                Meta::NameValue(
-                                    MetaNameValue {
-                                        lit: Lit::Bool(lit),
-                                        ..
-                                    }) => config.const_default = lit.value,
-                                _ => return Err(syn::Error::new(
-                                    meta.span(),
-                                    "`const` argument should be like `const = true` or simply `const`",
-                                ))
-                            }
-                            continue;
-                        } else if meta.path().is_ident("std_default") {
-                            match meta {
-                                Meta::Path(_) => config.impl_std_default = true,
-                                Meta::NameValue(
-                                    MetaNameValue {
-                                        lit: Lit::Bool(lit),
-                                        ..
-                                    }) => config.impl_std_default = lit.value,
-                                _ => return Err(syn::Error::new(
-                                    meta.span(),
-                                    "`std_default` argument should be like `std_default = true` or simply `std_default`",
-                                ))
-                            }
-                            continue;
-                        } else if meta.path().is_ident("debug") {
-                            match meta {
-                                Meta::Path(_) => config.manual_debug = true,
-                                Meta::NameValue(
-                                    MetaNameValue {
-                                        lit: Lit::Bool(lit),
-                                        ..
-                                    }) => config.manual_debug = lit.value,
-                                _ => return Err(syn::Error::new(
-                                    meta.span(),
-                                    "`debug` argument should be like `debug = true` or simply `debug`",
-                                ))
-                            }
-                            continue;
-                        } else if meta.path().is_ident("partial") {
-                            match meta {
-                                Meta::Path(_) => config.partial_default = true,
-                                Meta::NameValue(
-                                    MetaNameValue {
-                                        lit: Lit::Bool(lit),
-                                        ..
-                                    }) => config.const_default = lit.value,
-                                _ => return Err(syn::Error::new(
-                                    meta.span(),
-                                    "`partial` argument should be like `partial = true` or simply `partial`",
-                                ))
-                            }
-                            continue;
-                        } else if meta.path().is_ident("set") {
-                            match meta {
-                                Meta::Path(_) => config.override_auto_set = Default::default(),
-                                Meta::NameValue(
-                                    MetaNameValue {
-                                        lit: Lit::Str(lit), ..
-                                    }) => {
-                                    config.override_auto_set = SetterType::from_str(lit.value())
-                                        .ok_or_else(|| {
-                                            syn::Error::new(lit.span(), "unknown `set` type")
-                                        })?
-                                }
-                                _ => return Err(syn::Error::new(
-                                    meta.span(),
-                                    "invalid `set` value, see the documentation for more information",
-                                ))
-                            }
-                            continue;
-                        } else if meta.path().is_ident("get") {
-                            match meta {
-                                Meta::Path(_) => config.override_auto_get = Default::default(),
-                                Meta::NameValue(
-                                    MetaNameValue {
-                                        lit: Lit::Str(lit), ..
-                                    }) => {
-                                    config.override_auto_get = GetterType::from_str(lit.value())
-                                        .ok_or_else(|| {
-                                            syn::Error::new(lit.span(), "unknown `get` type")
-                                        })?
-                                }
-                                _ => return Err(syn::Error::new(
-                                    meta.span(),
-                                    "invalid `get` value, see the documentation for more information",
-                                ))
-                            }
-                            continue;
-                        } else if meta.path().is_ident("cmp") {
-                            match meta {
-                                Meta::List(ml) => {
-                                    collect_meta_set(&ml, |item, span| {
-                                        match item {
-                                            "eq" => config.cmp.eq = true,
-                                            "peq" | "partial_eq" => config.cmp.partial_eq = true,
-                                            "ord" | "cmp" => config.cmp.ord = true,
-                                            "partial_ord" | "pord" | "partial_cmp" | "pcmp" => config.cmp.partial_ord = true,
-                                            _ => return Err(syn::Error::new(span, "invalid `cmp` value"))
-                                        };
-                                        Ok(())
-                                    })?;
-                                }
-                                _ => return Err(syn::Error::new(
-                                    meta.span(),
-                                    "invalid `cmp` value, see the documentation for more information",
-                                ))
-                            }
-                        } else if meta.path().is_ident("ops") {
-                            match meta {
-                                Meta::List(ml) => config.ops.mut_and(StructOpsConfig::from_meta(&ml)?),
-                                _ => return Err(syn::Error::new(
-                                    meta.span(),
-                                    "invalid `cmp` value, see the documentation for more information",
-                                ))
-                            }
+            if attr.path().is_ident("dstruct") {
+                match &attr.meta {
+                    Meta::List(ml) => {
+                        if let Err(e) = parse_options(ml.tokens.clone(), |meta| config.parse_declared(meta)) {
+                            err.update_or_combine(e);
                         }
                     }
+                    meta => err.update_or_combine(syn::Error::new(
+                        meta.span(),
+                        "`dstruct` attribute should be like `#[dstruct(...)]`",
+                    )),
                 }
             }
 
             avec.push(attr);
         }
+        err.ok_or(()).swap()?;
 
-        if (config.generate_default || config.const_default) && config.partial_default {
-            return Err(syn::Error::new(
-                parent_span,
-                "partial default does nothing if all fields have default values.",
-            ));
+        Ok((config, avec))
+    }
+
+    /// Resolves renamed and repeated options before parsing them.
+    fn parse_declared(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        let value = option_tokens(meta.input);
+        if let Some(resolved) = resolve_alias(&meta.path, &value, STRUCT_ALIASES, &mut self.declarations.warnings) {
+            skip_option(meta.input)?;
+            return parse_options(resolved, |meta| self.parse_declared(meta));
+        }
+        if !self.declarations.accept(&meta.path, &value, self.precedence, STRUCT_REPEATABLE_OPTIONS, false) {
+            return skip_option(meta.input);
         }
+        self.parse_option(meta)
+    }
 
-        Ok((config, avec))
+    /// Parses one option of `#[dstruct(...)]` into the configuration.
+    fn parse_option(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        self.spans.push((meta.path.to_token_stream().to_string(), meta.path.span()));
+        options::parse_option(self, &meta, "dstruct", STRUCT_OPTIONS)
     }
-}
 
-fn meta_list_from_attr(attr: &Attribute) -> syn::Result<Option<MetaList>> {
-    if let Meta::List(meta_list) = attr.parse_meta()? {
-        if meta_list.path.is_ident("dstruct") {
-            return Ok(Some(meta_list));
+    /// Span of the last declaration of the option.
+    pub fn span_of(&self, name: &str) -> Option<Span> {
+        self.spans.iter().rev().find(|(n, _)| n == name).map(|(_, span)| *span)
+    }
+
+    /// Enables the options bundled by the preset.
+    /// Options declared after the preset can still override them.
+    fn apply_preset(&mut self, lit: &LitStr) -> syn::Result<()> {
+        match lit.value().as_str() {
+            // `cmp(peq, eq, hash), debug, get`
+            "value_object" => {
+                if !cfg!(feature = "cmp") {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        "`value_object` preset requires the `cmp` feature of `datastruct`",
+                    ));
+                }
+                self.cmp.partial_eq = true;
+                self.cmp.eq = true;
+                self.cmp.hash = true;
+                self.manual_debug = true;
+                self.override_auto_get = Default::default();
+            }
+            // `default, set, get, debug`
+            "dto" => {
+                self.generate_default = true;
+                self.override_auto_set = Default::default();
+                self.override_auto_get = Default::default();
+                self.manual_debug = true;
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    "unknown preset, expect `value_object` or `dto`",
+                ))
+            }
         }
+        Ok(())
     }
+}
+
+/// Every `dstruct` option.
+const STRUCT_OPTIONS: &[OptionSpec<StructConfig>] = &[
+    OptionSpec {
+        name: "precedence",
+        synonyms: &[],
+        usage: &["precedence = \"first\""],
+        // parsed ahead of the other options
+        parse: |_, meta| Ok(skip_option(meta.input)?),
+    },
+    OptionSpec {
+        name: "crate",
+        synonyms: &[],
+        usage: &["crate = \"::path::to::datastruct\""],
+        parse: |config, meta| {
+            config.crate_path = options::value::<LitStr>(meta)?.parse()?;
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "preset",
+        synonyms: &[],
+        usage: &["preset = \"value_object\""],
+        parse: |config, meta| Ok(config.apply_preset(&options::value(meta)?)?),
+    },
+    OptionSpec {
+        name: "default",
+        synonyms: &[],
+        usage: &["default", "default = false", "default(bound = \"T: Bound\")", "default(env_overrides)"],
+        parse: |config, meta| {
+            if !is_list(meta) {
+                config.generate_default = options::flag(meta)?;
+                return Ok(());
+            }
+            config.generate_default = true;
+            parse_nested_options(meta, |meta| {
+                if meta.path.is_ident("bound") && is_value(&meta) {
+                    config.where_bounds.default.extend(parse_predicates(&str_value(&meta)?)?);
+                } else if !config.env_overrides.parse_arg(&meta)? {
+                    return Err(meta.error(
+                        "invalid `default` argument, expect `bound = \"...\"`, `env_overrides` or `env_prefix = \"...\"`",
+                    ));
+                }
+                Ok(())
+            })?;
+            config.env_overrides.check()?;
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "const",
+        synonyms: &[],
+        usage: &["const", "const = false", "const(check)"],
+        parse: |config, meta| {
+            if !is_list(meta) {
+                config.const_default = options::flag(meta)?;
+                return Ok(());
+            }
+            config.const_default = true;
+            parse_nested_options(meta, |meta| {
+                if meta.path.is_ident("check") && is_flag(&meta) {
+                    config.const_check = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("invalid `const` argument, expect `check`"))
+                }
+            })?;
+            Ok(())
+        },
+    },
+    flag_option!("std_default" => impl_std_default),
+    flag_option!("debug" => manual_debug),
+    flag_option!("debug_fields" => debug_fields),
+    flag_option!("defaults_doc" => defaults_doc),
+    flag_option!("fixture" => fixture),
+    flag_option!("gen_tests" => gen_tests),
+    flag_option!("serde_defaults" => serde_defaults),
+    flag_option!("serde_is_default" => serde_is_default),
+    flag_option!("zeroed" => zeroed),
+    flag_option!("random" => random, feature = "rand"),
+    flag_option!("cache_key" => cache_key, feature = "cmp"),
+    flag_option!("diff_display" => diff_display, feature = "cmp"),
+    flag_option!("assert_eq_verbose" => assert_eq_verbose, feature = "testing"),
+    OptionSpec {
+        name: "construct",
+        synonyms: &[],
+        usage: &["construct = \"default\""],
+        parse: |config, meta| {
+            config.construct = options::named(meta, |s| ConstructType::from_str(s))?;
+            Ok(())
+        },
+    },
+    flag_option!("doc_hidden" => doc_hidden),
+    OptionSpec {
+        name: "assert",
+        synonyms: &[],
+        usage: &["assert(size = 64, align = 8)"],
+        parse: |config, meta| {
+            options::list(meta)?;
+            config.layout = StructLayoutConfig::from_meta(meta)?;
+            Ok(())
+        },
+    },
+    flag_option!("dump" => dump),
+    flag_option!("lerp" => lerp),
+    OptionSpec {
+        name: "map_type",
+        synonyms: &[],
+        usage: &["map_type(\"f64\")", "map_type(\"f32\", \"f64\")"],
+        parse: |config, meta| {
+            options::list(meta)?;
+            let types = Punctuated::<LitStr, Token![,]>::parse_terminated
+                .parse2(list_tokens(meta)?)
+                .map_err(|e| syn::Error::new(e.span(), "`map_type` expects types like `map_type(\"f64\")`"))?;
+            for lit in types {
+                config.map_types.push(lit.parse()?);
+            }
+            Ok(())
+        },
+    },
+    flag_option!("clone" => clone),
+    flag_option!("clone_with_default" => clone_with_default),
+    flag_option!("dirty" => dirty),
+    flag_option!("snapshot" => snapshot),
+    flag_option!("string_map" => string_map),
+    flag_option!("zip_with" => zip_with),
+    flag_option!("tuple" => tuple),
+    OptionSpec {
+        name: "reflect",
+        synonyms: &[],
+        usage: &["reflect", "reflect = false", "reflect(...)"],
+        parse: |config, meta| {
+            options::require_feature(meta, "reflect", cfg!(feature = "reflect"))?;
+            if is_list(meta) {
+                config.reflect = StructReflectConfig::from_meta(meta)?;
+            } else {
+                config.reflect.enabled = options::flag(meta)?;
+            }
+            Ok(())
+        },
+    },
+    flag_option!("visit" => visit, feature = "reflect"),
+    OptionSpec {
+        name: "ffi",
+        synonyms: &[],
+        usage: &["ffi", "ffi = false", "ffi(prefix = \"name\")"],
+        parse: |config, meta| {
+            if is_list(meta) {
+                config.ffi = StructFfiConfig::from_meta(meta)?;
+            } else {
+                config.ffi.enabled = options::flag(meta)?;
+            }
+            Ok(())
+        },
+    },
+    flag_option!("field_enum" => field_enum, feature = "reflect"),
+    flag_option!("wasm" => wasm, feature = "wasm"),
+    flag_option!("tracing" => tracing, feature = "tracing"),
+    flag_option!("cow" => cow),
+    OptionSpec {
+        name: "merge",
+        synonyms: &[],
+        usage: &["merge", "merge = false", "merge = \"strategy\""],
+        parse: |config, meta| {
+            if is_flag(meta) {
+                config.merge = true;
+                return Ok(());
+            }
+            match options::value(meta)? {
+                Lit::Bool(lit) => config.merge = lit.value,
+                lit => {
+                    config.merge = true;
+                    config.merge_default = MergeStrategy::from_lit(&lit)?;
+                }
+            }
+            Ok(())
+        },
+    },
+    flag_option!("partial" => partial_default),
+    OptionSpec {
+        name: "set",
+        synonyms: &[],
+        usage: &["set", "set = \"with\""],
+        parse: |config, meta| {
+            config.override_auto_set = options::choice(meta, |s| SetterType::from_str(s))?;
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "get",
+        synonyms: &[],
+        usage: &["get", "get = \"move\""],
+        parse: |config, meta| {
+            config.override_auto_get = options::choice(meta, |s| GetterType::from_str(s))?;
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "cmp",
+        synonyms: &[],
+        usage: &["cmp(eq, ord, ...)"],
+        parse: |config, meta| {
+            options::require_feature(meta, "cmp", cfg!(feature = "cmp"))?;
+            options::list(meta)?;
+            collect_meta_set(meta, |item, span| {
+                let key = match item {
+                    "eq" => { config.cmp.eq = true; "eq" }
+                    "peq" | "partial_eq" => { config.cmp.partial_eq = true; "partial_eq" }
+                    "ord" | "cmp" => { config.cmp.ord = true; "ord" }
+                    "partial_ord" | "pord" | "partial_cmp" | "pcmp" => { config.cmp.partial_ord = true; "partial_ord" }
+                    "hash" => { config.cmp.hash = true; "hash" }
+                    "comparator" => { config.cmp.comparator = true; "comparator" }
+                    _ => return Err(unknown_option("cmp", item, span, STRUCT_CMP_OPTIONS))
+                };
+                // nested keys are recorded as `cmp(key)`
+                config.spans.push((format!("cmp({})", key), span));
+                Ok(())
+            })?;
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "patch",
+        synonyms: &[],
+        usage: &["patch", "patch = false", "patch(...)"],
+        parse: |config, meta| {
+            if is_list(meta) {
+                config.patch = StructPatchConfig::from_meta(meta)?;
+            } else {
+                config.patch.enabled = options::flag(meta)?;
+            }
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "builder",
+        synonyms: &[],
+        usage: &["builder", "builder = false", "builder(...)"],
+        parse: |config, meta| {
+            options::require_feature(meta, "builder", cfg!(feature = "builder"))?;
+            if is_list(meta) {
+                config.builder = StructBuilderConfig::from_meta(meta)?;
+            } else {
+                config.builder.enabled = options::flag(meta)?;
+            }
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "into_parts",
+        synonyms: &[],
+        usage: &["into_parts", "into_parts = false", "into_parts(...)"],
+        parse: |config, meta| {
+            config.into_parts = if is_list(meta) {
+                PartsType::from_meta(meta)?
+            } else if options::flag(meta)? {
+                PartsType::Tuple
+            } else {
+                PartsType::No
+            };
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "convert",
+        synonyms: &[],
+        usage: &["convert(...)"],
+        parse: |config, meta| {
+            options::list(meta)?;
+            config.convert = StructConvertConfig::from_meta(meta)?;
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "computed",
+        synonyms: &[],
+        usage: &["computed(...)"],
+        parse: |config, meta| {
+            options::list(meta)?;
+            config.computed.push(ComputedField::from_meta(meta)?);
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "where",
+        synonyms: &[],
+        usage: &["where(...)"],
+        parse: |config, meta| {
+            options::list(meta)?;
+            config.where_bounds.extend_from_meta(meta)?;
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "group",
+        synonyms: &[],
+        usage: &["group(...)"],
+        parse: |config, meta| {
+            options::list(meta)?;
+            config.groups.push(FieldGroup::from_meta(meta)?);
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "consts",
+        synonyms: &[],
+        usage: &["consts(...)"],
+        parse: |config, meta| {
+            options::list(meta)?;
+            config.consts.extend(StructConst::from_meta(meta)?);
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "invariant",
+        synonyms: &[],
+        usage: &["invariant = \"expr\"", "invariant(...)"],
+        parse: |config, meta| {
+            config.invariant = Some(if is_list(meta) {
+                StructInvariantConfig::from_meta(meta)?
+            } else {
+                StructInvariantConfig::from_lit(&options::value(meta)?)?
+            });
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "arbitrary",
+        synonyms: &[],
+        usage: &["arbitrary", "arbitrary = false", "arbitrary(...)"],
+        parse: |config, meta| {
+            config.arbitrary = if is_list(meta) {
+                Some(StructArbitraryConfig::from_meta(meta)?)
+            } else if options::flag(meta)? {
+                Some(StructArbitraryConfig::enabled_features())
+            } else {
+                None
+            };
+            Ok(())
+        },
+    },
+    OptionSpec {
+        name: "ops",
+        synonyms: &[],
+        usage: &["ops(...)"],
+        parse: |config, meta| {
+            options::require_feature(meta, "ops", cfg!(feature = "ops"))?;
+            options::list(meta)?;
+            config.ops.mut_and(StructOpsConfig::from_meta(meta)?);
+            Ok(())
+        },
+    },
+];
+
+/// `dstruct` options which accumulate instead of being overridden.
+const STRUCT_REPEATABLE_OPTIONS: &[&str] = &["cmp", "ops", "convert", "computed", "where", "group", "consts"];
+
+const STRUCT_CMP_OPTIONS: &[&str] = &[
+    "eq", "peq", "partial_eq", "ord", "cmp", "partial_ord", "pord", "partial_cmp", "pcmp", "hash",
+    "comparator",
+];
 
-    Ok(None)
+/// How the generated code constructs the structure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConstructType {
+    /// `Self { field: value, ... }`
+    Literal,
+    /// Assigns the fields of `data_default()` one by one.
+    Default,
+}
+
+impl ConstructType {
+    pub fn from_str(s: impl AsRef<str>) -> Option<Self> {
+        match s.as_ref() {
+            "literal" => Some(Self::Literal),
+            "default" => Some(Self::Default),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,167 @@
+use proc_macro2::{Span, TokenStream as TokenStream2};
+
+use crate::config::struct_config::ConstructType;
+use crate::convert::PartsType;
+use crate::generate::{lock_type, RichStructContent};
+use crate::utils::synerr::{ResultExt, SynErrorExt};
+use crate::utils::warning::warning;
+
+/// Reports every conflicting or ineffective combination of options, at the span of the offending option.
+pub fn check_conflicts(syntax: &RichStructContent) -> syn::Result<()> {
+    let config = &syntax.config;
+    let mut err: Option<syn::Error> = None;
+
+    if config.partial_default && (config.generate_default || config.const_default) {
+        err.update_or_combine(syn::Error::new(
+            syntax.option_span("partial"),
+            "partial default does nothing if all fields have default values.",
+        ));
+    }
+
+    if config.construct == ConstructType::Default && !config.generate_default {
+        err.update_or_combine(syn::Error::new(
+            syntax.option_span("construct"),
+            "`construct = \"default\"` requires `default`",
+        ));
+    }
+
+    if config.reflect.compare && !config.field_enum {
+        err.update_or_combine(syn::Error::new(
+            syntax.option_span("reflect"),
+            "`reflect(compare)` requires `field_enum`",
+        ));
+    }
+
+    if config.const_check && !config.generate_default {
+        err.update_or_combine(syn::Error::new(
+            syntax.option_span("const"),
+            "`const(check)` requires `default`",
+        ));
+    }
+
+    if config.construct == ConstructType::Default {
+        for field in syntax
+            .fields
+            .iter()
+            .filter(|f| f.config.default_value.is_none())
+        {
+            err.update_or_combine(syn::Error::new(
+                field.ident.span(),
+                format!(
+                    "`construct = \"default\"` requires a default value for field `{}`",
+                    field.ident
+                ),
+            ));
+        }
+    }
+
+    // field options taking effect only with the corresponding structure options
+    let cmp = &config.cmp;
+    let struct_options: [(&str, bool, &str); 15] = [
+        ("arbitrary", config.arbitrary.is_some(), "arbitrary"),
+        ("random", config.random, "random"),
+        ("lerp", config.lerp, "lerp"),
+        ("merge", config.merge, "merge"),
+        ("clone", config.clone || config.clone_with_default || config.snapshot, "clone"),
+        ("snapshot", config.snapshot, "snapshot"),
+        ("string_map", config.string_map, "string_map"),
+        ("ffi", config.ffi.enabled, "ffi"),
+        ("env", config.env_overrides.enabled, "default(env_overrides)"),
+        ("fixture", config.fixture, "fixture"),
+        (
+            "tuple",
+            config.tuple || config.into_parts != PartsType::No,
+            "tuple",
+        ),
+        (
+            "debug",
+            config.manual_debug
+                || config.debug_fields
+                || config.reflect.debug
+                || config.tracing
+                || config.diff_display
+                || config.assert_eq_verbose,
+            "debug",
+        ),
+        (
+            "cmp",
+            cmp.eq || cmp.partial_eq || cmp.ord || cmp.partial_ord || cmp.hash || cmp.comparator
+                || config.cache_key
+                || config.diff_display
+                || config.assert_eq_verbose
+                || config.reflect.compare,
+            "cmp",
+        ),
+        ("hash", cmp.hash || config.cache_key, "cmp(hash)"),
+        (
+            "convert",
+            !config.convert.from.is_empty() || !config.convert.try_from.is_empty(),
+            "convert",
+        ),
+    ];
+    for field in &syntax.fields {
+        for (option, enabled, required) in &struct_options {
+            if let (false, Some(span)) = (enabled, field.config.span_of(option)) {
+                err.update_or_combine(ineffective(span, option, required));
+            }
+        }
+        for option in ["try_convert", "validate"] {
+            if let (true, Some(span)) = (
+                config.convert.try_from.is_empty(),
+                field.config.span_of(option),
+            ) {
+                err.update_or_combine(ineffective(span, option, "convert(try_from = ...)"));
+            }
+        }
+        if field.config.do_with_lock && lock_type(&field.field_type).is_none() {
+            err.update_or_combine(syn::Error::new(
+                field.option_span("do_with"),
+                "`do_with = \"lock\"` requires a `std::sync::Mutex<T>` or `std::sync::RwLock<T>` field",
+            ));
+        }
+    }
+
+    err.ok_or(()).swap()
+}
+
+fn ineffective(span: Span, option: &str, required: &str) -> syn::Error {
+    syn::Error::new(
+        span,
+        format!(
+            "`{}` has no effect without `#[dstruct({})]`",
+            option, required
+        ),
+    )
+}
+
+/// Warns about default values that no enabled option makes use of.
+pub fn lint_unused_defaults(syntax: &RichStructContent) -> TokenStream2 {
+    let config = &syntax.config;
+    let used = config.generate_default
+        || config.const_default
+        || config.impl_std_default
+        || config.partial_default
+        || config.builder.enabled
+        || config.serde_defaults
+        || config.serde_is_default
+        || config.arbitrary.is_some()
+        || config.random
+        || config.clone_with_default
+        || config.string_map
+        || config.defaults_doc
+        || config.fixture
+        || config.tuple
+        || config.reflect.enabled
+        || !config.convert.from.is_empty()
+        || !config.convert.try_from.is_empty();
+    let field = match syntax.fields.iter().find(|f| f.config.default_value.is_some()) {
+        Some(field) if !used => field,
+        _ => return TokenStream2::new(),
+    };
+
+    let note = format!(
+        "default values of `{}` are never used, enable `default`, `const`, `std_default`, `partial` or `builder`",
+        syntax.ident
+    );
+    warning(field.option_span("default"), &note)
+}
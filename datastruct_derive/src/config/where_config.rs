@@ -0,0 +1,57 @@
+use syn::meta::ParseNestedMeta;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{LitStr, Token, WherePredicate};
+
+use crate::utils::collect_meta::{parse_nested_options, str_value};
+
+/// Extra bounds of each kind of generated implementations,
+/// e.g. `where(default = "T: Clone", ops = "T: Copy")`.
+#[derive(Default)]
+pub struct StructWhereConfig {
+    /// `DataStruct`, `ConstDataStruct` and `Default`, also added by `default(bound = "...")`.
+    pub default: Vec<WherePredicate>,
+    /// `Debug`, `debug_fields` and `DataStructFields`.
+    pub debug: Vec<WherePredicate>,
+    /// `PartialEq`, `Eq`, `PartialOrd`, `Ord`, `Hash` and `cache_key`.
+    pub cmp: Vec<WherePredicate>,
+    /// The operators.
+    pub ops: Vec<WherePredicate>,
+    pub lerp: Vec<WherePredicate>,
+    pub merge: Vec<WherePredicate>,
+    /// `Clone` and `clone_with_default`.
+    pub clone: Vec<WherePredicate>,
+    /// `From` and `TryFrom`, including the tuple conversions.
+    pub convert: Vec<WherePredicate>,
+}
+
+impl StructWhereConfig {
+    pub fn extend_from_meta(&mut self, meta: &ParseNestedMeta) -> syn::Result<()> {
+        parse_nested_options(meta, |meta| {
+            let target = match meta.path.get_ident().map(|i| i.to_string()).as_deref() {
+                Some("default") => &mut self.default,
+                Some("debug") => &mut self.debug,
+                Some("cmp") => &mut self.cmp,
+                Some("ops") => &mut self.ops,
+                Some("lerp") => &mut self.lerp,
+                Some("merge") => &mut self.merge,
+                Some("clone") => &mut self.clone,
+                Some("convert") => &mut self.convert,
+                _ => return Err(meta.error(
+                    "unknown `where` kind, expect one of `default`, `debug`, `cmp`, `ops`, `lerp`, `merge`, `clone` or `convert`",
+                )),
+            };
+            let lit = str_value(&meta)
+                .map_err(|_| meta.error("`where` argument should be like `where(kind = \"T: Bound\")`"))?;
+            target.extend(parse_predicates(&lit)?);
+            Ok(())
+        })
+    }
+}
+
+/// Parses the predicates written as a string, like `"T: Clone, U: Copy"`.
+pub fn parse_predicates(lit: &LitStr) -> syn::Result<Punctuated<WherePredicate, Token![,]>> {
+    Punctuated::<WherePredicate, Token![,]>::parse_terminated
+        .parse_str(&lit.value())
+        .map_err(|e| syn::Error::new(lit.span(), e))
+}
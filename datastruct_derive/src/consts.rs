@@ -0,0 +1,103 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::meta::ParseNestedMeta;
+use syn::{parse_quote, Expr, ExprLit, Ident, Lit, Type};
+
+use crate::utils::collect_meta::{is_list, is_value, parse_expr, parse_nested_options, str_value};
+
+/// An associated constant of the structure.
+pub struct StructConst {
+    pub name: Ident,
+    pub ty: Type,
+    pub value: Expr,
+}
+
+impl StructConst {
+    /// `consts(NAME = "literal", NAME(ty = "Type", value = "expr"))`
+    pub fn from_meta(meta: &ParseNestedMeta) -> syn::Result<Vec<Self>> {
+        let mut consts = Vec::new();
+
+        parse_nested_options(meta, |meta| {
+            let name = meta
+                .path
+                .get_ident()
+                .ok_or_else(|| syn::Error::new(meta.path.span(), "constant name must be an ident"))?
+                .clone();
+            if is_list(&meta) {
+                consts.push(Self::from_typed(name, &meta)?);
+            } else if is_value(&meta) {
+                let value = parse_expr(&meta)?;
+                let ty = Self::infer_type(&value)?;
+                consts.push(Self { name, ty, value });
+            } else {
+                return Err(meta.error("invalid `consts` argument, see the documentation for more information"));
+            }
+            Ok(())
+        })?;
+
+        Ok(consts)
+    }
+
+    fn from_typed(name: Ident, meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut ty = None;
+        let mut value = None;
+
+        parse_nested_options(meta, |meta| {
+            let invalid = || meta.error("invalid constant argument, expect `ty` or `value`");
+            if meta.path.is_ident("ty") && is_value(&meta) {
+                ty = Some(str_value(&meta).map_err(|_| invalid())?.parse::<Type>()?);
+            } else if meta.path.is_ident("value") && is_value(&meta) {
+                value = Some(parse_expr(&meta)?);
+            } else {
+                return Err(invalid());
+            }
+            Ok(())
+        })?;
+
+        let value = value.ok_or_else(|| meta.error(format!("constant `{}` requires `value`", name)))?;
+        let ty = match ty {
+            Some(ty) => ty,
+            None => Self::infer_type(&value)?,
+        };
+
+        Ok(Self { name, ty, value })
+    }
+
+    // Follows the language's defaults, i.e. `i32` and `f64` for unsuffixed numbers.
+    fn infer_type(value: &Expr) -> syn::Result<Type> {
+        let unknown = || {
+            syn::Error::new(
+                value.span(),
+                "cannot infer the type of the constant, use `NAME(ty = \"Type\", value = \"expr\")` instead",
+            )
+        };
+        let ty = match value {
+            Expr::Lit(ExprLit { lit, .. }) => match lit {
+                Lit::Str(_) => parse_quote! { &'static str },
+                Lit::ByteStr(_) => parse_quote! { &'static [u8] },
+                Lit::Byte(_) => parse_quote! { u8 },
+                Lit::Char(_) => parse_quote! { char },
+                Lit::Bool(_) => parse_quote! { bool },
+                Lit::Int(int) => match int.suffix() {
+                    "" => parse_quote! { i32 },
+                    suffix => syn::parse_str(suffix)?,
+                },
+                Lit::Float(float) => match float.suffix() {
+                    "" => parse_quote! { f64 },
+                    suffix => syn::parse_str(suffix)?,
+                },
+                _ => return Err(unknown()),
+            },
+            _ => return Err(unknown()),
+        };
+        Ok(ty)
+    }
+
+    pub fn to_code(&self) -> TokenStream2 {
+        let Self { name, ty, value } = self;
+        quote! {
+            pub const #name: #ty = #value;
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::meta::ParseNestedMeta;
+use syn::{Lit, LitStr, Type};
+
+use crate::generate::RichStructContent;
+use crate::utils::collect_meta::{is_flag, is_value, parse_nested_options, str_value};
+use crate::utils::hygiene::{local, parse_template};
+use crate::utils::synerr::{ResultExt, SynErrorExt};
+
+/// Generate `From<(T1, T2, ...)>` for the structure and `From<Self>` for the tuple.
+///
+/// Fields with `#[dfield(tuple = false)]` are not part of the tuple and are defaulted.
+pub fn impl_tuple(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+    let ident = &syntax.ident;
+    let generics = syntax.generics_for(&syntax.config.where_bounds.convert);
+    let (impl_g, type_g, where_clause) = generics.split_for_impl();
+
+    let mut err: Option<syn::Error> = None;
+    for field in syntax.fields.iter().filter(|f| !f.config.tuple) {
+        if field.config.default_value.is_none() {
+            err.update_or_combine(syn::Error::new(
+                field.option_span("tuple"),
+                "fields excluded from `tuple` must have a default value",
+            ));
+        }
+    }
+    err.ok_or(()).swap()?;
+
+    let (included, excluded): (Vec<_>, Vec<_>) =
+        syntax.fields.iter().partition(|f| f.config.tuple);
+    let idents = included.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    let types = included.iter().map(|f| &f.field_type).collect::<Vec<_>>();
+    let defaults = excluded.iter().map(|f| {
+        let field_ident = &f.ident;
+        // SAFETY: checked above
+        let value = f.config.default_value.as_ref().unwrap();
+        quote! { #field_ident: #value }
+    });
+    let construct = syntax.construct(idents.iter().map(|i| quote! { #i }).chain(defaults));
+
+    Ok(quote! {
+        impl #impl_g ::std::convert::From<(#(#types,)*)> for #ident #type_g #where_clause {
+            fn from((#(#idents,)*): (#(#types,)*)) -> Self {
+                #construct
+            }
+        }
+
+        impl #impl_g ::std::convert::From<#ident #type_g> for (#(#types,)*) #where_clause {
+            fn from(value: #ident #type_g) -> Self {
+                (#(value.#idents,)*)
+            }
+        }
+    })
+}
+
+/// Output of the generated `into_parts` method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PartsType {
+    #[default]
+    No,
+    /// `(T1, T2, ...)`
+    Tuple,
+    /// A companion `<Struct>Parts` structure with public fields.
+    Named,
+}
+
+impl PartsType {
+    pub fn from_meta(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut config = Self::Tuple;
+
+        parse_nested_options(meta, |meta| {
+            if meta.path.is_ident("named") && is_flag(&meta) {
+                config = Self::Named;
+            } else if meta.path.is_ident("tuple") && is_flag(&meta) {
+                config = Self::Tuple;
+            } else {
+                return Err(meta.error("invalid `into_parts` argument, see the documentation for more information"));
+            }
+            Ok(())
+        })?;
+
+        Ok(config)
+    }
+}
+
+/// Generate `into_parts(self)`, moving every field out of the structure at once.
+pub fn impl_into_parts(syntax: &RichStructContent) -> TokenStream2 {
+    let ident = &syntax.ident;
+    let vis = &syntax.vis;
+    let generics = &syntax.generics;
+    let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+    let idents = syntax.fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    let types = syntax.fields.iter().map(|f| &f.field_type).collect::<Vec<_>>();
+
+    match syntax.config.into_parts {
+        PartsType::No => Default::default(),
+        PartsType::Tuple => quote! {
+            impl #impl_g #ident #type_g #where_clause {
+                /// Take the ownership of all fields, in declaration order.
+                #vis fn into_parts(self) -> (#(#types,)*) {
+                    (#(self.#idents,)*)
+                }
+            }
+        },
+        PartsType::Named => {
+            let parts_ident = format_ident!("{}Parts", ident);
+            let doc = format!("All fields of [`{}`], see `{}::into_parts`.", ident, ident);
+            quote! {
+                #[doc = #doc]
+                #vis struct #parts_ident #generics #where_clause {
+                    #(pub #idents: #types),*
+                }
+
+                impl #impl_g #ident #type_g #where_clause {
+                    /// Take the ownership of all fields.
+                    #vis fn into_parts(self) -> #parts_ident #type_g {
+                        #parts_ident {
+                            #(#idents: self.#idents),*
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct StructConvertConfig {
+    /// Source types of the generated `From` implementations.
+    pub from: Vec<Type>,
+    /// Source types of the generated `TryFrom` implementations.
+    pub try_from: Vec<Type>,
+    /// Error type of the `TryFrom` implementations.
+    pub error: Option<Type>,
+}
+
+impl StructConvertConfig {
+    pub fn from_meta(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut config: Self = Default::default();
+
+        parse_nested_options(meta, |meta| {
+            let invalid = || meta.error("invalid `convert` argument, see the documentation for more information");
+            if !is_value(&meta) {
+                return Err(invalid());
+            }
+            let ty = str_value(&meta).map_err(|_| invalid())?.parse::<Type>()?;
+            if meta.path.is_ident("from") {
+                config.from.push(ty);
+            } else if meta.path.is_ident("try_from") {
+                config.try_from.push(ty);
+            } else if meta.path.is_ident("error") {
+                config.error = Some(ty);
+            } else {
+                return Err(invalid());
+            }
+            Ok(())
+        })?;
+
+        if config.error.is_some() && config.try_from.is_empty() {
+            return Err(meta.error("`error` can only be used together with `try_from`"));
+        }
+
+        Ok(config)
+    }
+}
+
+/// How a field is initialized from the source type of a conversion.
+#[derive(Clone, Default)]
+pub enum FieldConvertConfig {
+    /// `Into::into(from.field)`
+    #[default]
+    Inherit,
+    /// Use the default value of the field.
+    Default,
+    Manual(LitStr),
+}
+
+impl FieldConvertConfig {
+    pub fn from_lit(lit: &Lit) -> syn::Result<Self> {
+        match lit {
+            Lit::Str(lit_str) => match lit_str.value().as_str() {
+                "inherit" | "same" => Ok(Self::Inherit),
+                "default" | "no" => Ok(Self::Default),
+                _ => Ok(Self::Manual(lit_str.clone())),
+            },
+            Lit::Bool(lit_bool) => {
+                if lit_bool.value {
+                    Ok(Self::Inherit)
+                } else {
+                    Ok(Self::Default)
+                }
+            }
+            _ => Err(syn::Error::new(lit.span(), "invalid `convert` value")),
+        }
+    }
+}
+
+// Statements binding every field to a local variable of the same name.
+// Custom expressions are evaluated first, before the other fields are moved out of the source.
+fn field_bindings(syntax: &RichStructContent, fallible: bool) -> syn::Result<Vec<TokenStream2>> {
+    let mut manual = Vec::new();
+    let mut moved = Vec::new();
+    let mut validations = Vec::new();
+    let mut err: Option<syn::Error> = None;
+
+    let from = local("from");
+    for field in &syntax.fields {
+        let field_ident = &field.ident;
+        let ty = &field.field_type;
+        let parse = |s: &LitStr| parse_template(s, &[("value", field_ident), ("from", &from)]);
+
+        match (&field.config.try_convert, &field.config.convert) {
+            (Some(s), _) if fallible => match parse(s) {
+                Ok(expr) => manual.push(quote! { let #field_ident: #ty = (#expr)?; }),
+                Err(e) => err.update_or_combine(e),
+            },
+            (_, FieldConvertConfig::Inherit) => moved.push(quote! {
+                let #field_ident: #ty = ::std::convert::Into::into(#from.#field_ident);
+            }),
+            (_, FieldConvertConfig::Default) => match &field.config.default_value {
+                Some(value) => moved.push(quote! { let #field_ident: #ty = #value; }),
+                None => err.update_or_combine(syn::Error::new(
+                    field_ident.span(),
+                    "fields not converted from the source type must have a default value",
+                )),
+            },
+            (_, FieldConvertConfig::Manual(s)) => match parse(s) {
+                Ok(expr) => manual.push(quote! { let #field_ident: #ty = #expr; }),
+                Err(e) => err.update_or_combine(e),
+            },
+        }
+
+        if let (true, Some(s)) = (fallible, &field.config.validate) {
+            match parse(s) {
+                Ok(expr) => {
+                    let msg = format!("validation of field `{}` failed", field_ident.unraw());
+                    validations.push(quote! {
+                        if !(#expr) {
+                            return ::core::result::Result::Err(::core::convert::From::from(
+                                ::std::string::String::from(#msg),
+                            ));
+                        }
+                    })
+                }
+                Err(e) => err.update_or_combine(e),
+            }
+        }
+    }
+
+    err.ok_or(()).swap()?;
+
+    manual.extend(moved);
+    manual.extend(validations);
+    Ok(manual)
+}
+
+pub fn impl_convert_from(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+    let ident = &syntax.ident;
+    let generics = syntax.generics_for(&syntax.config.where_bounds.convert);
+    let (impl_g, type_g, where_clause) = generics.split_for_impl();
+    let construct = syntax.construct(syntax.fields.iter().map(|f| {
+        let ident = &f.ident;
+        quote! { #ident }
+    }));
+
+    let from = local("from");
+    let bindings = field_bindings(syntax, false)?;
+    let impls = syntax.config.convert.from.iter().map(|from_ty| {
+        quote! {
+            impl #impl_g ::std::convert::From<#from_ty> for #ident #type_g #where_clause {
+                #[allow(unused_variables)]
+                fn from(#from: #from_ty) -> Self {
+                    #(#bindings)*
+                    #construct
+                }
+            }
+        }
+    });
+
+    let bindings = field_bindings(syntax, true)?;
+    let error = syntax
+        .config
+        .convert
+        .error
+        .as_ref()
+        .map(|ty| quote! { #ty })
+        .unwrap_or_else(|| {
+            quote! { ::std::boxed::Box<dyn ::std::error::Error + ::core::marker::Send + ::core::marker::Sync> }
+        });
+    let value = local("value");
+    let check_invariant = if syntax.config.invariant.is_some() {
+        quote! { #value.check_invariant()?; }
+    } else {
+        Default::default()
+    };
+    let try_impls = syntax.config.convert.try_from.iter().map(|from_ty| {
+        quote! {
+            impl #impl_g ::std::convert::TryFrom<#from_ty> for #ident #type_g #where_clause {
+                type Error = #error;
+
+                #[allow(unused_variables)]
+                fn try_from(#from: #from_ty) -> ::core::result::Result<Self, Self::Error> {
+                    #(#bindings)*
+                    let #value = #construct;
+                    #check_invariant
+                    ::core::result::Result::Ok(#value)
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        #(#impls)*
+
+        #(#try_impls)*
+    })
+}
@@ -0,0 +1,98 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens};
+use syn::{parse_quote, GenericParam, Type, WherePredicate};
+
+use crate::generate::RichStructContent;
+use crate::utils::generics::type_uses_params;
+
+fn is_cow(ty: &Type) -> bool {
+    match ty {
+        Type::Path(pth) => pth
+            .path
+            .segments
+            .last()
+            .is_some_and(|seg| seg.ident == "Cow"),
+        _ => false,
+    }
+}
+
+// every lifetime of the structure is replaced with `lifetime`
+fn replaced_args(syntax: &RichStructContent, lifetime: TokenStream2) -> Vec<TokenStream2> {
+    syntax
+        .generics
+        .params
+        .iter()
+        .map(|p| match p {
+            GenericParam::Type(t) => t.ident.to_token_stream(),
+            GenericParam::Lifetime(_) => lifetime.clone(),
+            GenericParam::Const(c) => c.ident.to_token_stream(),
+        })
+        .collect()
+}
+
+pub fn impl_cow(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+    if syntax.generics.lifetimes().next().is_none() {
+        return Err(syn::Error::new(
+            syntax.option_span("cow"),
+            "`cow` requires the structure to have a lifetime parameter",
+        ));
+    }
+
+    let ident = &syntax.ident;
+    let owned_args = replaced_args(syntax, quote! { 'static });
+    let borrowed_args = replaced_args(syntax, quote! { '_ });
+
+    let owned = syntax.fields.iter().map(|field| {
+        let ident = &field.ident;
+        if is_cow(&field.field_type) {
+            quote! { #ident: ::std::borrow::Cow::Owned(self.#ident.into_owned()) }
+        } else {
+            quote! { #ident: self.#ident }
+        }
+    });
+    let borrowed = syntax.fields.iter().map(|field| {
+        let ident = &field.ident;
+        if is_cow(&field.field_type) {
+            quote! { #ident: ::std::borrow::Cow::Borrowed(&*self.#ident) }
+        } else {
+            quote! { #ident: ::std::clone::Clone::clone(&self.#ident) }
+        }
+    });
+
+    let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+
+    let mut borrowed_generics = syntax.generics.clone();
+    let bounds = syntax
+        .fields
+        .iter()
+        .filter(|f| !is_cow(&f.field_type) && type_uses_params(&f.field_type, &syntax.generics))
+        .map(|f| -> WherePredicate {
+            let ty = &f.field_type;
+            parse_quote! { #ty: ::std::clone::Clone }
+        })
+        .collect::<Vec<_>>();
+    if !bounds.is_empty() {
+        borrowed_generics.make_where_clause().predicates.extend(bounds);
+    }
+    let borrowed_where = &borrowed_generics.where_clause;
+
+    Ok(quote! {
+        impl #impl_g #ident #type_g #where_clause {
+            /// Converts all `Cow` fields into owned values.
+            pub fn into_owned(self) -> #ident<#(#owned_args),*> {
+                #ident {
+                    #(#owned),*
+                }
+            }
+        }
+
+        impl #impl_g #ident #type_g #borrowed_where {
+            /// Borrows all `Cow` fields from `self`, other fields are cloned.
+            pub fn to_borrowed(&self) -> #ident<#(#borrowed_args),*> {
+                #ident {
+                    #(#borrowed),*
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,70 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+
+use crate::generate::RichStructContent;
+use crate::utils::hygiene::local;
+
+/// Generate a `<Struct>Dirty` companion with a flag for every field, and `set_xxx_tracked` setters.
+pub fn impl_dirty(syntax: &RichStructContent) -> TokenStream2 {
+    let ident = &syntax.ident;
+    let vis = &syntax.vis;
+    let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+    let dirty_ident = format_ident!("{}Dirty", ident);
+    let (value, dirty) = (local("value"), local("dirty"));
+
+    let idents = syntax.fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    let names = idents.iter().map(|i| i.unraw().to_string()).collect::<Vec<_>>();
+    let len = idents.len();
+    let flags = idents.iter().zip(&names).map(|(ident, name)| {
+        let doc = format!("Whether `{}` has been modified.", name);
+        quote! {
+            #[doc = #doc]
+            #vis #ident: bool
+        }
+    });
+    let setters = syntax.fields.iter().map(|field| {
+        let field_ident = &field.ident;
+        let ty = &field.field_type;
+        let setter = &field.accessors.set_tracked;
+        let doc = format!("Set `{}` and mark it as modified.", field_ident.unraw());
+        quote! {
+            #[doc = #doc]
+            #vis fn #setter(&mut self, #value: #ty, #dirty: &mut #dirty_ident) {
+                self.#field_ident = #value;
+                #dirty.#field_ident = true;
+            }
+        }
+    });
+    let doc = format!("Modified fields of [`{}`], see the `set_xxx_tracked` setters.", ident);
+
+    quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+        #vis struct #dirty_ident {
+            #(#flags),*
+        }
+
+        impl #dirty_ident {
+            /// Whether no field has been modified.
+            #vis fn is_clean(&self) -> bool {
+                *self == <Self as ::core::default::Default>::default()
+            }
+
+            /// Mark every field as unmodified, e.g. once the changes are persisted.
+            #vis fn clear(&mut self) {
+                *self = ::core::default::Default::default();
+            }
+
+            /// Names of the modified fields, in declaration order.
+            #vis fn changed(&self) -> impl ::core::iter::Iterator<Item = &'static str> {
+                let fields: [(&'static str, bool); #len] = [#((#names, self.#idents)),*];
+                ::core::iter::IntoIterator::into_iter(fields).filter_map(|(name, dirty)| dirty.then_some(name))
+            }
+        }
+
+        impl #impl_g #ident #type_g #where_clause {
+            #(#setters)*
+        }
+    }
+}
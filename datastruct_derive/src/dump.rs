@@ -0,0 +1,33 @@
+use proc_macro2::TokenStream as TokenStream2;
+use syn::Ident;
+
+/// Environment variable listing the structures to dump, separated by commas, or `*` for all of them.
+const DUMP_ENV: &str = "DATASTRUCT_DUMP";
+
+/// Whether the expansion of the structure is requested via [`DUMP_ENV`].
+pub fn dump_requested(ident: &Ident) -> bool {
+    std::env::var(DUMP_ENV).is_ok_and(|var| {
+        var.split(',')
+            .map(str::trim)
+            .any(|name| name == "*" || ident == name)
+    })
+}
+
+/// Prints the expansion to stderr, formatted if the `dump` feature is enabled.
+pub fn dump(ident: &Ident, ts: &TokenStream2) {
+    eprintln!("// ----- `DataStruct` expansion of `{}` -----", ident);
+    eprintln!("{}", format(ts));
+}
+
+#[cfg(feature = "dump")]
+fn format(ts: &TokenStream2) -> String {
+    match syn::parse2(ts.clone()) {
+        Ok(file) => prettyplease::unparse(&file),
+        Err(_) => ts.to_string(),
+    }
+}
+
+#[cfg(not(feature = "dump"))]
+fn format(ts: &TokenStream2) -> String {
+    ts.to_string()
+}
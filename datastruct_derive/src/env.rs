@@ -0,0 +1,96 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::meta::ParseNestedMeta;
+use syn::{parse_quote, LitStr, WherePredicate};
+
+use crate::ffi::snake_case;
+use crate::generate::RichStructContent;
+use crate::utils::collect_meta::{is_flag, is_value, str_value};
+use crate::utils::generics::type_uses_params;
+use crate::utils::hygiene::local;
+
+#[derive(Default)]
+pub struct StructEnvConfig {
+    pub enabled: bool,
+    /// Prefix of the variables, the structure name in `SCREAMING_SNAKE_CASE` by default.
+    pub prefix: Option<LitStr>,
+}
+
+impl StructEnvConfig {
+    /// Parses an argument of `default(...)`, returning whether it's an environment argument.
+    pub fn parse_arg(&mut self, meta: &ParseNestedMeta) -> syn::Result<bool> {
+        if meta.path.is_ident("env_overrides") && is_flag(meta) {
+            self.enabled = true;
+        } else if meta.path.is_ident("env_prefix") && is_value(meta) {
+            self.prefix = Some(str_value(meta)?);
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    pub fn check(&self) -> syn::Result<()> {
+        match (self.enabled, &self.prefix) {
+            (false, Some(prefix)) => Err(syn::Error::new(prefix.span(), "`env_prefix` requires `env_overrides`")),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Generate `data_default_from_env`, overriding the default values with the parsable environment variables.
+pub fn impl_env_overrides(syntax: &RichStructContent) -> TokenStream2 {
+    let krate = &syntax.config.crate_path;
+    let ident = &syntax.ident;
+    let vis = &syntax.vis;
+    let prefix = match &syntax.config.env_overrides.prefix {
+        Some(prefix) => prefix.value(),
+        None => snake_case(&ident.unraw().to_string()).to_uppercase(),
+    };
+    let (this, var, value) = (local("this"), local("var"), local("value"));
+
+    let mut bounds: Vec<WherePredicate> = Vec::new();
+    let overrides = syntax.fields.iter().map(|field| {
+        let field_ident = &field.ident;
+        let ty = &field.field_type;
+        let name = match &field.config.env {
+            Some(name) => name.value(),
+            None => {
+                let field_name = field_ident.unraw().to_string().to_uppercase();
+                if prefix.is_empty() {
+                    field_name
+                } else {
+                    format!("{prefix}_{field_name}")
+                }
+            }
+        };
+        if type_uses_params(ty, &syntax.generics) {
+            bounds.push(parse_quote! { #ty: ::core::str::FromStr });
+        }
+        quote! {
+            if let ::core::result::Result::Ok(#var) = ::std::env::var(#name) {
+                if let ::core::result::Result::Ok(#value) = ::core::str::FromStr::from_str(&#var) {
+                    #this.#field_ident = #value;
+                }
+            }
+        }
+    }).collect::<Vec<_>>();
+
+    let mut generics = syntax.generics_for(&syntax.config.where_bounds.default);
+    if !bounds.is_empty() {
+        generics.make_where_clause().predicates.extend(bounds);
+    }
+    let (impl_g, type_g, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_g #ident #type_g #where_clause {
+            /// Creates the default value, then overrides the fields by the environment variables,
+            /// those unset or failing to parse are kept.
+            #vis fn data_default_from_env() -> Self {
+                let mut #this = <Self as #krate::DataStruct>::data_default();
+                #(#overrides)*
+                #this
+            }
+        }
+    }
+}
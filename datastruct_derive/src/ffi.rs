@@ -0,0 +1,196 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::meta::ParseNestedMeta;
+use syn::spanned::Spanned;
+use syn::{Attribute, GenericArgument, LitStr, PathArguments, Type};
+
+use crate::config::field_config::{GetterType, SetterType};
+use crate::generate::RichStructContent;
+use crate::utils::collect_meta::{is_value, parse_nested_options, str_value};
+use crate::utils::synerr::{ResultExt, SynErrorExt};
+
+#[derive(Default)]
+pub struct StructFfiConfig {
+    pub enabled: bool,
+    /// Prefix of the exported functions, the structure name in `snake_case` by default.
+    pub prefix: Option<LitStr>,
+}
+
+impl StructFfiConfig {
+    pub fn from_meta(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut config = Self {
+            enabled: true,
+            prefix: None,
+        };
+
+        parse_nested_options(meta, |meta| {
+            if meta.path.is_ident("prefix") && is_value(&meta) {
+                config.prefix = Some(str_value(&meta)?);
+            } else {
+                return Err(meta.error("invalid `ffi` argument, expect `prefix = \"...\"`"));
+            }
+            Ok(())
+        })?;
+
+        Ok(config)
+    }
+}
+
+// `HttpServer` -> `http_server`
+pub fn snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len());
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+fn is_repr_c(attrs: &[Attribute]) -> bool {
+    attrs.iter().filter(|attr| attr.path().is_ident("repr")).any(|attr| {
+        let mut found = false;
+        // other representation hints, like `align(8)`, are skipped
+        let _ = attr.parse_nested_meta(|meta| {
+            found |= meta.path.is_ident("C") || meta.path.is_ident("transparent");
+            if meta.input.peek(syn::token::Paren) {
+                let _content;
+                syn::parenthesized!(_content in meta.input);
+            }
+            Ok(())
+        });
+        found
+    })
+}
+
+// A syntactic check of the types which are certainly not FFI-safe.
+// Other paths may name `#[repr(C)]` types, they're left to `improper_ctypes_definitions`.
+fn is_ffi_safe(ty: &Type) -> bool {
+    match ty {
+        Type::Ptr(_) => true,
+        Type::Reference(reference) => reference.mutability.is_none(),
+        Type::BareFn(func) => func.abi.is_some(),
+        Type::Paren(paren) => is_ffi_safe(&paren.elem),
+        Type::Group(group) => is_ffi_safe(&group.elem),
+        Type::Path(pth) if pth.qself.is_none() => {
+            let seg = match pth.path.segments.last() {
+                Some(seg) => seg,
+                None => return false,
+            };
+            match &seg.arguments {
+                PathArguments::None => seg.ident != "char" && seg.ident != "str",
+                PathArguments::AngleBracketed(_) if seg.ident == "NonNull" => true,
+                // `Option` of a non-nullable pointer is a nullable pointer
+                PathArguments::AngleBracketed(args) if seg.ident == "Option" => {
+                    matches!(args.args.first(), Some(GenericArgument::Type(inner)) if is_non_null(inner))
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+fn is_non_null(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) => reference.mutability.is_none(),
+        Type::BareFn(func) => func.abi.is_some(),
+        Type::Paren(paren) => is_non_null(&paren.elem),
+        Type::Path(pth) => pth.path.segments.last().is_some_and(|seg| seg.ident == "NonNull"),
+        _ => false,
+    }
+}
+
+/// Generate `extern "C"` shims of the getters and setters.
+pub fn impl_ffi(syntax: &RichStructContent, config: &StructFfiConfig) -> syn::Result<TokenStream2> {
+    let ident = &syntax.ident;
+    let span = syntax.option_span("ffi");
+    let mut err: Option<syn::Error> = None;
+    if !is_repr_c(&syntax.attrs) {
+        err.update_or_combine(syn::Error::new(span, "`ffi` requires `#[repr(C)]` on the structure"));
+    }
+    if !syntax.generics.params.is_empty() {
+        err.update_or_combine(syn::Error::new(span, "`ffi` doesn't support generic structures"));
+    }
+    err.ok_or(()).swap()?;
+
+    let prefix = match &config.prefix {
+        Some(prefix) => prefix.value(),
+        None => snake_case(&ident.unraw().to_string()),
+    };
+    let this = format_ident!("this");
+    // the shims are free functions, which `doc_hidden` doesn't reach
+    let hidden = if syntax.config.doc_hidden {
+        quote! { #[doc(hidden)] }
+    } else {
+        TokenStream2::new()
+    };
+    let mut err: Option<syn::Error> = None;
+    let mut shims = Vec::new();
+    for field in &syntax.fields {
+        let field_ident = &field.ident;
+        let ty = &field.field_type;
+        let name = field_ident.unraw().to_string();
+        let (get, set) = (field.config.auto_get != GetterType::No, field.config.auto_set != SetterType::No);
+        if !field.config.ffi || !get && !set {
+            continue;
+        }
+        if !is_ffi_safe(ty) {
+            err.update_or_combine(syn::Error::new(
+                ty.span(),
+                "`ffi` requires an FFI-safe field type, like a primitive, a raw pointer or a `#[repr(C)]` type, \
+                 exclude the field with `#[dfield(ffi = false)]`",
+            ));
+            continue;
+        }
+        // reported at the field type, the getter reads the field without moving out of it
+        shims.push(quote_spanned! {
+            ty.span() => const _: () = {
+                const fn __ds_ffi_copy<T: ::core::marker::Copy>() {}
+                __ds_ffi_copy::<#ty>();
+            };
+        });
+        if get {
+            let func = format_ident!("{}_{}", prefix, name);
+            let doc = format!("Returns `{}` of the `{}`.", field_ident, ident);
+            shims.push(quote! {
+                #[doc = #doc]
+                ///
+                /// # Safety
+                ///
+                /// `this` must point to a valid structure.
+                #hidden
+                #[unsafe(no_mangle)]
+                pub unsafe extern "C" fn #func(#this: *const #ident) -> #ty {
+                    ::core::ptr::read(::core::ptr::addr_of!((*#this).#field_ident))
+                }
+            });
+        }
+        if set {
+            let func = format_ident!("{}_set_{}", prefix, name);
+            let doc = format!("Sets `{}` of the `{}`.", field_ident, ident);
+            shims.push(quote! {
+                #[doc = #doc]
+                ///
+                /// # Safety
+                ///
+                /// `this` must point to a valid structure, which is not aliased.
+                #hidden
+                #[unsafe(no_mangle)]
+                pub unsafe extern "C" fn #func(#this: *mut #ident, value: #ty) {
+                    (*#this).#field_ident = value;
+                }
+            });
+        }
+    }
+
+    err.ok_or(()).swap()?;
+
+    Ok(quote! { #(#shims)* })
+}
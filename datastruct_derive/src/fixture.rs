@@ -0,0 +1,47 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+
+use crate::generate::RichStructContent;
+use crate::utils::synerr::{ResultExt, SynErrorExt};
+
+/// Generate `fixture`, a value for tests built from the fields' `fixture` expressions,
+/// falling back to their default values.
+// Like `data_default`, the fields are initialized by `seq`, so the expressions may refer to the previous fields.
+pub fn impl_fixture(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+    let ident = &syntax.ident;
+    let vis = &syntax.vis;
+    let generics = syntax.generics_for(&syntax.config.where_bounds.default);
+    let (impl_g, type_g, where_clause) = generics.split_for_impl();
+
+    let mut stmts = Vec::new();
+    let mut err: Option<syn::Error> = None;
+    for field in &syntax.fields {
+        let field_ident = &field.ident;
+        let ty = &field.field_type;
+        match field.config.fixture.as_ref().or(field.config.default_value.as_ref()) {
+            Some(expr) => stmts.push(quote_spanned! {
+                expr.span() => let #field_ident: #ty = #expr;
+            }),
+            None => err.update_or_combine(syn::Error::new(
+                field_ident.span(),
+                "`fixture` requires a `fixture` expression or a default value for every field",
+            )),
+        }
+    }
+    err.ok_or(()).swap()?;
+
+    let idents = syntax.fields.iter().map(|f| &f.ident);
+    Ok(quote! {
+        #[cfg(test)]
+        impl #impl_g #ident #type_g #where_clause {
+            /// A deterministic value for tests, distinct from the default value.
+            #vis fn fixture() -> Self {
+                #(#stmts)*
+                Self {
+                    #(#idents),*
+                }
+            }
+        }
+    })
+}
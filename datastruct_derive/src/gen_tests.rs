@@ -0,0 +1,182 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+
+use crate::config::field_config::{GetterType, SetterType};
+#[cfg(feature = "ops")]
+use crate::config::ops_config::StructOpsConfig;
+use crate::ffi::snake_case;
+use crate::generate::RichStructContent;
+use crate::utils::hygiene::local;
+
+/// Generate `#[cfg(test)] mod __datastruct_tests_<struct>`, smoke tests of the generated code.
+// Every check is a hidden inherent function, so that `Self` and the crate path resolve as in the other impls,
+// and the module only calls them.
+pub fn impl_gen_tests(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+    let ident = &syntax.ident;
+    if !syntax.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            syntax.option_span("gen_tests"),
+            "`gen_tests` doesn't support generic structures",
+        ));
+    }
+    if !syntax.config.generate_default || syntax.fields.iter().any(|f| f.config.default_value.is_none()) {
+        return Err(syn::Error::new(
+            syntax.option_span("gen_tests"),
+            "`gen_tests` requires `default` and a default value for every field",
+        ));
+    }
+
+    let krate = &syntax.config.crate_path;
+    let default = quote! { <Self as #krate::DataStruct>::data_default() };
+    // the values given to the setters, distinct from the default value when possible
+    let source = if syntax.config.fixture {
+        quote! { Self::fixture() }
+    } else {
+        default.clone()
+    };
+
+    let checks = [
+        ("default_round_trip", default_round_trip(syntax, &default)),
+        ("setter_getter_symmetry", setter_getter_symmetry(syntax, &default, &source)),
+        ("clone_with_default_idempotence", clone_with_default_idempotence(syntax, &source)),
+        ("ops_identity", ops_identity(syntax, &source)),
+    ];
+    let (functions, tests): (Vec<_>, Vec<_>) = checks
+        .into_iter()
+        .filter(|(_, asserts)| !asserts.is_empty())
+        .map(|(name, asserts)| {
+            let test = format_ident!("{}", name);
+            let function = format_ident!("__ds_test_{}", name);
+            (
+                quote! {
+                    #[doc(hidden)]
+                    fn #function() {
+                        #(#asserts)*
+                    }
+                },
+                quote! {
+                    #[test]
+                    fn #test() {
+                        super::#ident::#function();
+                    }
+                },
+            )
+        })
+        .unzip();
+    let module = format_ident!("__datastruct_tests_{}", snake_case(&ident.unraw().to_string()));
+
+    Ok(quote! {
+        #[cfg(test)]
+        impl #ident {
+            #(#functions)*
+        }
+
+        #[cfg(test)]
+        #[doc(hidden)]
+        mod #module {
+            #(#tests)*
+        }
+    })
+}
+
+fn default_round_trip(syntax: &RichStructContent, default: &TokenStream2) -> Vec<TokenStream2> {
+    let config = &syntax.config;
+    let krate = &config.crate_path;
+    let ident = &syntax.ident;
+    let mut asserts = Vec::new();
+    if config.impl_std_default {
+        let message = format!("`{}::default()` differs from `data_default()`", ident);
+        asserts.push(quote! {
+            ::std::assert!(<Self as ::std::default::Default>::default() == #default, #message);
+        });
+    }
+    if config.const_default {
+        let message = format!("`{}::DEFAULT` differs from `data_default()`", ident);
+        asserts.push(quote! {
+            ::std::assert!(<Self as #krate::ConstDataStruct>::DEFAULT == #default, #message);
+        });
+    }
+    if config.clone {
+        let message = format!("a clone of `{}::data_default()` differs from it", ident);
+        asserts.push(quote! {
+            ::std::assert!(::std::clone::Clone::clone(&#default) == #default, #message);
+        });
+    }
+    if config.string_map {
+        let message = format!("`{}::from_map` doesn't restore the map of `data_default()`", ident);
+        asserts.push(quote! {
+            ::std::assert!(Self::from_map(&#default.to_map()).ok() == ::std::option::Option::Some(#default), #message);
+        });
+    }
+    asserts
+}
+
+fn setter_getter_symmetry(
+    syntax: &RichStructContent,
+    default: &TokenStream2,
+    source: &TokenStream2,
+) -> Vec<TokenStream2> {
+    let value = local("value");
+    syntax
+        .fields
+        .iter()
+        .filter_map(|field| {
+            let ident = &field.ident;
+            let accessors = &field.accessors;
+            let get = match field.config.auto_get {
+                GetterType::Full | GetterType::Get => quote! { *#value.#ident() },
+                GetterType::Move => {
+                    let getter = &accessors.r#move;
+                    quote! { #value.#getter() }
+                }
+                GetterType::No => return None,
+            };
+            let (setter, assigned) = match field.config.auto_set {
+                SetterType::Full | SetterType::Set => {
+                    let setter = &accessors.set;
+                    (setter, quote! {
+                        let mut #value = #default;
+                        #value.#setter(#source.#ident);
+                    })
+                }
+                SetterType::With => {
+                    let setter = &accessors.with;
+                    (setter, quote! {
+                        let #value = #default.#setter(#source.#ident);
+                    })
+                }
+                SetterType::No => return None,
+            };
+            let message = format!("`{}.{}` doesn't return the value given to `{}`", syntax.ident, ident.unraw(), setter);
+            Some(quote! {
+                {
+                    #assigned
+                    ::std::assert!(#get == #source.#ident, #message);
+                }
+            })
+        })
+        .collect()
+}
+
+fn clone_with_default_idempotence(syntax: &RichStructContent, source: &TokenStream2) -> Vec<TokenStream2> {
+    if !syntax.config.clone_with_default {
+        return Vec::new();
+    }
+    let value = local("value");
+    let message = format!("`{}::clone_with_default` is not idempotent", syntax.ident);
+    vec![quote! {
+        let #value = #source.clone_with_default();
+        ::std::assert!(#value.clone_with_default() == #value, #message);
+    }]
+}
+
+#[cfg(feature = "ops")]
+fn ops_identity(syntax: &RichStructContent, source: &TokenStream2) -> Vec<TokenStream2> {
+    StructOpsConfig::identity_checks(syntax, source)
+}
+
+#[cfg(not(feature = "ops"))]
+fn ops_identity(_syntax: &RichStructContent, _source: &TokenStream2) -> Vec<TokenStream2> {
+    Vec::new()
+}
@@ -1,16 +1,59 @@
 use crate::config::field_config::{FieldConfig, GetterType, SetterType};
-use crate::config::struct_config::StructConfig;
+use crate::config::precedence::Precedence;
+use crate::config::struct_config::{ConstructType, StructConfig};
+use crate::config::validate::{check_conflicts, lint_unused_defaults};
+use crate::utils::hygiene::local;
+use crate::utils::respan::respan;
+use crate::utils::source::{doc_text, expr_text};
+use crate::utils::warning::warning;
 use crate::syntax::{RichStruct, StructField};
 
-use crate::cmp::StructCmpConfig;
+use crate::access::{impl_as_ref, impl_borrow, impl_deref};
+use crate::arbitrary::impl_arbitrary;
+use crate::clone::{impl_clone, impl_clone_with_default};
+#[cfg(feature = "builder")]
+use crate::config::builder_config::StructBuilderConfig;
+#[cfg(feature = "cmp")]
+use crate::config::cmp_config::StructCmpConfig;
+use crate::computed::impl_computed;
+use crate::convert::{impl_convert_from, impl_into_parts, impl_tuple};
+use crate::cow::impl_cow;
+use crate::dirty::impl_dirty;
+use crate::dump::{dump, dump_requested};
+use crate::env::impl_env_overrides;
+use crate::ffi::{impl_ffi, snake_case};
+use crate::fixture::impl_fixture;
+use crate::gen_tests::impl_gen_tests;
+use crate::group::{check_groups, FieldGroup};
 use itertools::{Either, Itertools};
-use proc_macro2::{Literal, TokenStream as TokenStream2};
-use quote::{quote, quote_spanned};
+use proc_macro2::{Literal, Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::ext::IdentExt;
 use syn::spanned::Spanned;
-use syn::{Attribute, Generics, Ident, Type, Visibility};
-use crate::ops::StructOpsConfig;
+use syn::{
+    parse_quote, Attribute, FieldValue, FnArg, GenericArgument, Generics, Ident, Item, PathArguments, Type, Visibility,
+    WherePredicate,
+};
+#[cfg(feature = "ops")]
+use crate::config::ops_config::StructOpsConfig;
+use crate::utils::synerr::{ResultExt, SynErrorExt};
+use crate::invariant::impl_invariant;
+use crate::layout::impl_layout_asserts;
+use crate::lerp::impl_lerp;
+use crate::map_type::impl_map_type;
+use crate::merge::impl_merge;
+use crate::random::impl_random;
+use crate::patch::StructPatchConfig;
+#[cfg(feature = "reflect")]
+use crate::reflect::{impl_field_enum, impl_reflect, impl_visit};
+use crate::serde::{impl_serde_defaults, impl_serde_is_default};
+#[cfg(feature = "tracing")]
+use crate::tracing::impl_tracing;
+#[cfg(feature = "wasm")]
+use crate::wasm::impl_wasm;
+use crate::snapshot::impl_snapshot;
+use crate::string_map::impl_string_map;
 
-#[derive(Clone)]
 pub struct RichStructContent {
     pub config: StructConfig,
     pub attrs: Vec<Attribute>,
@@ -22,25 +65,55 @@ pub struct RichStructContent {
 
 impl RichStructContent {
     pub fn from_syntax(syntax: RichStruct) -> syn::Result<Self> {
-        let (config, attrs) = StructConfig::from_attribute(syntax.attrs, syntax.ident.span())?;
+        let (config, attrs) = match StructConfig::from_attribute(syntax.attrs) {
+            Ok(parsed) => parsed,
+            Err(mut err) => {
+                // the fields are still checked, so that all errors are reported at once
+                for field in syntax.fields {
+                    if let Err(e) = FieldConfig::from_attribute(field.attrs, SetterType::No, GetterType::No, Precedence::Last) {
+                        err.combine(e);
+                    }
+                }
+                return Err(err);
+            }
+        };
+        check_groups(&config.groups, syntax.fields.iter().map(|f| &f.ident))?;
+        let mut err: Option<syn::Error> = None;
         let fields = syntax
             .fields
             .into_iter()
             .enumerate()
-            .map(|(idx, field)| -> Result<_, syn::Error> {
-                let content = StructFieldContent::from_syntax(
+            .filter_map(|(idx, mut field)| {
+                // the field's own `dfield`s take precedence over the group configurations
+                let group_attrs = config
+                    .groups
+                    .iter()
+                    .filter(|g| g.contains(&field.ident))
+                    .map(FieldGroup::to_attribute);
+                field.attrs = match config.precedence {
+                    Precedence::Last => group_attrs.chain(field.attrs).collect(),
+                    Precedence::First => field.attrs.into_iter().chain(group_attrs).collect(),
+                };
+                match StructFieldContent::from_syntax(
                     field,
                     config.override_auto_set,
                     config.override_auto_get,
-                )?;
-                let seq = content.config.init_seq.unwrap_or(idx as isize);
-                Ok((content, seq))
+                    config.precedence,
+                ) {
+                    Ok(content) => {
+                        let seq = content.config.init_seq.unwrap_or(idx as isize);
+                        Some((content, seq))
+                    }
+                    Err(e) => {
+                        err.update_or_combine(e);
+                        None
+                    }
+                }
             })
-            .collect::<Result<Vec<_>, _>>()?
-            .into_iter()
             .sorted_by_key(|(_, i)| *i)
             .map(|(content, _)| content)
             .collect();
+        err.ok_or(()).swap()?;
 
         let val = Self {
             config,
@@ -51,15 +124,83 @@ impl RichStructContent {
             fields,
         };
 
+        check_conflicts(&val)?;
+
         Ok(val)
     }
 
+    /// Constructs the structure from `field: value` entries, according to the `construct` option.
+    pub fn construct(&self, fields: impl IntoIterator<Item = TokenStream2>) -> TokenStream2 {
+        let krate = &self.config.crate_path;
+        let (_, type_g, _) = self.generics.split_for_impl();
+        let ident = &self.ident;
+        self.construct_from(quote! { <#ident #type_g as #krate::DataStruct>::data_default() }, fields)
+    }
+
+    /// Like [`Self::construct`], in constants: the `default` mode starts from `ConstDataStruct::DEFAULT`.
+    pub fn construct_const(&self, fields: impl IntoIterator<Item = TokenStream2>) -> TokenStream2 {
+        let krate = &self.config.crate_path;
+        let (_, type_g, _) = self.generics.split_for_impl();
+        let ident = &self.ident;
+        self.construct_from(quote! { <#ident #type_g as #krate::ConstDataStruct>::DEFAULT }, fields)
+    }
+
+    fn construct_from(&self, default: TokenStream2, fields: impl IntoIterator<Item = TokenStream2>) -> TokenStream2 {
+        let ident = &self.ident;
+        match self.config.construct {
+            ConstructType::Literal => {
+                let fields = fields.into_iter();
+                quote! {
+                    #ident {
+                        #(#fields),*
+                    }
+                }
+            }
+            ConstructType::Default => {
+                let value = local("__ds_value");
+                let assigns = fields.into_iter().map(|field| match syn::parse2::<FieldValue>(field) {
+                    Ok(FieldValue { member, expr, .. }) => quote! { #value.#member = #expr; },
+                    Err(e) => e.to_compile_error(),
+                });
+                quote! {
+                    {
+                        let mut #value = #default;
+                        #(#assigns)*
+                        #value
+                    }
+                }
+            }
+        }
+    }
+
+    /// The generics with extra `where` bounds.
+    pub fn generics_for(&self, bounds: &[WherePredicate]) -> Generics {
+        let mut generics = self.generics.clone();
+        if !bounds.is_empty() {
+            generics.make_where_clause().predicates.extend(bounds.iter().cloned());
+        }
+        generics
+    }
+
+    /// Span of the structure option, or of the structure if it's not declared.
+    pub fn option_span(&self, name: &str) -> Span {
+        self.config.span_of(name).unwrap_or_else(|| self.ident.span())
+    }
+
     fn can_impl_default(&self) -> bool {
         self.fields.iter().all(|f| f.config.default_value.is_some())
     }
 
     pub fn to_impl(&self) -> syn::Result<TokenStream2> {
         let impl_ = self.generate_impl();
+        let unused_defaults = lint_unused_defaults(self);
+        let redeclared = self
+            .config
+            .declarations
+            .warnings
+            .iter()
+            .chain(self.fields.iter().flat_map(|f| &f.config.declarations.warnings))
+            .map(|(span, note)| warning(*span, note));
         let default = if self.can_impl_default() && self.config.generate_default {
             self.impl_default()
         } else {
@@ -70,34 +211,356 @@ impl RichStructContent {
         } else {
             Default::default()
         };
+        let const_check = if self.can_impl_default() && self.config.const_check {
+            self.impl_const_check()?
+        } else {
+            Default::default()
+        };
         let std_default = if self.can_impl_default() && self.config.impl_std_default {
             self.impl_std_default()
         } else {
             Default::default()
         };
+        let serde_defaults_impl = if self.config.serde_defaults {
+            impl_serde_defaults(self)
+        } else {
+            Default::default()
+        };
+        let serde_is_default_impl = if self.config.serde_is_default {
+            impl_serde_is_default(self)
+        } else {
+            Default::default()
+        };
+        let computed_impl = if self.config.computed.is_empty() {
+            Default::default()
+        } else {
+            impl_computed(self)
+        };
+        let zeroed_impl = if self.config.zeroed {
+            self.impl_zeroed()?
+        } else {
+            Default::default()
+        };
         let debug_impl = if self.config.manual_debug {
             self.impl_debug()
         } else {
             Default::default()
         };
+        let debug_fields_impl = if self.config.debug_fields {
+            self.impl_debug_fields()
+        } else {
+            Default::default()
+        };
+        let env_impl = if self.config.env_overrides.enabled {
+            impl_env_overrides(self)
+        } else {
+            Default::default()
+        };
+        let fixture_impl = if self.config.fixture {
+            impl_fixture(self)?
+        } else {
+            Default::default()
+        };
+        let gen_tests_impl = if self.config.gen_tests {
+            impl_gen_tests(self)?
+        } else {
+            Default::default()
+        };
+        let layout_impl = if self.config.layout.enabled() {
+            impl_layout_asserts(self, &self.config.layout)?
+        } else {
+            Default::default()
+        };
+        let defaults_doc_impl = if self.config.defaults_doc {
+            self.impl_defaults_doc()
+        } else {
+            Default::default()
+        };
+        #[cfg(feature = "cmp")]
         let cmp_impl = StructCmpConfig::impl_cmp(self)?;
+        #[cfg(not(feature = "cmp"))]
+        let cmp_impl = TokenStream2::new();
+        #[cfg(feature = "ops")]
         let ops_impl = StructOpsConfig::impl_ops(self)?;
+        #[cfg(not(feature = "ops"))]
+        let ops_impl = TokenStream2::new();
+        let clone_impl = if self.config.clone {
+            impl_clone(self)?
+        } else {
+            Default::default()
+        };
+        let clone_with_default_impl = if self.config.clone_with_default {
+            impl_clone_with_default(self)?
+        } else {
+            Default::default()
+        };
+        let dirty_impl = if self.config.dirty {
+            impl_dirty(self)
+        } else {
+            Default::default()
+        };
+        let snapshot_impl = if self.config.snapshot {
+            impl_snapshot(self)?
+        } else {
+            Default::default()
+        };
+        let ffi_impl = if self.config.ffi.enabled {
+            impl_ffi(self, &self.config.ffi)?
+        } else {
+            Default::default()
+        };
+        #[cfg(feature = "wasm")]
+        let wasm_impl = if self.config.wasm {
+            impl_wasm(self)?
+        } else {
+            Default::default()
+        };
+        #[cfg(not(feature = "wasm"))]
+        let wasm_impl = TokenStream2::new();
+        #[cfg(feature = "tracing")]
+        let tracing_impl = if self.config.tracing {
+            impl_tracing(self)
+        } else {
+            Default::default()
+        };
+        #[cfg(not(feature = "tracing"))]
+        let tracing_impl = TokenStream2::new();
+        let string_map_impl = if self.config.string_map {
+            impl_string_map(self)?
+        } else {
+            Default::default()
+        };
+        let lerp_impl = if self.config.lerp {
+            impl_lerp(self)?
+        } else {
+            Default::default()
+        };
+        #[cfg(feature = "builder")]
+        let builder_impl = if self.config.builder.enabled {
+            StructBuilderConfig::impl_builder(self)
+        } else {
+            Default::default()
+        };
+        #[cfg(not(feature = "builder"))]
+        let builder_impl = TokenStream2::new();
+        let merge_impl = if self.config.merge {
+            impl_merge(self)?
+        } else {
+            Default::default()
+        };
+        let map_type_impl = if self.config.map_types.is_empty() {
+            Default::default()
+        } else {
+            impl_map_type(self)?
+        };
+        let cow_impl = if self.config.cow {
+            impl_cow(self)?
+        } else {
+            Default::default()
+        };
+        let tuple_impl = if self.config.tuple {
+            impl_tuple(self)?
+        } else {
+            Default::default()
+        };
+        let into_parts_impl = impl_into_parts(self);
+        let convert_impl = if self.config.convert.from.is_empty()
+            && self.config.convert.try_from.is_empty()
+        {
+            Default::default()
+        } else {
+            impl_convert_from(self)?
+        };
+        #[cfg(feature = "reflect")]
+        let reflect_impl = if self.config.reflect.enabled {
+            impl_reflect(self)?
+        } else {
+            Default::default()
+        };
+        #[cfg(not(feature = "reflect"))]
+        let reflect_impl = TokenStream2::new();
+        #[cfg(feature = "reflect")]
+        let visit_impl = if self.config.visit {
+            impl_visit(self)
+        } else {
+            Default::default()
+        };
+        #[cfg(not(feature = "reflect"))]
+        let visit_impl = TokenStream2::new();
+        #[cfg(feature = "reflect")]
+        let field_enum_impl = if self.config.field_enum {
+            impl_field_enum(self)
+        } else {
+            Default::default()
+        };
+        #[cfg(not(feature = "reflect"))]
+        let field_enum_impl = TokenStream2::new();
+        let invariant_impl = match &self.config.invariant {
+            Some(config) => impl_invariant(self, config),
+            None => Default::default(),
+        };
+        let arbitrary_impl = match &self.config.arbitrary {
+            Some(config) => impl_arbitrary(self, config)?,
+            None => Default::default(),
+        };
+        #[cfg(feature = "cmp")]
+        let cache_key_impl = if self.config.cache_key {
+            StructCmpConfig::impl_cache_key(self)?
+        } else {
+            Default::default()
+        };
+        #[cfg(not(feature = "cmp"))]
+        let cache_key_impl = TokenStream2::new();
+        #[cfg(feature = "cmp")]
+        let diff_display_impl = if self.config.diff_display {
+            StructCmpConfig::impl_diff_display(self)?
+        } else {
+            Default::default()
+        };
+        #[cfg(not(feature = "cmp"))]
+        let diff_display_impl = TokenStream2::new();
+        #[cfg(feature = "testing")]
+        let assert_eq_verbose_impl = if self.config.assert_eq_verbose {
+            StructCmpConfig::impl_assert_eq_verbose(self)?
+        } else {
+            Default::default()
+        };
+        #[cfg(not(feature = "testing"))]
+        let assert_eq_verbose_impl = TokenStream2::new();
+        let random_impl = if self.config.random {
+            impl_random(self)?
+        } else {
+            Default::default()
+        };
+        let deref_impl = impl_deref(self)?;
+        let as_ref_impl = impl_as_ref(self)?;
+        let borrow_impl = impl_borrow(self)?;
+        let zip_impl = if self.config.zip_with {
+            self.impl_zip_with()?
+        } else {
+            Default::default()
+        };
+        let patch_impl = if self.config.patch.enabled {
+            StructPatchConfig::impl_patch(self)
+        } else {
+            Default::default()
+        };
 
-        Ok(quote! {
+        let ts = quote! {
             #impl_
 
             #default
 
             #const_default
+            #const_check
 
             #std_default
 
+            #zeroed_impl
+
+            #serde_defaults_impl
+
+            #serde_is_default_impl
+
+            #computed_impl
+
+            #invariant_impl
+
+            #arbitrary_impl
+
+            #random_impl
+
             #debug_impl
 
+            #debug_fields_impl
+
+            #defaults_doc_impl
+
+            #env_impl
+
+            #fixture_impl
+            #gen_tests_impl
+            #layout_impl
+
+            #clone_impl
+
+            #clone_with_default_impl
+
             #cmp_impl
 
+            #cache_key_impl
+
+            #diff_display_impl
+
+            #assert_eq_verbose_impl
+
             #ops_impl
-        })
+
+            #lerp_impl
+            #map_type_impl
+
+            #patch_impl
+
+            #dirty_impl
+
+            #snapshot_impl
+
+            #ffi_impl
+
+            #wasm_impl
+
+            #tracing_impl
+
+            #string_map_impl
+
+            #zip_impl
+
+            #builder_impl
+
+            #merge_impl
+
+            #cow_impl
+
+            #tuple_impl
+
+            #into_parts_impl
+
+            #convert_impl
+
+            #reflect_impl
+
+            #visit_impl
+
+            #field_enum_impl
+
+            #deref_impl
+
+            #as_ref_impl
+
+            #borrow_impl
+
+            #unused_defaults
+
+            #(#redeclared)*
+        };
+
+        // Left as is if a user expression breaks the parsing, rustc reports it with better spans.
+        let ts = match syn::parse2::<syn::File>(ts.clone()) {
+            Ok(mut file) => {
+                merge_inherent_impls(&mut file);
+                if self.config.doc_hidden {
+                    hide_from_docs(&mut file);
+                }
+                file.into_token_stream()
+            }
+            Err(_) => ts,
+        };
+
+        if self.config.dump || dump_requested(&self.ident) {
+            dump(&self.ident, &ts);
+        }
+
+        Ok(ts)
     }
 
     fn generate_impl(&self) -> TokenStream2 {
@@ -105,6 +568,7 @@ impl RichStructContent {
             .fields
             .iter()
             .flat_map(|field| field.generate_impl_code());
+        let consts = self.config.consts.iter().map(|c| c.to_code());
         let ident = &self.ident;
         let (impl_g, type_g, where_clause) = self.generics.split_for_impl();
 
@@ -116,6 +580,8 @@ impl RichStructContent {
 
         quote! {
             impl #impl_g #ident #type_g #where_clause {
+                #(#consts)*
+
                 #(#fns)*
 
                 #p_default
@@ -147,12 +613,19 @@ impl RichStructContent {
     // complete block
     // all fields must have default value
     fn impl_default(&self) -> TokenStream2 {
-        let construct = self.impl_default_construct();
+        let krate = &self.config.crate_path;
+        // the constant is evaluated at compile time, the construction is only expanded there
+        let construct = if self.config.const_default {
+            quote! { <Self as #krate::ConstDataStruct>::DEFAULT }
+        } else {
+            self.impl_default_construct()
+        };
         let ident = &self.ident;
-        let (impl_g, type_g, where_clause) = self.generics.split_for_impl();
+        let generics = self.generics_for(&self.config.where_bounds.default);
+        let (impl_g, type_g, where_clause) = generics.split_for_impl();
 
         quote! {
-            impl #impl_g ::datastruct::DataStruct for #ident #type_g #where_clause {
+            impl #impl_g #krate::DataStruct for #ident #type_g #where_clause {
                 fn data_default() -> Self {
                     #construct
                 }
@@ -161,9 +634,16 @@ impl RichStructContent {
     }
 
     fn impl_std_default(&self) -> TokenStream2 {
-        let construct = self.impl_default_construct();
+        // delegate to `DataStruct` when available instead of expanding the construction twice
+        let construct = if self.config.generate_default {
+            let krate = &self.config.crate_path;
+            quote! { <Self as #krate::DataStruct>::data_default() }
+        } else {
+            self.impl_default_construct()
+        };
         let ident = &self.ident;
-        let (impl_g, type_g, where_clause) = self.generics.split_for_impl();
+        let generics = self.generics_for(&self.config.where_bounds.default);
+        let (impl_g, type_g, where_clause) = generics.split_for_impl();
 
         quote! {
             impl #impl_g ::std::default::Default for #ident #type_g #where_clause {
@@ -175,12 +655,37 @@ impl RichStructContent {
     }
 
     fn impl_const_default(&self) -> TokenStream2 {
-        let construct = self.impl_default_construct();
+        let krate = &self.config.crate_path;
+        // each value is moved onto its `default = "..."` option,
+        // so non-const expressions are reported there instead of at the derive
+        let stmt = self.fields.iter().map(|field| {
+            let name = &field.ident;
+            let ty = &field.field_type;
+            // SAFETY: Caller-guaranteed
+            let default_expr = field.config.default_value.as_ref().unwrap();
+            let span = field
+                .config
+                .span_of("default")
+                .unwrap_or_else(|| default_expr.span());
+            let default_expr = respan(default_expr.to_token_stream(), span);
+            quote_spanned! {
+                span => let #name: #ty = #default_expr;
+            }
+        });
+        let idents = self.fields.iter().map(|field| &field.ident);
+        let construct = quote! {
+            #(#stmt)*
+
+            Self {
+                #(#idents),*
+            }
+        };
         let ident = &self.ident;
-        let (impl_g, type_g, where_clause) = self.generics.split_for_impl();
+        let generics = self.generics_for(&self.config.where_bounds.default);
+        let (impl_g, type_g, where_clause) = generics.split_for_impl();
 
         quote! {
-            impl #impl_g ::datastruct::ConstDataStruct for #ident #type_g #where_clause {
+            impl #impl_g #krate::ConstDataStruct for #ident #type_g #where_clause {
                 const DEFAULT: Self = {
                     #construct
                 };
@@ -188,6 +693,86 @@ impl RichStructContent {
         }
     }
 
+    // `data_default` returns `DEFAULT`, so the test evaluates the default values again at runtime
+    // to catch drift between the const and the runtime evaluation of an expression
+    fn impl_const_check(&self) -> syn::Result<TokenStream2> {
+        if !self.generics.params.is_empty() {
+            return Err(syn::Error::new(
+                self.option_span("const"),
+                "`const(check)` doesn't support generic structures",
+            ));
+        }
+        let krate = &self.config.crate_path;
+        let ident = &self.ident;
+        let construct = self.impl_default_construct();
+        let module = format_ident!("__ds_{}_const_check", snake_case(&ident.unraw().to_string()));
+        let message = format!("`{}::DEFAULT` differs from the default values evaluated at runtime", ident);
+
+        Ok(quote! {
+            #[cfg(test)]
+            impl #ident {
+                #[doc(hidden)]
+                fn __ds_check_const_default() {
+                    let runtime: Self = {
+                        #construct
+                    };
+                    ::std::assert!(<Self as #krate::ConstDataStruct>::DEFAULT == runtime, #message);
+                }
+            }
+
+            #[cfg(test)]
+            #[doc(hidden)]
+            mod #module {
+                #[test]
+                fn const_default_matches_runtime_default() {
+                    super::#ident::__ds_check_const_default();
+                }
+            }
+        })
+    }
+
+    fn impl_zeroed(&self) -> syn::Result<TokenStream2> {
+        let mut err: Option<syn::Error> = None;
+        let fields = self
+            .fields
+            .iter()
+            .filter_map(|field| {
+                let ident = &field.ident;
+                match zero_value(&field.field_type) {
+                    Some(value) => Some(quote! { #ident: #value }),
+                    None => {
+                        err.update_or_combine(syn::Error::new(
+                            field.field_type.span(),
+                            "`zeroed` only supports numbers, `bool`, `char`, `Option`, and arrays or tuples of them",
+                        ));
+                        None
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if self.config.construct == ConstructType::Default && !self.config.const_default {
+            err.update_or_combine(syn::Error::new(
+                self.option_span("construct"),
+                "`construct = \"default\"` requires `const` for `zeroed`",
+            ));
+        }
+        err.ok_or(()).swap()?;
+
+        let ident = &self.ident;
+        let (impl_g, type_g, where_clause) = self.generics.split_for_impl();
+        let value = self.construct_const(fields);
+
+        Ok(quote! {
+            impl #impl_g #ident #type_g #where_clause {
+                /// Returns the structure with every field set to zero, `false` or `None`.
+                pub const fn zeroed() -> Self {
+                    #value
+                }
+            }
+        })
+    }
+
     fn impl_partial_default(&self) -> TokenStream2 {
         let (default, non_default): (Vec<_>, Vec<_>) =
             self.fields
@@ -226,6 +811,45 @@ impl RichStructContent {
         }
     }
 
+    // all fields must share the same type
+    fn impl_zip_with(&self) -> syn::Result<TokenStream2> {
+        let ty = match self.fields.first() {
+            Some(field) => &field.field_type,
+            None => {
+                return Err(syn::Error::new(
+                    self.option_span("zip_with"),
+                    "`zip_with` requires at least one field",
+                ))
+            }
+        };
+        let ty_str = ty.to_token_stream().to_string();
+        if let Some(field) = self
+            .fields
+            .iter()
+            .find(|f| f.field_type.to_token_stream().to_string() != ty_str)
+        {
+            return Err(syn::Error::new(
+                field.field_type.span(),
+                "`zip_with` requires all fields to share the same type",
+            ));
+        }
+
+        let construct = self.construct(self.fields.iter().map(|f| {
+            let ident = &f.ident;
+            quote! { #ident: func(self.#ident, other.#ident) }
+        }));
+        let ident = &self.ident;
+        let (impl_g, type_g, where_clause) = self.generics.split_for_impl();
+
+        Ok(quote! {
+            impl #impl_g #ident #type_g #where_clause {
+                pub fn zip_with(self, other: Self, func: impl Fn(#ty, #ty) -> #ty) -> Self {
+                    #construct
+                }
+            }
+        })
+    }
+
     fn impl_debug(&self) -> TokenStream2 {
         let struct_name: Literal = Literal::string(&self.ident.to_string());
         let struct_ident = &self.ident;
@@ -240,33 +864,283 @@ impl RichStructContent {
                     .field(#field_string, &self.#field_ident)
                 }
             });
+        let computed = self
+            .config
+            .computed
+            .iter()
+            .filter(|computed| computed.debug)
+            .map(|computed| {
+                let name = &computed.name;
+                let name_string: Literal = Literal::string(&name.to_string());
+                quote! {
+                    .field(#name_string, &self.#name())
+                }
+            });
 
-        let (impl_g, type_g, where_clause) = self.generics.split_for_impl();
+        let generics = self.generics_for(&self.config.where_bounds.debug);
+        let (impl_g, type_g, where_clause) = generics.split_for_impl();
 
         quote! {
             impl #impl_g ::std::fmt::Debug for #struct_ident #type_g #where_clause {
                 fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
                     f.debug_struct(#struct_name)
                         #(#fields)*
+                        #(#computed)*
                         .finish()
                 }
             }
         }
     }
+
+    fn impl_debug_fields(&self) -> TokenStream2 {
+        let struct_ident = &self.ident;
+        let vis = &self.vis;
+        let fields = self
+            .fields
+            .iter()
+            .filter(|field| !field.config.no_debug)
+            .map(|field| {
+                let field_ident = &field.ident;
+                let field_string: Literal = Literal::string(&field.ident.unraw().to_string());
+                quote! {
+                    (#field_string, &self.#field_ident as &dyn ::std::fmt::Debug)
+                }
+            })
+            .collect::<Vec<_>>();
+        let len = fields.len();
+
+        let generics = self.generics_for(&self.config.where_bounds.debug);
+        let (impl_g, type_g, where_clause) = generics.split_for_impl();
+
+        quote! {
+            impl #impl_g #struct_ident #type_g #where_clause {
+                /// Iterate over the names and values of the fields, except those with `debug = false`.
+                #vis fn debug_fields(
+                    &self,
+                ) -> impl ::core::iter::Iterator<Item = (&'static str, &dyn ::std::fmt::Debug)> + '_ {
+                    let fields: [(&'static str, &dyn ::std::fmt::Debug); #len] = [#(#fields),*];
+                    ::core::iter::IntoIterator::into_iter(fields)
+                }
+            }
+        }
+    }
+
+    fn impl_defaults_doc(&self) -> TokenStream2 {
+        let struct_ident = &self.ident;
+        let vis = &self.vis;
+        // `Port to listen on [default: 8080]`, like the help of command line parsers
+        let entries = self.fields.iter().filter_map(|field| {
+            let default_expr = field.config.default_value.as_ref()?;
+            let name = field.ident.unraw().to_string();
+            let doc = doc_text(&field.attrs).replace('\n', " ");
+            let default_text = field.config.default_text.clone().unwrap_or_else(|| expr_text(default_expr));
+            let default_doc = format!("[default: {}]", default_text);
+            let text = if doc.is_empty() {
+                default_doc
+            } else {
+                format!("{doc} {default_doc}")
+            };
+            Some(quote! { (#name, #text) })
+        });
+        let (impl_g, type_g, where_clause) = self.generics.split_for_impl();
+
+        quote! {
+            impl #impl_g #struct_ident #type_g #where_clause {
+                /// Names of the fields with default values, paired with their documentation and default values.
+                #vis const DEFAULTS_DOC: &'static [(&'static str, &'static str)] = &[#(#entries),*];
+            }
+        }
+    }
+}
+
+// Merges the inherent impl blocks with the same header into the first one, as every subsystem emits its own.
+fn merge_inherent_impls(file: &mut syn::File) {
+    let mut items: Vec<Item> = Vec::with_capacity(file.items.len());
+    // header of each mergeable impl block, and its index in `items`
+    let mut heads: Vec<(String, usize)> = Vec::new();
+    for item in file.items.drain(..) {
+        let item_impl = match item {
+            Item::Impl(item_impl)
+                if item_impl.trait_.is_none()
+                    && item_impl.unsafety.is_none()
+                    && item_impl.defaultness.is_none()
+                    && item_impl.attrs.is_empty() =>
+            {
+                item_impl
+            }
+            item => {
+                items.push(item);
+                continue;
+            }
+        };
+        let (impl_g, _, where_clause) = item_impl.generics.split_for_impl();
+        let self_ty = &item_impl.self_ty;
+        let head = quote! { #impl_g #self_ty #where_clause }.to_string();
+        match heads.iter().find(|(h, _)| *h == head) {
+            Some((_, idx)) => match &mut items[*idx] {
+                Item::Impl(first) => first.items.extend(item_impl.items),
+                _ => unreachable!(),
+            },
+            None => {
+                heads.push((head, items.len()));
+                items.push(Item::Impl(item_impl));
+            }
+        }
+    }
+    file.items = items;
+}
+
+// Hides the inherent methods and the companion types, the trait implementations are kept.
+fn hide_from_docs(file: &mut syn::File) {
+    for item in &mut file.items {
+        let attrs = match item {
+            Item::Impl(item) if item.trait_.is_none() => &mut item.attrs,
+            Item::Struct(item) => &mut item.attrs,
+            Item::Enum(item) => &mut item.attrs,
+            Item::Trait(item) => &mut item.attrs,
+            _ => continue,
+        };
+        attrs.push(parse_quote! { #[doc(hidden)] });
+    }
+}
+
+pub enum LockType {
+    Mutex,
+    RwLock,
+}
+
+// `Mutex<T>` or `RwLock<T>` of `std::sync`, with `T`.
+// A bare `Mutex` may be imported from anywhere, so it's only accepted with `do_with = "lock"`,
+// while a qualified path must name `std::sync`.
+pub fn lock_type(ty: &Type) -> Option<(LockType, &Type)> {
+    let path = match ty {
+        Type::Path(pth) if pth.qself.is_none() => &pth.path,
+        Type::Paren(paren) => return lock_type(&paren.elem),
+        Type::Group(group) => return lock_type(&group.elem),
+        _ => return None,
+    };
+    let seg = path.segments.last()?;
+    let module = path.segments.iter().map(|s| s.ident.to_string()).collect::<Vec<_>>();
+    let module = module[..module.len() - 1].iter().map(String::as_str).collect::<Vec<_>>();
+    if !matches!(module.as_slice(), [] | ["sync"] | ["std", "sync"]) {
+        return None;
+    }
+    let lock = match seg.ident.to_string().as_str() {
+        "Mutex" => LockType::Mutex,
+        "RwLock" => LockType::RwLock,
+        _ => return None,
+    };
+    match &seg.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(inner) => Some((lock, inner)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// The zero value of a type, if it's zeroable in const contexts.
+fn zero_value(ty: &Type) -> Option<TokenStream2> {
+    match ty {
+        _ if numeric_primitive(ty) => Some(quote! { 0 as #ty }),
+        Type::Path(pth) if pth.qself.is_none() && pth.path.is_ident("bool") => Some(quote! { false }),
+        Type::Path(pth) if pth.qself.is_none() && pth.path.is_ident("char") => Some(quote! { '\0' }),
+        Type::Path(pth)
+            if pth.qself.is_none()
+                && pth.path.segments.last().is_some_and(|seg| seg.ident == "Option") =>
+        {
+            Some(quote! { ::core::option::Option::None })
+        }
+        Type::Array(arr) => {
+            let elem = zero_value(&arr.elem)?;
+            let len = &arr.len;
+            Some(quote! { [#elem; #len] })
+        }
+        Type::Tuple(tuple) => {
+            let elems = tuple.elems.iter().map(zero_value).collect::<Option<Vec<_>>>()?;
+            Some(quote! { (#(#elems,)*) })
+        }
+        Type::Paren(paren) => zero_value(&paren.elem),
+        Type::Group(group) => zero_value(&group.elem),
+        _ => None,
+    }
+}
+
+// Whether the type is one of the built-in numeric primitives.
+pub fn numeric_primitive(ty: &Type) -> bool {
+    const PRIMITIVES: &[&str] = &[
+        "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+        "f32", "f64",
+    ];
+
+    match ty {
+        Type::Path(pth) if pth.qself.is_none() => pth
+            .path
+            .get_ident()
+            .is_some_and(|i| PRIMITIVES.contains(&i.to_string().as_str())),
+        _ => false,
+    }
 }
 
-#[derive(Clone)]
 pub struct StructFieldContent {
     pub config: FieldConfig,
+    // read by `reflect` and `defaults_doc` for the field documentation
     pub attrs: Vec<Attribute>,
+    #[allow(dead_code)]
     pub vis: Visibility,
     pub ident: Ident,
     pub field_type: Type,
+    pub accessors: AccessorIdents,
+}
+
+/// Names of the accessors of a field, built once and shared by every accessor.
+pub struct AccessorIdents {
+    /// `set_xxx`
+    pub set: Ident,
+    /// `with_xxx`
+    pub with: Ident,
+    /// `get_xxx`, the by-value getter
+    pub r#move: Ident,
+    /// `do_with_xxx`
+    pub do_with: Ident,
+    /// `map_xxx`
+    pub map: Ident,
+    /// `set_xxx_tracked`
+    pub set_tracked: Ident,
+    /// `read_xxx`, for `RwLock` fields
+    pub read: Ident,
+}
+
+impl AccessorIdents {
+    fn new(field: &Ident) -> Self {
+        // `r#type` gives `set_type`
+        let name = field.unraw().to_string();
+        let ident = |prefix: &str| Ident::new(&format!("{}_{}", prefix, name), field.span());
+        Self {
+            set: ident("set"),
+            with: ident("with"),
+            r#move: ident("get"),
+            do_with: ident("do_with"),
+            map: ident("map"),
+            set_tracked: Ident::new(&format!("set_{}_tracked", name), field.span()),
+            read: ident("read"),
+        }
+    }
 }
 
 impl StructFieldContent {
-    pub fn from_syntax(syntax: StructField, set: SetterType, get: GetterType) -> syn::Result<Self> {
-        let (mut config, attrs) = FieldConfig::from_attribute(syntax.attrs, set, get)?;
+    /// Span of the field option, or of the field if it's not declared.
+    pub fn option_span(&self, name: &str) -> Span {
+        self.config.span_of(name).unwrap_or_else(|| self.ident.span())
+    }
+
+    pub fn from_syntax(
+        syntax: StructField,
+        set: SetterType,
+        get: GetterType,
+        precedence: Precedence,
+    ) -> syn::Result<Self> {
+        let (mut config, attrs) = FieldConfig::from_attribute(syntax.attrs, set, get, precedence)?;
 
         if let (None, Some(t)) = (&config.default_value, syntax.default_value) {
             config.default_value = Some(t.value)
@@ -276,6 +1150,7 @@ impl StructFieldContent {
             config,
             attrs,
             vis: syntax.vis,
+            accessors: AccessorIdents::new(&syntax.ident),
             ident: syntax.ident,
             field_type: syntax.field_type,
         })
@@ -283,33 +1158,70 @@ impl StructFieldContent {
 
     fn generate_impl_code(&self) -> Vec<TokenStream2> {
         let mut code = Vec::with_capacity(4);
-        code.extend(self.config.auto_get.to_code(
-            &self.ident.to_string(),
-            &self.field_type,
-            &self.ident.span(),
-        ));
-        code.extend(self.config.auto_set.to_code(
-            &self.ident.to_string(),
-            &self.field_type,
-            &self.ident.span(),
-        ));
+        let attrs = &self.config.attrs;
+        code.extend(self.config.auto_get.to_code(&self.ident, &self.accessors, &self.field_type, attrs));
+        code.extend(self.config.auto_set.to_code(&self.ident, &self.accessors, &self.field_type, attrs));
 
         if self.config.do_with {
-            let func_ident = Ident::new(&format!("do_with_{}", self.ident), self.ident.span());
+            let func_ident = &self.accessors.do_with;
             let ident = &self.ident;
             let ty = &self.field_type;
+            let attrs = &attrs.do_with;
+            // with `do_with = "lock"`, locks are accessed through `&self`, so that the structure can be shared
+            let lock = if self.config.do_with_lock { lock_type(ty) } else { None };
+            match lock {
+                Some((LockType::Mutex, inner)) => code.push(quote! {
+                    #(#attrs)*
+                    pub fn #func_ident(&self, func: impl FnOnce(&mut #inner)) {
+                        func(&mut self.#ident.lock().unwrap());
+                    }
+                }),
+                Some((LockType::RwLock, inner)) => {
+                    let read_ident = &self.accessors.read;
+                    code.push(quote! {
+                        #(#attrs)*
+                        pub fn #func_ident(&self, func: impl FnOnce(&mut #inner)) {
+                            func(&mut self.#ident.write().unwrap());
+                        }
+
+                        #(#attrs)*
+                        pub fn #read_ident<__DsReturn>(&self, func: impl FnOnce(&#inner) -> __DsReturn) -> __DsReturn {
+                            func(&self.#ident.read().unwrap())
+                        }
+                    })
+                }
+                None => code.push(quote! {
+                    #(#attrs)*
+                    pub fn #func_ident(&mut self, func: impl FnOnce(&mut #ty)) {
+                        func(&mut self.#ident);
+                    }
+                }),
+            }
+        }
+
+        for sig in &self.config.delegate {
+            let ident = &self.ident;
+            let method = &sig.ident;
+            let args = sig.inputs.iter().filter_map(|arg| match arg {
+                FnArg::Typed(pat) => Some(&pat.pat),
+                FnArg::Receiver(_) => None,
+            });
+            let attrs = &attrs.delegate;
             code.push(quote! {
-                pub fn #func_ident(&mut self, func: impl FnOnce(&mut #ty)) {
-                    func(&mut self.#ident);
+                #(#attrs)*
+                pub #sig {
+                    self.#ident.#method(#(#args),*)
                 }
             });
         }
 
         if self.config.map {
-            let func_ident = Ident::new(&format!("map_{}", self.ident), self.ident.span());
+            let func_ident = &self.accessors.map;
             let ident = &self.ident;
             let ty = &self.field_type;
+            let attrs = &attrs.map;
             code.push(quote! {
+                #(#attrs)*
                 pub fn #func_ident(mut self, func: impl FnOnce(#ty) -> #ty) -> Self {
                     self.#ident = func(self.#ident);
                     self
@@ -0,0 +1,114 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::{parse_quote, Attribute, Ident};
+
+use crate::utils::collect_meta::{is_flag, is_list, option_tokens, parse_nested_options, skip_option};
+use crate::utils::synerr::{ResultExt, SynErrorExt};
+
+/// Field configuration shared by several fields,
+/// e.g. `group(secrets, fields(password, token), debug = false)`.
+pub struct FieldGroup {
+    pub name: Ident,
+    pub fields: Vec<Ident>,
+    /// The `dfield` arguments applied to every field of the group.
+    pub config: Vec<TokenStream2>,
+}
+
+impl FieldGroup {
+    pub fn from_meta(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut name = None;
+        let mut fields = None;
+        let mut config = Vec::new();
+        let mut err: Option<syn::Error> = None;
+
+        let res = parse_nested_options(meta, |meta| {
+            if name.is_none() {
+                return match meta.path.get_ident() {
+                    Some(ident) if is_flag(&meta) && fields.is_none() && config.is_empty() => {
+                        name = Some(ident.clone());
+                        Ok(())
+                    }
+                    _ => Err(meta.error("`group` should start with its name, like `group(name, fields(a, b), ...)`")),
+                };
+            }
+            if meta.path.is_ident("fields") && is_list(&meta) {
+                let idents = fields.get_or_insert_with(Vec::new);
+                parse_nested_options(&meta, |field| match field.path.get_ident() {
+                    Some(ident) if is_flag(&field) => {
+                        idents.push(ident.clone());
+                        Ok(())
+                    }
+                    _ => Err(field.error("`fields` expects a list of field names")),
+                })?;
+            } else {
+                let path = &meta.path;
+                let value = option_tokens(meta.input);
+                config.push(quote! { #path #value });
+                skip_option(meta.input)?;
+            }
+            Ok(())
+        });
+        if let Err(e) = res {
+            err.update_or_combine(e);
+        }
+
+        let name = match name {
+            Some(name) => name,
+            None => {
+                err.update_or_combine(meta.error(
+                    "`group` should start with its name, like `group(name, fields(a, b), ...)`",
+                ));
+                return Err(err.unwrap());
+            }
+        };
+        if fields.is_none() {
+            err.update_or_combine(meta.error(format!("group `{}` requires `fields(...)`", name)));
+        }
+
+        err.ok_or(()).swap()?;
+
+        Ok(Self {
+            name,
+            fields: fields.unwrap_or_default(),
+            config,
+        })
+    }
+
+    /// Whether the field belongs to the group.
+    pub fn contains(&self, field: &Ident) -> bool {
+        self.fields.iter().any(|f| f == field)
+    }
+
+    /// The shared configuration as a `dfield` attribute.
+    /// The configuration as an attribute of the fields, parsed like `dfield`.
+    pub fn to_attribute(&self) -> Attribute {
+        let config = &self.config;
+        parse_quote! { #[dfield_group(#(#config),*)] }
+    }
+}
+
+/// Checks that the groups have distinct names and only refer to existing fields.
+pub fn check_groups<'a>(
+    groups: &[FieldGroup],
+    fields: impl Iterator<Item = &'a Ident> + Clone,
+) -> syn::Result<()> {
+    let mut err: Option<syn::Error> = None;
+    for (idx, group) in groups.iter().enumerate() {
+        if groups[..idx].iter().any(|g| g.name == group.name) {
+            err.update_or_combine(syn::Error::new(
+                group.name.span(),
+                format!("duplicate group `{}`", group.name),
+            ));
+        }
+        for field in &group.fields {
+            if !fields.clone().any(|f| f == field) {
+                err.update_or_combine(syn::Error::new(
+                    field.span(),
+                    format!("group `{}` refers to an unknown field `{}`", group.name, field),
+                ));
+            }
+        }
+    }
+    err.ok_or(()).swap()
+}
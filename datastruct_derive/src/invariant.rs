@@ -0,0 +1,132 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::meta::ParseNestedMeta;
+use syn::{Expr, LitStr, Type};
+
+use crate::config::field_config::SetterType;
+use crate::generate::RichStructContent;
+use crate::utils::collect_meta::{is_value, parse_expr, parse_nested_options, str_value};
+
+/// A `fn(&Self) -> Result<(), E>` guarding every generated mutation path.
+pub struct StructInvariantConfig {
+    pub check: Expr,
+    /// The error returned by the generated methods, the error of `check` is converted with `Into`.
+    pub error: Option<Type>,
+}
+
+impl StructInvariantConfig {
+    pub fn from_lit(lit: &LitStr) -> syn::Result<Self> {
+        Ok(Self {
+            check: lit.parse()?,
+            error: None,
+        })
+    }
+
+    pub fn from_meta(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut check = None;
+        let mut error = None;
+
+        parse_nested_options(meta, |meta| {
+            let invalid = || meta.error("invalid `invariant` argument, see the documentation for more information");
+            if meta.path.is_ident("check") && is_value(&meta) {
+                check = Some(parse_expr(&meta)?);
+            } else if meta.path.is_ident("error") && is_value(&meta) {
+                error = Some(str_value(&meta).map_err(|_| invalid())?.parse::<Type>()?);
+            } else {
+                return Err(invalid());
+            }
+            Ok(())
+        })?;
+
+        let check = check.ok_or_else(|| meta.error("`invariant` requires `check`"))?;
+
+        Ok(Self { check, error })
+    }
+
+    pub fn error_type(&self) -> TokenStream2 {
+        match &self.error {
+            Some(ty) => quote! { #ty },
+            None => quote! {
+                ::std::boxed::Box<dyn ::std::error::Error + ::core::marker::Send + ::core::marker::Sync>
+            },
+        }
+    }
+}
+
+/// Generate `check_invariant`, and fallible versions of the generated setters and defaults.
+pub fn impl_invariant(syntax: &RichStructContent, config: &StructInvariantConfig) -> TokenStream2 {
+    let krate = &syntax.config.crate_path;
+    let ident = &syntax.ident;
+    let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+    let check = &config.check;
+    let error = config.error_type();
+
+    let setters = syntax
+        .fields
+        .iter()
+        .filter(|f| matches!(f.config.auto_set, SetterType::Full | SetterType::Set))
+        .map(|field| {
+            let field_ident = &field.ident;
+            let ty = &field.field_type;
+            let method = format_ident!("try_set_{}", field_ident);
+            quote! {
+                /// Set the field, the old value is restored if the invariant is violated.
+                pub fn #method(&mut self, #field_ident: #ty) -> ::core::result::Result<(), #error> {
+                    let old = ::core::mem::replace(&mut self.#field_ident, #field_ident);
+                    match self.check_invariant() {
+                        ::core::result::Result::Ok(()) => ::core::result::Result::Ok(()),
+                        ::core::result::Result::Err(e) => {
+                            self.#field_ident = old;
+                            ::core::result::Result::Err(e)
+                        }
+                    }
+                }
+            }
+        });
+
+    let has_default = syntax.config.generate_default
+        && syntax.fields.iter().all(|f| f.config.default_value.is_some());
+    let try_default = if has_default {
+        quote! {
+            /// Returns the default value of the structure, if it satisfies the invariant.
+            pub fn try_data_default() -> ::core::result::Result<Self, #error> {
+                let value = <Self as #krate::DataStruct>::data_default();
+                value.check_invariant()?;
+                ::core::result::Result::Ok(value)
+            }
+        }
+    } else {
+        Default::default()
+    };
+    // the inherent method is kept, so that callers don't need to import the trait
+    let try_default_impl = if has_default {
+        let generics = syntax.generics_for(&syntax.config.where_bounds.default);
+        let (impl_g, type_g, where_clause) = generics.split_for_impl();
+        quote! {
+            impl #impl_g #krate::TryDataStruct for #ident #type_g #where_clause {
+                type Error = #error;
+
+                fn try_data_default() -> ::core::result::Result<Self, Self::Error> {
+                    Self::try_data_default()
+                }
+            }
+        }
+    } else {
+        Default::default()
+    };
+
+    quote! {
+        impl #impl_g #ident #type_g #where_clause {
+            /// Check the invariant of the structure.
+            pub fn check_invariant(&self) -> ::core::result::Result<(), #error> {
+                ::core::result::Result::map_err((#check)(self), ::core::convert::Into::into)
+            }
+
+            #(#setters)*
+
+            #try_default
+        }
+
+        #try_default_impl
+    }
+}
@@ -0,0 +1,87 @@
+use proc_macro2::{Literal, TokenStream as TokenStream2};
+use quote::{quote, quote_spanned};
+use syn::meta::ParseNestedMeta;
+use syn::LitInt;
+
+use crate::generate::RichStructContent;
+use crate::utils::collect_meta::{is_value, parse_nested_options};
+
+#[derive(Default)]
+pub struct StructLayoutConfig {
+    /// Expected `size_of::<Self>()`, in bytes.
+    pub size: Option<LitInt>,
+    /// Expected `align_of::<Self>()`, in bytes.
+    pub align: Option<LitInt>,
+}
+
+impl StructLayoutConfig {
+    pub fn enabled(&self) -> bool {
+        self.size.is_some() || self.align.is_some()
+    }
+
+    pub fn from_meta(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut config = Self::default();
+
+        parse_nested_options(meta, |meta| {
+            let lit: LitInt = if is_value(&meta) {
+                meta.value()?.parse()?
+            } else {
+                return Err(meta.error("invalid `assert` argument, expect `size = n` or `align = n`"));
+            };
+            let bytes: usize = lit.base10_parse()?;
+            if meta.path.is_ident("size") {
+                config.size = Some(lit);
+            } else if meta.path.is_ident("align") {
+                if !bytes.is_power_of_two() {
+                    return Err(syn::Error::new(lit.span(), "alignment must be a power of two"));
+                }
+                config.align = Some(lit);
+            } else {
+                return Err(meta.error("invalid `assert` argument, expect `size = n` or `align = n`"));
+            }
+            Ok(())
+        })?;
+
+        if !config.enabled() {
+            return Err(meta.error("`assert` requires `size = n` or `align = n`"));
+        }
+        Ok(config)
+    }
+}
+
+/// Generate const assertions on the size and the alignment of the structure.
+// The assertions are spanned at the expected values, which is where a layout regression gets reported.
+pub fn impl_layout_asserts(syntax: &RichStructContent, config: &StructLayoutConfig) -> syn::Result<TokenStream2> {
+    let ident = &syntax.ident;
+    if !syntax.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            syntax.option_span("assert"),
+            "`assert` doesn't support generic structures, whose layout depends on the parameters",
+        ));
+    }
+
+    let assertion = |lit: &LitInt, function: TokenStream2, what: &str| -> syn::Result<TokenStream2> {
+        let bytes = Literal::usize_unsuffixed(lit.base10_parse()?);
+        let message = format!("the {} of `{}` is not {} bytes", what, ident, bytes);
+        Ok(quote_spanned! {
+            lit.span() => ::core::assert!(::core::mem::#function::<#ident>() == #bytes, #message);
+        })
+    };
+    let size = config
+        .size
+        .iter()
+        .map(|size| assertion(size, quote! { size_of }, "size"))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let align = config
+        .align
+        .iter()
+        .map(|align| assertion(align, quote! { align_of }, "alignment"))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(quote_spanned! {
+        ident.span() => const _: () = {
+            #(#size)*
+            #(#align)*
+        };
+    })
+}
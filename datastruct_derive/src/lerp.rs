@@ -0,0 +1,83 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Ident, Lit, LitStr, Path};
+
+use crate::generate::RichStructContent;
+use crate::utils::hygiene::{local, parse_template};
+use crate::utils::synerr::{ResultExt, SynErrorExt};
+
+pub fn impl_lerp(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+    let krate = &syntax.config.crate_path;
+    let mut fields = Vec::with_capacity(syntax.fields.len());
+    let mut err: Option<syn::Error> = None;
+
+    for field in &syntax.fields {
+        match field.config.lerp.impl_lerp(&field.ident, krate) {
+            Ok(op) => {
+                let ident = &field.ident;
+                fields.push(quote! { #ident: #op })
+            }
+            Err(e) => err.update_or_combine(e),
+        }
+    }
+
+    err.ok_or(()).swap()?;
+
+    let construct = syntax.construct(fields);
+    let ident = &syntax.ident;
+    let generics = syntax.generics_for(&syntax.config.where_bounds.lerp);
+    let (impl_g, type_g, where_clause) = generics.split_for_impl();
+
+    let (other, t) = (local("other"), local("t"));
+
+    Ok(quote! {
+        impl #impl_g #krate::Lerp for #ident #type_g #where_clause {
+            fn lerp(self, #other: Self, #t: f64) -> Self {
+                #construct
+            }
+        }
+    })
+}
+
+#[derive(Clone, Default)]
+pub enum FieldLerpConfig {
+    Manual(LitStr),
+    #[default]
+    Inherit,
+    Ignore,
+}
+
+impl FieldLerpConfig {
+    pub fn from_lit(lit: &Lit) -> syn::Result<Self> {
+        match lit {
+            Lit::Str(lit_str) => match lit_str.value().as_str() {
+                "inherit" | "default" => Ok(Self::Inherit),
+                "ignore" | "no" => Ok(Self::Ignore),
+                _ => Ok(Self::Manual(lit_str.clone())),
+            },
+            Lit::Bool(lit_bool) => {
+                if lit_bool.value {
+                    Ok(Self::Inherit)
+                } else {
+                    Ok(Self::Ignore)
+                }
+            }
+            _ => Err(syn::Error::new(lit.span(), "invalid `lerp` value")),
+        }
+    }
+
+    fn impl_lerp(&self, ident: &Ident, krate: &Path) -> syn::Result<TokenStream2> {
+        match self {
+            Self::Ignore => Ok(quote! { self.#ident }),
+            Self::Inherit => {
+                let (other, t) = (local("other"), local("t"));
+                Ok(quote! { #krate::Lerp::lerp(self.#ident, #other.#ident, #t) })
+            }
+            Self::Manual(s) => {
+                let (other, t) = (local("other"), local("t"));
+                let token = parse_template(s, &[("self", &quote! { self }), ("other", &other), ("t", &t)])?;
+                Ok(quote! { #token })
+            }
+        }
+    }
+}
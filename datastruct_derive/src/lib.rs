@@ -1,9 +1,43 @@
+mod access;
+mod arbitrary;
+#[cfg(feature = "builder")]
+mod builder;
+mod clone;
+#[cfg(feature = "cmp")]
 mod cmp;
+mod computed;
 mod config;
+mod consts;
+mod convert;
+mod cow;
+mod dirty;
+mod dump;
+mod env;
+mod ffi;
+mod fixture;
+mod gen_tests;
 mod generate;
+mod group;
+mod invariant;
+mod layout;
+mod lerp;
+mod map_type;
+mod merge;
 mod syntax;
 mod utils;
+#[cfg(feature = "ops")]
 mod ops;
+mod patch;
+mod random;
+#[cfg(feature = "reflect")]
+mod reflect;
+mod serde;
+mod snapshot;
+mod string_map;
+#[cfg(feature = "tracing")]
+mod tracing;
+#[cfg(feature = "wasm")]
+mod wasm;
 
 use crate::generate::RichStructContent;
 use proc_macro::TokenStream;
@@ -0,0 +1,61 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens};
+use syn::spanned::Spanned;
+use syn::Type;
+
+use crate::generate::RichStructContent;
+use crate::utils::hygiene::local;
+use crate::utils::synerr::{ResultExt, SynErrorExt};
+
+/// Generate `MapFieldsOfType<T>` for every type of `map_type(...)`,
+/// and `map_fields_of_type::<T>` selecting the implementation by the turbofish.
+// Types are compared by their tokens, so an alias and the type it names are different types.
+pub fn impl_map_type(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+    let krate = &syntax.config.crate_path;
+    let ident = &syntax.ident;
+    let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+    let func = local("func");
+    let type_key = |ty: &Type| ty.to_token_stream().to_string();
+
+    let mut impls = Vec::with_capacity(syntax.config.map_types.len());
+    let mut err: Option<syn::Error> = None;
+    for (i, ty) in syntax.config.map_types.iter().enumerate() {
+        let key = type_key(ty);
+        if syntax.config.map_types[..i].iter().any(|t| type_key(t) == key) {
+            err.update_or_combine(syn::Error::new(ty.span(), format!("`{}` is already listed", key)));
+            continue;
+        }
+        let fields = syntax
+            .fields
+            .iter()
+            .filter(|f| type_key(&f.field_type) == key)
+            .map(|f| &f.ident)
+            .collect::<Vec<_>>();
+        if fields.is_empty() {
+            err.update_or_combine(syn::Error::new(ty.span(), format!("no field of type `{}`", key)));
+            continue;
+        }
+        impls.push(quote! {
+            impl #impl_g #krate::MapFieldsOfType<#ty> for #ident #type_g #where_clause {
+                fn map_fields_of_type(&mut self, mut #func: impl ::core::ops::FnMut(&mut #ty)) {
+                    #(#func(&mut self.#fields);)*
+                }
+            }
+        });
+    }
+    err.ok_or(()).swap()?;
+
+    Ok(quote! {
+        #(#impls)*
+
+        impl #impl_g #ident #type_g #where_clause {
+            /// Applies `func` to every field of the selected type, one of the types listed in `map_type(...)`.
+            pub fn map_fields_of_type<__DsType>(&mut self, #func: impl ::core::ops::FnMut(&mut __DsType))
+            where
+                Self: #krate::MapFieldsOfType<__DsType>,
+            {
+                <Self as #krate::MapFieldsOfType<__DsType>>::map_fields_of_type(self, #func)
+            }
+        }
+    })
+}
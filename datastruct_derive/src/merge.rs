@@ -0,0 +1,99 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Ident, Lit, LitStr};
+
+use crate::generate::RichStructContent;
+use crate::utils::hygiene::{local, parse_template};
+use crate::utils::synerr::{ResultExt, SynErrorExt};
+
+#[derive(Clone, Default)]
+pub enum MergeStrategy {
+    /// `self.field = other.field`
+    #[default]
+    Overwrite,
+    /// Keep `self.field`.
+    Keep,
+    /// Extend `self.field` with `other.field`.
+    Append,
+    /// Merge nested structures recursively.
+    Merge,
+    Manual(LitStr),
+}
+
+impl MergeStrategy {
+    pub fn from_lit(lit: &Lit) -> syn::Result<Self> {
+        match lit {
+            Lit::Str(lit_str) => match lit_str.value().as_str() {
+                "overwrite" | "default" => Ok(Self::Overwrite),
+                "keep" | "ignore" | "no" => Ok(Self::Keep),
+                "append" | "extend" => Ok(Self::Append),
+                "merge" | "deep" => Ok(Self::Merge),
+                _ => Ok(Self::Manual(lit_str.clone())),
+            },
+            Lit::Bool(lit_bool) => {
+                if lit_bool.value {
+                    Ok(Self::Overwrite)
+                } else {
+                    Ok(Self::Keep)
+                }
+            }
+            _ => Err(syn::Error::new(lit.span(), "invalid `merge` strategy")),
+        }
+    }
+
+    fn impl_merge(&self, ident: &Ident) -> syn::Result<TokenStream2> {
+        let other = local("other");
+        match self {
+            Self::Overwrite => Ok(quote! { self.#ident = #other.#ident; }),
+            Self::Keep => Ok(quote! {}),
+            Self::Append => Ok(quote! {
+                ::std::iter::Extend::extend(&mut self.#ident, #other.#ident);
+            }),
+            Self::Merge => Ok(quote! { self.#ident.merge(#other.#ident); }),
+            Self::Manual(s) => {
+                let token = parse_template(s, &[("self", &quote! { self }), ("other", &other)])?;
+                Ok(quote! { self.#ident = #token; })
+            }
+        }
+    }
+}
+
+pub fn impl_merge(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+    let mut stmts = Vec::with_capacity(syntax.fields.len());
+    let mut err: Option<syn::Error> = None;
+
+    for field in &syntax.fields {
+        let strategy = field
+            .config
+            .merge
+            .as_ref()
+            .unwrap_or(&syntax.config.merge_default);
+        match strategy.impl_merge(&field.ident) {
+            Ok(stmt) => stmts.push(stmt),
+            Err(e) => err.update_or_combine(e),
+        }
+    }
+
+    err.ok_or(()).swap()?;
+
+    let ident = &syntax.ident;
+    let generics = syntax.generics_for(&syntax.config.where_bounds.merge);
+    let (impl_g, type_g, where_clause) = generics.split_for_impl();
+
+    let krate = &syntax.config.crate_path;
+    let other = local("other");
+
+    Ok(quote! {
+        impl #impl_g #ident #type_g #where_clause {
+            pub fn merge(&mut self, #other: Self) {
+                #(#stmts)*
+            }
+        }
+
+        impl #impl_g #krate::MergeDataStruct for #ident #type_g #where_clause {
+            fn merge_from(&mut self, #other: Self) {
+                Self::merge(self, #other)
+            }
+        }
+    })
+}
@@ -1,36 +1,34 @@
-use crate::utils::collect_meta::collect_meta_map;
+use crate::utils::generics::type_uses_params;
+use crate::utils::hygiene::{local, parse_template};
 use crate::utils::synerr::{ResultExt, SynErrorExt};
 
-use crate::generate::RichStructContent;
+use crate::config::ops_config::{FieldOpsConfig, OpsAssignableType, OpsOperationType, OpsType, StructOpsConfig};
+use crate::config::struct_config::ConstructType;
+use crate::generate::{numeric_primitive, RichStructContent};
 use itertools::Itertools;
-use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::{quote, ToTokens};
-use std::collections::HashMap;
-use syn::spanned::Spanned;
-use syn::{Expr, Ident, Lit, Meta, MetaList, MetaNameValue, NestedMeta};
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
-pub struct StructOpsConfig {
-    add: Option<OpsAssignableType>,
-    sub: Option<OpsAssignableType>,
-    mul: Option<OpsAssignableType>,
-    div: Option<OpsAssignableType>,
-}
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{parse_quote, Expr, Generics, Ident, LitStr, Token, Type, WherePredicate};
 
 macro_rules! __help_impl_struct_impl_ops {
-    (non-assign $fn_name:ident, $field_name:ident, $impl_fn:ident, $trait_name:path, $trait_fn:ident) => {
+    (non-assign $fn_name:ident, $field_name:ident, $template:ident, $impl_fn:ident, $trait_name:ident, $trait_fn:ident) => {
         fn $fn_name(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+            let mut bounds: Vec<WherePredicate> = Vec::new();
             let (fields, err_list): (Vec<_>, Vec<_>) = syntax
                 .fields
                 .iter()
                 .map(|field| {
-                    field
-                        .config
-                        .ops
-                        .$field_name
-                        .clone()
-                        .unwrap_or_default()
-                        .$impl_fn(&field.ident)
+                    let op = field.config.ops.$field_name.as_ref().unwrap_or(&OpsOperationType::Inherit);
+                    if matches!(op, OpsOperationType::Inherit)
+                        && syntax.config.ops.$template.is_none()
+                        && type_uses_params(&field.field_type, &syntax.generics)
+                    {
+                        let ty = &field.field_type;
+                        bounds.push(parse_quote! { #ty: ::std::ops::$trait_name<Output = #ty> });
+                    }
+                    op.$impl_fn(&field.ident, syntax.config.ops.$template.as_ref())
                         .map(|op| {
                             let ident = &field.ident;
                             quote! { #ident: #op }
@@ -48,36 +46,42 @@ macro_rules! __help_impl_struct_impl_ops {
             }
 
             let ident = &syntax.ident;
-            let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+            let generics = syntax
+                .config
+                .ops
+                .bounded_generics(&syntax.generics_for(&syntax.config.where_bounds.ops), bounds)?;
+            let (impl_g, type_g, where_clause) = generics.split_for_impl();
 
-            let fields = fields.iter().filter(|token| !token.is_empty());
+            let construct = syntax.construct(fields.into_iter().filter(|token| !token.is_empty()));
+            let rhs = local("rhs");
 
             Ok(quote! {
-                impl #impl_g $trait_name for #ident #type_g #where_clause {
+                impl #impl_g ::std::ops::$trait_name for #ident #type_g #where_clause {
                     type Output = Self;
 
-                    fn $trait_fn(self, rhs: Self) -> Self {
-                        Self {
-                            #(#fields),*
-                        }
+                    fn $trait_fn(self, #rhs: Self) -> Self {
+                        #construct
                     }
                 }
             })
         }
     };
-    (assign $fn_name:ident, $field_name:ident, $impl_fn:ident, $trait_name:path, $trait_fn:ident) => {
+    (assign $fn_name:ident, $field_name:ident, $template:ident, $impl_fn:ident, $trait_name:ident, $trait_fn:ident) => {
         fn $fn_name(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+            let mut bounds: Vec<WherePredicate> = Vec::new();
             let (fields, err_list): (Vec<_>, Vec<_>) = syntax
                 .fields
                 .iter()
                 .map(|field| {
-                    field
-                        .config
-                        .ops
-                        .$field_name
-                        .clone()
-                        .unwrap_or_default()
-                        .$impl_fn(&field.ident)
+                    let op = field.config.ops.$field_name.as_ref().unwrap_or(&OpsOperationType::Inherit);
+                    if matches!(op, OpsOperationType::Inherit)
+                        && syntax.config.ops.$template.is_none()
+                        && type_uses_params(&field.field_type, &syntax.generics)
+                    {
+                        let ty = &field.field_type;
+                        bounds.push(parse_quote! { #ty: ::std::ops::$trait_name });
+                    }
+                    op.$impl_fn(&field.ident, syntax.config.ops.$template.as_ref())
                 })
                 .partition_result();
 
@@ -91,11 +95,16 @@ macro_rules! __help_impl_struct_impl_ops {
             }
 
             let ident = &syntax.ident;
-            let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+            let generics = syntax
+                .config
+                .ops
+                .bounded_generics(&syntax.generics_for(&syntax.config.where_bounds.ops), bounds)?;
+            let (impl_g, type_g, where_clause) = generics.split_for_impl();
+            let rhs = local("rhs");
 
             Ok(quote! {
-                impl #impl_g $trait_name for #ident #type_g #where_clause {
-                    fn $trait_fn(&mut self, rhs: Self) {
+                impl #impl_g ::std::ops::$trait_name for #ident #type_g #where_clause {
+                    fn $trait_fn(&mut self, #rhs: Self) {
                         #(#fields;)*
                     }
                 }
@@ -105,60 +114,75 @@ macro_rules! __help_impl_struct_impl_ops {
 }
 
 impl StructOpsConfig {
-    pub fn mut_and(&mut self, other: Self) {
-        macro_rules! __impl_override {
-            ($self:ident, $other:ident, $($ident:ident),+ $(,)?) => {
-                $(if let Some(v) = $other.$ident {
-                    $self.$ident = Some(v)
-                })+
-            };
-        }
-
-        __impl_override!(self, other, add, sub, mul, div);
-    }
-
-    pub fn from_meta(meta_list: &MetaList) -> syn::Result<Self> {
-        let mut config: StructOpsConfig = Default::default();
-
-        let map: HashMap<OpsType, Option<OpsAssignableType>> =
-            collect_meta_map(meta_list, |_, ident, lit| {
-                let ops_type = OpsType::from_str(ident.to_string())
-                    .ok_or_else(|| syn::Error::new(ident.span(), "invalid ops type"))?;
-                let val: Option<OpsAssignableType> = if let Some(lit) = lit {
-                    match lit {
-                        Lit::Str(s) => {
-                            Some(OpsAssignableType::from_str(s.value()).ok_or_else(|| {
-                                syn::Error::new(lit.span(), "invalid ops operation type")
-                            })?)
-                        }
-                        Lit::Bool(b) => {
-                            if b.value {
-                                Some(Default::default())
-                            } else {
-                                None
+    // A struct-level expression template counts as an explicit decision for all fields.
+    fn check_strict(syntax: &RichStructContent, err: &mut Option<syn::Error>) {
+        macro_rules! __help_check_strict {
+            ($ident:ident, $template:ident, $assign:ident) => {
+                if let Some(mode) = syntax.config.ops.$ident {
+                    if syntax.config.ops.$template.is_none() {
+                        for field in &syntax.fields {
+                            if mode != OpsAssignableType::Assign && field.config.ops.$ident.is_none() {
+                                err.update_or_combine(syn::Error::new(
+                                    field.ident.span(),
+                                    concat!(
+                                        "`ops(strict)` requires an explicit `",
+                                        stringify!($ident),
+                                        "` configuration for this field"
+                                    ),
+                                ));
+                            }
+                            if mode != OpsAssignableType::Plain && field.config.ops.$assign.is_none() {
+                                err.update_or_combine(syn::Error::new(
+                                    field.ident.span(),
+                                    concat!(
+                                        "`ops(strict)` requires an explicit `",
+                                        stringify!($assign),
+                                        "` configuration for this field"
+                                    ),
+                                ));
                             }
                         }
-                        _ => return Err(syn::Error::new(lit.span(), "invalid ops operation type")),
                     }
-                } else {
-                    Some(Default::default())
-                };
+                }
+            };
+        }
 
-                Ok((ops_type, val))
-            })?;
+        __help_check_strict!(add, add_expr, add_assign);
+        __help_check_strict!(sub, sub_expr, sub_assign);
+        __help_check_strict!(mul, mul_expr, mul_assign);
+        __help_check_strict!(div, div_expr, div_assign);
+    }
 
-        config.add = map.get(&OpsType::Add).copied().flatten();
-        config.sub = map.get(&OpsType::Sub).copied().flatten();
-        config.mul = map.get(&OpsType::Mul).copied().flatten();
-        config.div = map.get(&OpsType::Div).copied().flatten();
+    // explicit `bound` replaces all inferred bounds
+    fn bounded_generics(
+        &self,
+        generics: &Generics,
+        inferred: Vec<WherePredicate>,
+    ) -> syn::Result<Generics> {
+        let mut generics = generics.clone();
+        let predicates: Vec<WherePredicate> = match &self.bound {
+            Some(bound) => Punctuated::<WherePredicate, Token![,]>::parse_terminated
+                .parse_str(bound)?
+                .into_iter()
+                .collect(),
+            None => inferred,
+        };
+
+        if !predicates.is_empty() {
+            generics.make_where_clause().predicates.extend(predicates);
+        }
 
-        Ok(config)
+        Ok(generics)
     }
 
     pub fn impl_ops(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
         let mut ts = TokenStream2::new();
         let mut err: Option<syn::Error> = None;
 
+        if syntax.config.ops.strict.unwrap_or(false) {
+            Self::check_strict(syntax, &mut err);
+        }
+
         macro_rules! __help_impl_ops_item {
             ($err:ident, $ts:ident, $syntax:ident, $plain:ident, $assign:ident, $ident:ident) => {
                 if let Some(v) = $syntax.config.ops.$ident {
@@ -191,178 +215,341 @@ impl StructOpsConfig {
         __help_impl_ops_item! { err, ts, syntax, impl_mul, impl_mul_assign, mul }
         __help_impl_ops_item! { err, ts, syntax, impl_div, impl_div_assign, div }
 
-        err.ok_or(()).swap()?;
+        let scalars = [
+            (&syntax.config.ops.mul, &syntax.config.ops.mul_scalar, OpsType::Mul),
+            (&syntax.config.ops.div, &syntax.config.ops.div_scalar, OpsType::Div),
+        ];
+        for (mode, scalar, ops_type) in scalars {
+            if let (Some(mode), Some(scalar)) = (mode, scalar) {
+                if *mode != OpsAssignableType::Assign {
+                    match Self::impl_scalar(syntax, scalar, ops_type, false) {
+                        Ok(v) => ts.extend(v),
+                        Err(e) => err.update_or_combine(e),
+                    }
+                }
+                if *mode != OpsAssignableType::Plain {
+                    match Self::impl_scalar(syntax, scalar, ops_type, true) {
+                        Ok(v) => ts.extend(v),
+                        Err(e) => err.update_or_combine(e),
+                    }
+                }
+            }
+        }
 
-        Ok(ts)
-    }
+        if syntax.config.ops.identity.unwrap_or(false) {
+            match Self::impl_identity(syntax) {
+                Ok(v) => ts.extend(v),
+                Err(e) => err.update_or_combine(e),
+            }
+        }
 
-    __help_impl_struct_impl_ops!(non-assign impl_add, add, impl_add, ::std::ops::Add, add);
-    __help_impl_struct_impl_ops!(non-assign impl_sub, sub, impl_sub, ::std::ops::Sub, sub);
-    __help_impl_struct_impl_ops!(non-assign impl_mul, mul, impl_mul, ::std::ops::Mul, mul);
-    __help_impl_struct_impl_ops!(non-assign impl_div, div, impl_div, ::std::ops::Div, div);
+        if syntax.config.ops.apply_op.unwrap_or(false) {
+            match Self::impl_apply_op(syntax) {
+                Ok(v) => ts.extend(v),
+                Err(e) => err.update_or_combine(e),
+            }
+        }
 
-    __help_impl_struct_impl_ops!(assign impl_add_assign, add_assign, impl_add_assign, ::std::ops::AddAssign, add_assign);
-    __help_impl_struct_impl_ops!(assign impl_sub_assign, sub_assign, impl_sub_assign, ::std::ops::SubAssign, sub_assign);
-    __help_impl_struct_impl_ops!(assign impl_mul_assign, mul_assign, impl_mul_assign, ::std::ops::MulAssign, mul_assign);
-    __help_impl_struct_impl_ops!(assign impl_div_assign, div_assign, impl_div_assign, ::std::ops::DivAssign, div_assign);
-}
+        err.ok_or(()).swap()?;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
-enum OpsType {
-    Add,
-    Sub,
-    Mul,
-    Div,
-}
+        Ok(ts)
+    }
 
-impl OpsType {
-    fn from_str(s: impl AsRef<str>) -> Option<Self> {
-        match s.as_ref() {
-            "add" => Some(OpsType::Add),
-            "sub" => Some(OpsType::Sub),
-            "mul" => Some(OpsType::Mul),
-            "div" => Some(OpsType::Div),
-            _ => None,
+    // `rhs` is borrowed, so the operands are cloned into the generated operators,
+    // preferring the assignment ones which leave `self` in place.
+    fn impl_apply_op(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+        let ops = &syntax.config.ops;
+        let krate = &syntax.config.crate_path;
+        let cases = [
+            (ops.add, "add", quote! { Add }, quote! { AddAssign }, quote! { add }, quote! { add_assign }),
+            (ops.sub, "sub", quote! { Sub }, quote! { SubAssign }, quote! { sub }, quote! { sub_assign }),
+            (ops.mul, "mul", quote! { Mul }, quote! { MulAssign }, quote! { mul }, quote! { mul_assign }),
+            (ops.div, "div", quote! { Div }, quote! { DivAssign }, quote! { div }, quote! { div_assign }),
+        ];
+        let missing = cases
+            .iter()
+            .filter(|(mode, ..)| mode.is_none())
+            .map(|(_, name, ..)| format!("`{}`", name))
+            .join(", ");
+        if !missing.is_empty() {
+            return Err(syn::Error::new(
+                syntax.option_span("ops"),
+                format!("`apply_op` requires `add`, `sub`, `mul` and `div`, missing {}", missing),
+            ));
         }
+
+        let op = local("op");
+        let rhs = local("rhs");
+        let mut bounds: Vec<WherePredicate> = vec![parse_quote! { Self: ::core::clone::Clone }];
+        let arms = cases.into_iter().map(|(mode, _, plain, assign, plain_fn, assign_fn)| {
+            match mode {
+                Some(OpsAssignableType::Plain) => {
+                    bounds.push(parse_quote! { Self: ::std::ops::#plain<Output = Self> });
+                    quote! {
+                        #krate::DataOp::#plain => {
+                            *self = ::std::ops::#plain::#plain_fn(
+                                ::core::clone::Clone::clone(self),
+                                ::core::clone::Clone::clone(#rhs),
+                            )
+                        }
+                    }
+                }
+                _ => {
+                    bounds.push(parse_quote! { Self: ::std::ops::#assign });
+                    quote! {
+                        #krate::DataOp::#plain => ::std::ops::#assign::#assign_fn(self, ::core::clone::Clone::clone(#rhs)),
+                    }
+                }
+            }
+        }).collect::<Vec<_>>();
+
+        let ident = &syntax.ident;
+        let generics = syntax.generics_for(&syntax.config.where_bounds.ops);
+        let (impl_g, type_g, where_clause) = generics.split_for_impl();
+
+        Ok(quote! {
+            impl #impl_g #ident #type_g #where_clause {
+                /// Apply the operation selected at runtime in place, `self = self <op> rhs`.
+                pub fn apply_op(&mut self, #op: #krate::DataOp, #rhs: &Self)
+                where
+                    #(#bounds),*
+                {
+                    match #op {
+                        #(#arms)*
+                    }
+                }
+            }
+        })
     }
-}
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
-enum OpsAssignableType {
-    Both,
-    Assign,
-    #[default]
-    Plain,
-}
+    // Scalar operations ignore field expressions, only `ignore` is respected.
+    fn impl_scalar(
+        syntax: &RichStructContent,
+        scalar: &str,
+        ops_type: OpsType,
+        assign: bool,
+    ) -> syn::Result<TokenStream2> {
+        let scalar: Type = syn::parse_str(scalar)?;
+        let (trait_name, trait_fn, op) = match (ops_type, assign) {
+            (OpsType::Mul, false) => (quote! { Mul }, quote! { mul }, quote! { * }),
+            (OpsType::Mul, true) => (quote! { MulAssign }, quote! { mul_assign }, quote! { *= }),
+            (OpsType::Div, false) => (quote! { Div }, quote! { div }, quote! { / }),
+            (OpsType::Div, true) => (quote! { DivAssign }, quote! { div_assign }, quote! { /= }),
+            _ => unreachable!("scalar operations are only available for `mul` and `div`"),
+        };
+
+        let mut bounds: Vec<WherePredicate> = Vec::new();
+        let mut fields = Vec::with_capacity(syntax.fields.len());
+        for field in &syntax.fields {
+            let ident = &field.ident;
+            let ty = &field.field_type;
+            let ops = &field.config.ops;
+            let field_op = match (ops_type, assign) {
+                (OpsType::Mul, false) => &ops.mul,
+                (OpsType::Mul, true) => &ops.mul_assign,
+                (OpsType::Div, false) => &ops.div,
+                _ => &ops.div_assign,
+            };
+            let ignored = matches!(field_op, Some(OpsOperationType::Ignore));
+
+            if !ignored && type_uses_params(ty, &syntax.generics) {
+                bounds.push(if assign {
+                    parse_quote! { #ty: ::std::ops::#trait_name<#scalar> }
+                } else {
+                    parse_quote! { #ty: ::std::ops::#trait_name<#scalar, Output = #ty> }
+                });
+            }
 
-impl OpsAssignableType {
-    fn from_str(s: impl AsRef<str>) -> Option<Self> {
-        match s.as_ref() {
-            "both" | "all" => Some(Self::Both),
-            "assign" => Some(Self::Assign),
-            "plain" | "default" => Some(Self::Plain),
-            _ => None,
+            let rhs = local("rhs");
+            match (ignored, assign) {
+                (true, true) => {}
+                (true, false) => fields.push(quote! { #ident: self.#ident }),
+                (false, true) => fields.push(quote! { self.#ident #op #rhs }),
+                (false, false) => fields.push(quote! { #ident: self.#ident #op #rhs }),
+            }
         }
-    }
-}
 
-#[derive(Clone, Default)]
-pub struct FieldOpsConfig {
-    add: Option<OpsOperationType>,
-    sub: Option<OpsOperationType>,
-    mul: Option<OpsOperationType>,
-    div: Option<OpsOperationType>,
-    add_assign: Option<OpsOperationType>,
-    sub_assign: Option<OpsOperationType>,
-    mul_assign: Option<OpsOperationType>,
-    div_assign: Option<OpsOperationType>,
-}
+        let ident = &syntax.ident;
+        let generics = syntax
+            .config
+            .ops
+            .bounded_generics(&syntax.generics_for(&syntax.config.where_bounds.ops), bounds)?;
+        let (impl_g, type_g, where_clause) = generics.split_for_impl();
+        let rhs = local("rhs");
 
-macro_rules! __help_impl_field_config_match {
-    (
-        $meta: ident, $config: ident, $err:ident:
-        $ident:ident;
-        $($ident2:ident;)+
-    ) => {
-        if $meta.path().is_ident(stringify!($ident)) {
-            match $meta {
-                Meta::Path(_) => $config.$ident = Some(Default::default()),
-                Meta::NameValue(MetaNameValue { lit, .. }) => {
-                    match OpsOperationType::from_lit(lit) {
-                        Ok(op) => $config.$ident = Some(op),
-                        Err(e) => $err.update_or_combine(e),
+        if assign {
+            Ok(quote! {
+                impl #impl_g ::std::ops::#trait_name<#scalar> for #ident #type_g #where_clause {
+                    fn #trait_fn(&mut self, #rhs: #scalar) {
+                        #(#fields;)*
                     }
                 }
-                _ => $err.update_or_combine(syn::Error::new(
-                    $meta.span(),
-                    concat!("invalid ops `", stringify!($ident), "` type")
-                ))
-            }
-        }
-        $(else if $meta.path().is_ident(stringify!($ident2)) {
-            match $meta {
-                Meta::Path(_) => $config.$ident2 = Some(Default::default()),
-                Meta::NameValue(MetaNameValue { lit, .. }) => {
-                    match OpsOperationType::from_lit(lit) {
-                        Ok(op) => $config.$ident2 = Some(op),
-                        Err(e) => $err.update_or_combine(e),
+            })
+        } else {
+            let construct = syntax.construct(fields);
+            Ok(quote! {
+                impl #impl_g ::std::ops::#trait_name<#scalar> for #ident #type_g #where_clause {
+                    type Output = Self;
+
+                    fn #trait_fn(self, #rhs: #scalar) -> Self {
+                        #construct
                     }
                 }
-                _ => $err.update_or_combine(syn::Error::new(
-                    $meta.span(),
-                    concat!("invalid ops `", stringify!($ident2), "` type")
-                ))
-            }
-        })+
-        else {
-            $err.update_or_combine(syn::Error::new($meta.span(), "invalid ops type"));
+            })
         }
-    };
-}
+    }
 
-impl FieldOpsConfig {
-    pub fn from_meta(meta_list: &MetaList) -> syn::Result<FieldOpsConfig> {
-        let mut config: FieldOpsConfig = Default::default();
+    // `ZERO` is required, while `ONE` is only generated if every field has a known unit value.
+    fn impl_identity(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
         let mut err: Option<syn::Error> = None;
-
-        for meta in meta_list.nested.iter().filter_map(|s| match s {
-            NestedMeta::Meta(mt) => Some(mt),
-            _ => None,
-        }) {
-            __help_impl_field_config_match! {
-                meta, config, err:
-                add; sub; mul; div;
-                add_assign; sub_assign; mul_assign; div_assign;
+        let mut zero = Vec::with_capacity(syntax.fields.len());
+        let mut one = Vec::with_capacity(syntax.fields.len());
+
+        for field in &syntax.fields {
+            let ident = &field.ident;
+            match field.config.ops.zero_value(&field.field_type) {
+                Some(v) => zero.push(quote! { #ident: #v }),
+                None => err.update_or_combine(syn::Error::new(
+                    ident.span(),
+                    "cannot infer the zero value of this field, specify it with `#[dfield(ops(zero = \"...\"))]`",
+                )),
+            }
+            if let Some(v) = field.config.ops.one_value(&field.field_type) {
+                one.push(quote! { #ident: #v })
             }
         }
 
+        // the constants can't call `data_default()`
+        if syntax.config.construct == ConstructType::Default && !syntax.config.const_default {
+            err.update_or_combine(syn::Error::new(
+                syntax.option_span("construct"),
+                "`construct = \"default\"` requires `const` for the constants of `ops(identity)`",
+            ));
+        }
         err.ok_or(()).swap()?;
 
-        Ok(config)
+        let one = if one.len() == syntax.fields.len() {
+            let one = syntax.construct_const(one);
+            quote! {
+                pub const ONE: Self = #one;
+            }
+        } else {
+            Default::default()
+        };
+        let zero = syntax.construct_const(zero);
+
+        let is_zero = if syntax.fields.is_empty() {
+            quote! { true }
+        } else {
+            let idents = syntax.fields.iter().map(|f| &f.ident);
+            quote! { #(self.#idents == Self::ZERO.#idents)&&* }
+        };
+        let ident = &syntax.ident;
+        let generics = syntax.generics_for(&syntax.config.where_bounds.ops);
+        let (impl_g, type_g, where_clause) = generics.split_for_impl();
+
+        Ok(quote! {
+            impl #impl_g #ident #type_g #where_clause {
+                pub const ZERO: Self = #zero;
+
+                #one
+
+                pub fn is_zero(&self) -> bool {
+                    #is_zero
+                }
+            }
+        })
+    }
+
+    /// Assertions that the identity constants leave `value` unchanged, for `gen_tests`.
+    pub fn identity_checks(syntax: &RichStructContent, value: &TokenStream2) -> Vec<TokenStream2> {
+        let ops = &syntax.config.ops;
+        if !ops.identity.unwrap_or(false) {
+            return Vec::new();
+        }
+        let ident = &syntax.ident;
+        let has_one = syntax
+            .fields
+            .iter()
+            .all(|f| f.config.ops.one_value(&f.field_type).is_some());
+        let result = local("result");
+        let zero_message = format!("`{}::ZERO` is not zero", ident);
+
+        let mut checks = vec![quote! {
+            ::std::assert!(Self::ZERO.is_zero(), #zero_message);
+        }];
+        let cases = [
+            (ops.add, quote! { + }, quote! { += }, "ZERO"),
+            (ops.sub, quote! { - }, quote! { -= }, "ZERO"),
+            (ops.mul, quote! { * }, quote! { *= }, "ONE"),
+            (ops.div, quote! { / }, quote! { /= }, "ONE"),
+        ];
+        for (mode, op, assign_op, constant) in cases {
+            let Some(mode) = mode else { continue };
+            if constant == "ONE" && !has_one {
+                continue;
+            }
+            let constant_ident = Ident::new(constant, Span::call_site());
+            let computed = match mode {
+                OpsAssignableType::Assign => quote! {
+                    {
+                        let mut #result = #value;
+                        #result #assign_op Self::#constant_ident;
+                        #result
+                    }
+                },
+                _ => quote! { #value #op Self::#constant_ident },
+            };
+            let message = format!("`{} {} {}` differs from the value", ident, op, constant);
+            checks.push(quote! {
+                ::std::assert!(#computed == #value, #message);
+            });
+        }
+        checks
     }
+
+    __help_impl_struct_impl_ops!(non-assign impl_add, add, add_expr, impl_add, Add, add);
+    __help_impl_struct_impl_ops!(non-assign impl_sub, sub, sub_expr, impl_sub, Sub, sub);
+    __help_impl_struct_impl_ops!(non-assign impl_mul, mul, mul_expr, impl_mul, Mul, mul);
+    __help_impl_struct_impl_ops!(non-assign impl_div, div, div_expr, impl_div, Div, div);
+
+    __help_impl_struct_impl_ops!(assign impl_add_assign, add_assign, add_expr, impl_add_assign, AddAssign, add_assign);
+    __help_impl_struct_impl_ops!(assign impl_sub_assign, sub_assign, sub_expr, impl_sub_assign, SubAssign, sub_assign);
+    __help_impl_struct_impl_ops!(assign impl_mul_assign, mul_assign, mul_expr, impl_mul_assign, MulAssign, mul_assign);
+    __help_impl_struct_impl_ops!(assign impl_div_assign, div_assign, div_expr, impl_div_assign, DivAssign, div_assign);
 }
 
-#[derive(Debug, Clone, Default)]
-enum OpsOperationType {
-    Manual(String),
-    #[default]
-    Inherit,
-    Ignore,
+impl FieldOpsConfig {
+    fn zero_value(&self, ty: &Type) -> Option<TokenStream2> {
+        match &self.zero {
+            Some(expr) => Some(quote! { #expr }),
+            None => numeric_primitive(ty).then(|| quote! { 0 as #ty }),
+        }
+    }
+
+    fn one_value(&self, ty: &Type) -> Option<TokenStream2> {
+        match &self.one {
+            Some(expr) => Some(quote! { #expr }),
+            None => numeric_primitive(ty).then(|| quote! { 1 as #ty }),
+        }
+    }
 }
 
 macro_rules! __help_impl_ops_operation {
     (non-assign $name:ident, $ops:tt) => {
-        fn $name(&self, ident: &Ident) -> syn::Result<TokenStream2> {
-            self._impl_ops(ident, quote! { $ops })
+        fn $name(&self, ident: &Ident, template: Option<&LitStr>) -> syn::Result<TokenStream2> {
+            self._impl_ops(ident, template, quote! { $ops })
         }
     };
 
     (assign $name:ident, $ops:tt) => {
-        fn $name(&self, ident: &Ident) -> syn::Result<TokenStream2> {
-            self._impl_ops_assign(ident, quote! { $ops })
+        fn $name(&self, ident: &Ident, template: Option<&LitStr>) -> syn::Result<TokenStream2> {
+            self._impl_ops_assign(ident, template, quote! { $ops })
         }
     };
 }
 
 impl OpsOperationType {
-    fn from_lit(lit: &Lit) -> syn::Result<Self> {
-        match lit {
-            Lit::Str(lit_str) => match lit_str.value().as_str() {
-                "inherit" | "default" => Ok(Self::Inherit),
-                "ignore" | "no" => Ok(Self::Ignore),
-                n => Ok(Self::Manual(n.to_string())),
-            },
-            Lit::Bool(lit_bool) => {
-                if lit_bool.value {
-                    Ok(Self::Inherit)
-                } else {
-                    Ok(Self::Ignore)
-                }
-            }
-            _ => Err(syn::Error::new(lit.span(), "invalid ops operation type")),
-        }
-    }
-
     __help_impl_ops_operation!(non-assign impl_add, +);
     __help_impl_ops_operation!(non-assign impl_sub, -);
     __help_impl_ops_operation!(non-assign impl_mul, *);
@@ -372,25 +559,57 @@ impl OpsOperationType {
     __help_impl_ops_operation!(assign impl_mul_assign, *=);
     __help_impl_ops_operation!(assign impl_div_assign, /=);
 
-    fn _impl_ops(&self, ident: &Ident, op_ident: impl ToTokens) -> syn::Result<TokenStream2> {
-        match self {
-            Self::Ignore => Ok(quote! { self.#ident }),
-            Self::Inherit => Ok(quote! { self.#ident #op_ident rhs.#ident }),
-            Self::Manual(s) => syn::parse_str(&s.replace("$self", "self").replace("$rhs", "rhs")),
+    fn expand_placeholders(lit: &LitStr, ident: &Ident) -> syn::Result<Expr> {
+        let rhs = local("rhs");
+        parse_template(
+            lit,
+            &[
+                ("self", &quote! { self }),
+                ("field", &quote! { self.#ident }),
+                ("rhs", &rhs),
+                ("rhs_field", &quote! { #rhs.#ident }),
+            ],
+        )
+    }
+
+    fn _impl_ops(
+        &self,
+        ident: &Ident,
+        template: Option<&LitStr>,
+        op_ident: impl ToTokens,
+    ) -> syn::Result<TokenStream2> {
+        let template = match self {
+            Self::Manual(s) => Some(s),
+            _ => template,
+        };
+        match (self, template) {
+            (Self::Ignore, _) => Ok(quote! { self.#ident }),
+            (_, None) => {
+                let rhs = local("rhs");
+                Ok(quote! { self.#ident #op_ident #rhs.#ident })
+            }
+            (_, Some(s)) => Ok(Self::expand_placeholders(s, ident)?.to_token_stream()),
         }
     }
 
     fn _impl_ops_assign(
         &self,
         ident: &Ident,
+        template: Option<&LitStr>,
         op_ident: impl ToTokens,
     ) -> syn::Result<TokenStream2> {
-        match self {
-            Self::Ignore => Ok(quote! {}),
-            Self::Inherit => Ok(quote! { self.#ident #op_ident rhs.#ident }),
-            Self::Manual(s) => {
-                let token: Expr =
-                    syn::parse_str(&s.replace("$self", "self").replace("$rhs", "rhs"))?;
+        let template = match self {
+            Self::Manual(s) => Some(s),
+            _ => template,
+        };
+        match (self, template) {
+            (Self::Ignore, _) => Ok(quote! {}),
+            (_, None) => {
+                let rhs = local("rhs");
+                Ok(quote! { self.#ident #op_ident #rhs.#ident })
+            }
+            (_, Some(s)) => {
+                let token = Self::expand_placeholders(s, ident)?;
                 Ok(quote! {
                     self.#ident = #token
                 })
@@ -0,0 +1,149 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::meta::ParseNestedMeta;
+use syn::{parse_quote, Ident, Path, WherePredicate};
+
+use crate::generate::RichStructContent;
+use crate::utils::collect_meta::{is_flag, is_list, parse_nested_options};
+use crate::utils::generics::type_uses_params;
+use crate::utils::hygiene::local;
+
+#[derive(Default)]
+pub struct StructPatchConfig {
+    pub enabled: bool,
+    /// Derive macros forwarded to the patch type.
+    pub derives: Vec<Path>,
+    /// Generate `diff`, which requires all fields to implement `PartialEq` and `Clone`.
+    pub diff: bool,
+}
+
+impl StructPatchConfig {
+    pub fn from_meta(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut config = Self {
+            enabled: true,
+            derives: Vec::new(),
+            diff: false,
+        };
+
+        parse_nested_options(meta, |meta| {
+            if meta.path.is_ident("derive") && is_list(&meta) {
+                parse_nested_options(&meta, |item| {
+                    if !is_flag(&item) {
+                        return Err(item.error("invalid `derive` argument, expect a path"));
+                    }
+                    config.derives.push(item.path);
+                    Ok(())
+                })?;
+            } else if meta.path.is_ident("diff") && is_flag(&meta) {
+                config.diff = true;
+            } else {
+                return Err(meta.error("invalid `patch` argument, see the documentation for more information"));
+            }
+            Ok(())
+        })?;
+
+        Ok(config)
+    }
+
+    pub fn patch_ident(syntax: &RichStructContent) -> Ident {
+        format_ident!("{}Patch", syntax.ident)
+    }
+
+    pub fn impl_patch(syntax: &RichStructContent) -> TokenStream2 {
+        let ident = &syntax.ident;
+        let patch_ident = Self::patch_ident(syntax);
+        let vis = &syntax.vis;
+        let derives = &syntax.config.patch.derives;
+        let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+        let generics = &syntax.generics;
+
+        let fields = syntax.fields.iter().map(|field| {
+            let ident = &field.ident;
+            let ty = &field.field_type;
+            quote! { pub #ident: ::core::option::Option<#ty> }
+        });
+        let idents = syntax.fields.iter().map(|field| &field.ident).collect::<Vec<_>>();
+        let doc = format!("Patch of [`{}`], `None` fields are left untouched.", ident);
+        let derive = if derives.is_empty() {
+            Default::default()
+        } else {
+            quote! { #[derive(#(#derives),*)] }
+        };
+        let diff = if syntax.config.patch.diff {
+            Self::impl_diff(syntax)
+        } else {
+            Default::default()
+        };
+        let (rhs, value) = (local("rhs"), local("value"));
+
+        quote! {
+            #[doc = #doc]
+            #derive
+            #vis struct #patch_ident #generics #where_clause {
+                #(#fields),*
+            }
+
+            impl #impl_g ::std::default::Default for #patch_ident #type_g #where_clause {
+                fn default() -> Self {
+                    Self {
+                        #(#idents: ::core::option::Option::None),*
+                    }
+                }
+            }
+
+            impl #impl_g ::std::ops::AddAssign<#patch_ident #type_g> for #ident #type_g #where_clause {
+                fn add_assign(&mut self, #rhs: #patch_ident #type_g) {
+                    #(if let ::core::option::Option::Some(#value) = #rhs.#idents {
+                        self.#idents = #value;
+                    })*
+                }
+            }
+
+            #diff
+        }
+    }
+
+    fn impl_diff(syntax: &RichStructContent) -> TokenStream2 {
+        let ident = &syntax.ident;
+        let patch_ident = Self::patch_ident(syntax);
+
+        let mut generics = syntax.generics.clone();
+        let bounds = syntax
+            .fields
+            .iter()
+            .filter(|f| type_uses_params(&f.field_type, &syntax.generics))
+            .map(|f| -> WherePredicate {
+                let ty = &f.field_type;
+                parse_quote! { #ty: ::std::cmp::PartialEq + ::std::clone::Clone }
+            })
+            .collect::<Vec<_>>();
+        if !bounds.is_empty() {
+            generics.make_where_clause().predicates.extend(bounds);
+        }
+        let (impl_g, type_g, where_clause) = generics.split_for_impl();
+
+        let other = local("other");
+        let fields = syntax.fields.iter().map(|field| {
+            let ident = &field.ident;
+            quote! {
+                #ident: if self.#ident != #other.#ident {
+                    ::core::option::Option::Some(::std::clone::Clone::clone(&#other.#ident))
+                } else {
+                    ::core::option::Option::None
+                }
+            }
+        });
+
+        quote! {
+            impl #impl_g #ident #type_g #where_clause {
+                /// Records the fields of `other` which differ from `self`,
+                /// so that applying the patch to `self` results in `other`.
+                pub fn diff(&self, #other: &Self) -> #patch_ident #type_g {
+                    #patch_ident {
+                        #(#fields),*
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::meta::ParseNestedMeta;
+use syn::{parse_quote, Expr, LitStr, WherePredicate};
+
+use crate::generate::RichStructContent;
+use crate::utils::collect_meta::{is_flag, is_value, parse_expr, parse_nested_options, str_value};
+use crate::utils::hygiene::{local, parse_template};
+use crate::utils::synerr::{ResultExt, SynErrorExt};
+
+#[derive(Default)]
+pub struct FieldRandomConfig {
+    /// Use the default value of the field instead.
+    pub default: bool,
+    /// A range passed to `Rng::gen_range`.
+    pub range: Option<Expr>,
+    /// A custom generator, `$rng` refers to the `&mut Rng`.
+    pub gen: Option<LitStr>,
+}
+
+impl FieldRandomConfig {
+    pub fn from_meta(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut config: Self = Default::default();
+
+        parse_nested_options(meta, |meta| {
+            let invalid = || meta.error("invalid `random` argument, see the documentation for more information");
+            if meta.path.is_ident("default") && is_flag(&meta) {
+                config.default = true;
+            } else if meta.path.is_ident("range") && is_value(&meta) {
+                match parse_expr(&meta)? {
+                    Expr::Range(range) if range.start.is_some() && range.end.is_some() => {
+                        config.range = Some(Expr::Range(range))
+                    }
+                    expr => return Err(syn::Error::new(
+                        expr.span(),
+                        "`range` expects a bounded range expression, e.g. `0..100` or `0..=100`",
+                    )),
+                }
+            } else if meta.path.is_ident("gen") && is_value(&meta) {
+                config.gen = Some(str_value(&meta).map_err(|_| invalid())?);
+            } else {
+                return Err(invalid());
+            }
+            Ok(())
+        })?;
+
+        if config.range.is_some() && config.gen.is_some() {
+            return Err(meta.error("`range` and `gen` cannot be used together"));
+        }
+
+        Ok(config)
+    }
+}
+
+pub fn impl_random(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+    let mut stmts = Vec::new();
+    let mut defaults = Vec::new();
+    let mut err: Option<syn::Error> = None;
+    let rng = local("__ds_rng");
+    for field in &syntax.fields {
+        let config = &field.config.random;
+        let ident = &field.ident;
+        let ty = &field.field_type;
+        if config.default {
+            match &field.config.default_value {
+                Some(default_expr) => defaults.push(quote! { let #ident: #ty = #default_expr; }),
+                None => err.update_or_combine(syn::Error::new(
+                    field.option_span("random"),
+                    "`random(default)` requires a default value",
+                )),
+            }
+            continue;
+        }
+        match (&config.range, &config.gen) {
+            (Some(Expr::Range(range)), _) => {
+                // the bounds are annotated, or unsuffixed integers would be inferred as `i32`
+                let bound = |b: &Option<Box<Expr>>| {
+                    let bound = local("bound");
+                    b.as_ref().map(|b| quote! { { let #bound: #ty = #b; #bound } })
+                };
+                let (from, limits, to) = (bound(&range.start), &range.limits, bound(&range.end));
+                stmts.push(quote! {
+                    let #ident: #ty = ::rand::Rng::gen_range(#rng, #from #limits #to);
+                })
+            }
+            (_, Some(s)) => match parse_template(s, &[("rng", &rng)]) {
+                Ok(expr) => stmts.push(quote! { let #ident: #ty = #expr; }),
+                Err(e) => err.update_or_combine(e),
+            },
+            _ => stmts.push(quote! { let #ident: #ty = ::rand::Rng::gen(#rng); }),
+        }
+    }
+    err.ok_or(()).swap()?;
+
+    let mut generics = syntax.generics.clone();
+    let predicates = syntax
+        .generics
+        .type_params()
+        .map(|t| {
+            let ident = &t.ident;
+            let predicate: WherePredicate = parse_quote! {
+                ::rand::distributions::Standard: ::rand::distributions::Distribution<#ident>
+            };
+            predicate
+        })
+        .collect::<Vec<_>>();
+    if !predicates.is_empty() {
+        generics.make_where_clause().predicates.extend(predicates);
+    }
+    let (impl_g, type_g, where_clause) = generics.split_for_impl();
+
+    let ident = &syntax.ident;
+    let construct = syntax.construct(syntax.fields.iter().map(|f| {
+        let ident = &f.ident;
+        quote! { #ident }
+    }));
+
+    Ok(quote! {
+        impl #impl_g #ident #type_g #where_clause {
+            /// Samples a random instance of the structure.
+            pub fn random<__DsRng: ::rand::Rng + ?::core::marker::Sized>(#rng: &mut __DsRng) -> Self {
+                #(#stmts)*
+                #(#defaults)*
+                #construct
+            }
+        }
+    })
+}
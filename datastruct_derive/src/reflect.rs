@@ -0,0 +1,381 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::Ident;
+
+use crate::generate::RichStructContent;
+use crate::utils::generics::{generic_args, type_uses_params};
+use crate::utils::hygiene::local;
+use crate::utils::source::{doc_text, type_text};
+
+/// Generate `FIELD_NAMES` and `FIELDS` constants describing the fields.
+pub fn impl_reflect(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+    let krate = &syntax.config.crate_path;
+    let ident = &syntax.ident;
+    let vis = &syntax.vis;
+    let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+
+    let names = syntax
+        .fields
+        .iter()
+        .map(|f| f.ident.unraw().to_string())
+        .collect::<Vec<_>>();
+    let infos = syntax.fields.iter().zip(&names).map(|(field, name)| {
+        let type_name = type_text(&field.field_type);
+        let has_default = field.config.default_value.is_some();
+        let doc = doc_text(&field.attrs);
+        quote! {
+            #krate::FieldInfo {
+                name: #name,
+                type_name: #type_name,
+                has_default: #has_default,
+                doc: #doc,
+            }
+        }
+    });
+
+    let any = if syntax.config.reflect.any {
+        impl_any(syntax)
+    } else {
+        Default::default()
+    };
+    let fields_impl = if syntax.config.reflect.debug {
+        impl_fields(syntax)
+    } else {
+        Default::default()
+    };
+    let compare_impl = if syntax.config.reflect.compare {
+        impl_compare(syntax)?
+    } else {
+        Default::default()
+    };
+
+    Ok(quote! {
+        impl #impl_g #ident #type_g #where_clause {
+            /// Names of the fields.
+            #vis const FIELD_NAMES: &'static [&'static str] = &[#(#names),*];
+            /// Metadata of the fields.
+            #vis const FIELDS: &'static [#krate::FieldInfo] = &[#(#infos),*];
+
+            #any
+        }
+
+        #fields_impl
+
+        #compare_impl
+    })
+}
+
+fn impl_fields(syntax: &RichStructContent) -> TokenStream2 {
+    let krate = &syntax.config.crate_path;
+    let ident = &syntax.ident;
+    let generics = syntax.generics_for(&syntax.config.where_bounds.debug);
+    let (impl_g, type_g, where_clause) = generics.split_for_impl();
+    let arms = syntax
+        .fields
+        .iter()
+        .filter(|field| !field.config.no_debug)
+        .map(|field| {
+            let field_ident = &field.ident;
+            let name = field_ident.unraw().to_string();
+            quote! {
+                #name => ::core::option::Option::Some(&self.#field_ident as &dyn ::core::fmt::Debug),
+            }
+        });
+
+    quote! {
+        impl #impl_g #krate::DataStructFields for #ident #type_g #where_clause {
+            const FIELD_NAMES: &'static [&'static str] = Self::FIELD_NAMES;
+
+            fn field_debug(&self, name: &str) -> ::core::option::Option<&dyn ::core::fmt::Debug> {
+                match name {
+                    #(#arms)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+    }
+}
+
+fn impl_any(syntax: &RichStructContent) -> TokenStream2 {
+    let krate = &syntax.config.crate_path;
+    let vis = &syntax.vis;
+    let names = syntax.fields.iter().map(|f| f.ident.unraw().to_string()).collect::<Vec<_>>();
+    let idents = syntax.fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    let setters = syntax.fields.iter().zip(&names).map(|(field, name)| {
+        let ident = &field.ident;
+        let ty = &field.field_type;
+        let type_name = type_text(ty);
+        quote! {
+            #name => match value.downcast::<#ty>() {
+                ::core::result::Result::Ok(value) => {
+                    self.#ident = *value;
+                    ::core::result::Result::Ok(())
+                }
+                ::core::result::Result::Err(_) => ::core::result::Result::Err(
+                    #krate::SetFieldError::TypeMismatch {
+                        field: #name,
+                        expected: #type_name,
+                    },
+                ),
+            },
+        }
+    });
+
+    quote! {
+        /// Get a field by its name, or `None` if there's no such field.
+        #vis fn field(&self, name: &str) -> ::core::option::Option<&dyn ::core::any::Any>
+        where
+            Self: 'static,
+        {
+            match name {
+                #(#names => ::core::option::Option::Some(&self.#idents),)*
+                _ => ::core::option::Option::None,
+            }
+        }
+
+        /// Get a mutable reference to a field by its name, or `None` if there's no such field.
+        #vis fn field_mut(&mut self, name: &str) -> ::core::option::Option<&mut dyn ::core::any::Any>
+        where
+            Self: 'static,
+        {
+            match name {
+                #(#names => ::core::option::Option::Some(&mut self.#idents),)*
+                _ => ::core::option::Option::None,
+            }
+        }
+
+        /// Set a field by its name, the value must be of the field's type.
+        #vis fn set_field(
+            &mut self,
+            name: &str,
+            value: ::std::boxed::Box<dyn ::core::any::Any>,
+        ) -> ::core::result::Result<(), #krate::SetFieldError>
+        where
+            Self: 'static,
+        {
+            match name {
+                #(#setters)*
+                _ => ::core::result::Result::Err(#krate::SetFieldError::UnknownField),
+            }
+        }
+    }
+}
+
+// Fields ordered by `cmp(ord)` use `Ord`, those only ordered by `cmp(pord)` use `PartialOrd`,
+// and incomparable values are considered equal so that the next field decides.
+// The fields are selected by the `field_enum` variants, which `check_conflicts` requires.
+fn impl_compare(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+    let krate = &syntax.config.crate_path;
+    let ident = &syntax.ident;
+    let vis = &syntax.vis;
+    let generics = syntax.generics_for(&syntax.config.where_bounds.cmp);
+    let (impl_g, type_g, where_clause) = generics.split_for_impl();
+    let field_enum = format_ident!("{}Field", ident);
+    let (other, field, order, ordering) = (local("other"), local("field"), local("order"), local("ordering"));
+
+    let mut comparable = false;
+    let arms = syntax
+        .fields
+        .iter()
+        .map(|f| {
+            let field_ident = &f.ident;
+            let variant = pascal_case(field_ident);
+            let cmp = &f.config.cmp;
+            let compare = if cmp.ord.is_some() {
+                quote! { ::std::cmp::Ord::cmp(&self.#field_ident, &#other.#field_ident) }
+            } else if cmp.partial_ord.is_some() {
+                quote! {
+                    ::std::cmp::PartialOrd::partial_cmp(&self.#field_ident, &#other.#field_ident)
+                        .unwrap_or(::std::cmp::Ordering::Equal)
+                }
+            } else {
+                let message = format!(
+                    "`{}::compare_by` can't compare field `{}`, which has neither `cmp(ord)` nor `cmp(pord)`",
+                    ident,
+                    field_ident.unraw()
+                );
+                return quote! { #field_enum::#variant => ::core::panic!(#message), };
+            };
+            comparable = true;
+            quote! { #field_enum::#variant => #compare, }
+        })
+        .collect::<Vec<_>>();
+    if !comparable {
+        return Err(syn::Error::new(
+            syntax.option_span("reflect"),
+            "`reflect(compare)` requires at least one field with `cmp(ord)` or `cmp(pord)`",
+        ));
+    }
+
+    Ok(quote! {
+        impl #impl_g #ident #type_g #where_clause {
+            /// Compares by the fields in order, each in the ascending or the descending order.
+            ///
+            /// # Panics
+            ///
+            /// Panics if a field has neither `cmp(ord)` nor `cmp(pord)`.
+            #vis fn compare_by(&self, #other: &Self, fields: &[(#field_enum, #krate::Order)]) -> ::std::cmp::Ordering {
+                for &(#field, #order) in fields {
+                    let #ordering = match #field {
+                        #(#arms)*
+                    };
+                    let #ordering = match #order {
+                        #krate::Order::Ascending => #ordering,
+                        #krate::Order::Descending => #ordering.reverse(),
+                    };
+                    if #ordering != ::std::cmp::Ordering::Equal {
+                        return #ordering;
+                    }
+                }
+                ::std::cmp::Ordering::Equal
+            }
+        }
+    })
+}
+
+/// Generate a `<Struct>Visitor` trait with one method per field, and `accept` on the structure.
+pub fn impl_visit(syntax: &RichStructContent) -> TokenStream2 {
+    let ident = &syntax.ident;
+    let vis = &syntax.vis;
+    let generics = &syntax.generics;
+    let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+    let visitor_ident = format_ident!("{}Visitor", ident);
+    let args = generic_args(generics);
+
+    let (methods, calls): (Vec<_>, Vec<_>) = syntax
+        .fields
+        .iter()
+        .map(|field| {
+            let field_ident = &field.ident;
+            let ty = &field.field_type;
+            let method = format_ident!("visit_{}", field_ident);
+            let doc = format!("Visit the `{}` field, does nothing by default.", field_ident);
+            (
+                quote! {
+                    #[doc = #doc]
+                    #[allow(unused_variables)]
+                    fn #method(&mut self, value: &#ty) {}
+                },
+                quote! { visitor.#method(&self.#field_ident); },
+            )
+        })
+        .unzip();
+    let doc = format!("Visitor of [`{}`], see `{}::accept`.", ident, ident);
+
+    quote! {
+        #[doc = #doc]
+        #vis trait #visitor_ident #generics #where_clause {
+            #(#methods)*
+        }
+
+        impl #impl_g #ident #type_g #where_clause {
+            /// Visit every field with the visitor, in order.
+            #vis fn accept<__DsVisitor: #visitor_ident<#(#args),*>>(&self, visitor: &mut __DsVisitor) {
+                #(#calls)*
+            }
+        }
+    }
+}
+
+// `max_connections` -> `MaxConnections`
+fn pascal_case(ident: &Ident) -> Ident {
+    let name = ident
+        .unraw()
+        .to_string()
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect::<String>();
+    format_ident!("{}", name, span = ident.span())
+}
+
+/// Generate the `<Struct>Field` and `<Struct>FieldValue` enums, with `get`, `set` and `apply_values` on the structure.
+pub fn impl_field_enum(syntax: &RichStructContent) -> TokenStream2 {
+    let ident = &syntax.ident;
+    let vis = &syntax.vis;
+    let generics = &syntax.generics;
+    let (impl_g, type_g, where_clause) = generics.split_for_impl();
+    let field_ident = format_ident!("{}Field", ident);
+    let value_ident = format_ident!("{}FieldValue", ident);
+    let args = generic_args(generics);
+
+    let idents = syntax.fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    let names = idents.iter().map(|i| i.unraw().to_string()).collect::<Vec<_>>();
+    let variants = idents.iter().map(|i| pascal_case(i)).collect::<Vec<_>>();
+    let types = syntax.fields.iter().map(|f| &f.field_type).collect::<Vec<_>>();
+    let len = idents.len();
+    // concrete types are not bounded, as a false bound on them is an error
+    let clone_bounds = types
+        .iter()
+        .filter(|ty| type_uses_params(ty, generics))
+        .map(|ty| quote! { #ty: ::core::clone::Clone });
+    let value = local("value");
+    let field_doc = format!("Fields of [`{}`].", ident);
+    let value_doc = format!("Values of the fields of [`{}`].", ident);
+
+    quote! {
+        #[doc = #field_doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #vis enum #field_ident {
+            #(#variants),*
+        }
+
+        impl #field_ident {
+            /// Every field, in declaration order.
+            #vis const ALL: [Self; #len] = [#(Self::#variants),*];
+
+            /// Name of the field.
+            #vis const fn name(self) -> &'static str {
+                match self {
+                    #(Self::#variants => #names,)*
+                }
+            }
+        }
+
+        #[doc = #value_doc]
+        #vis enum #value_ident #generics #where_clause {
+            #(#variants(#types)),*
+        }
+
+        impl #impl_g #value_ident #type_g #where_clause {
+            /// The field of the value.
+            #vis fn field(&self) -> #field_ident {
+                match self {
+                    #(Self::#variants(_) => #field_ident::#variants,)*
+                }
+            }
+        }
+
+        impl #impl_g #ident #type_g #where_clause {
+            /// Get a clone of the field.
+            #vis fn get(&self, field: #field_ident) -> #value_ident<#(#args),*>
+            where
+                #(#clone_bounds,)*
+            {
+                match field {
+                    #(#field_ident::#variants => #value_ident::#variants(::core::clone::Clone::clone(&self.#idents)),)*
+                }
+            }
+
+            /// Set the field of the value.
+            #vis fn set(&mut self, #value: #value_ident<#(#args),*>) {
+                match #value {
+                    #(#value_ident::#variants(#value) => self.#idents = #value,)*
+                }
+            }
+
+            /// Set the fields of the values in order, so a later value of the same field wins.
+            #vis fn apply_values(&mut self, values: impl ::core::iter::IntoIterator<Item = #value_ident<#(#args),*>>) {
+                for #value in values {
+                    self.set(#value);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+
+use crate::generate::{RichStructContent, StructFieldContent};
+
+// Statements computing the default value of the fields, the last one of each entry is the field itself.
+// Fields are sorted by `seq`, so defaults may refer to the previous fields.
+fn default_bodies(syntax: &RichStructContent) -> Vec<(&StructFieldContent, TokenStream2)> {
+    let stmts = syntax
+        .fields
+        .iter()
+        .filter_map(|field| {
+            let name = &field.ident;
+            let ty = &field.field_type;
+            field.config.default_value.as_ref().map(|default_expr| {
+                (
+                    field,
+                    quote_spanned! {
+                        default_expr.span() => let #name: #ty = #default_expr;
+                    },
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+
+    stmts
+        .iter()
+        .enumerate()
+        .map(|(idx, (field, _))| {
+            let previous = stmts[..=idx].iter().map(|(_, stmt)| stmt);
+            (*field, quote! { #(#previous)* })
+        })
+        .collect()
+}
+
+/// Generate `__default_xxx() -> Type` for every field with a default value,
+/// to be referred to by `#[serde(default = "Struct::__default_xxx")]`.
+pub fn impl_serde_defaults(syntax: &RichStructContent) -> TokenStream2 {
+    let ident = &syntax.ident;
+    let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+
+    let fns = default_bodies(syntax).into_iter().map(|(field, body)| {
+        let name = &field.ident;
+        let ty = &field.field_type;
+        let func = format_ident!("__default_{}", name);
+        quote! {
+            #[doc(hidden)]
+            #[allow(unused_variables)]
+            pub fn #func() -> #ty {
+                #body
+                #name
+            }
+        }
+    });
+
+    quote! {
+        impl #impl_g #ident #type_g #where_clause {
+            #(#fns)*
+        }
+    }
+}
+
+/// Generate `xxx_is_default(&Type) -> bool` for every field with a default value,
+/// to be referred to by `#[serde(skip_serializing_if = "Struct::xxx_is_default")]`.
+pub fn impl_serde_is_default(syntax: &RichStructContent) -> TokenStream2 {
+    let ident = &syntax.ident;
+    let (impl_g, type_g, where_clause) = syntax.generics.split_for_impl();
+
+    let fns = default_bodies(syntax).into_iter().map(|(field, body)| {
+        let name = &field.ident;
+        let ty = &field.field_type;
+        let func = format_ident!("{}_is_default", name);
+        let doc = format!("Whether the value equals the default value of `{}`.", name);
+        quote! {
+            #[doc = #doc]
+            #[allow(unused_variables)]
+            pub fn #func(__ds_value: &#ty) -> bool
+            where
+                #ty: ::core::cmp::PartialEq,
+            {
+                #body
+                *__ds_value == #name
+            }
+        }
+    });
+
+    quote! {
+        impl #impl_g #ident #type_g #where_clause {
+            #(#fns)*
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_quote, WherePredicate};
+
+use crate::clone::FieldCloneConfig;
+use crate::generate::RichStructContent;
+use crate::utils::generics::type_uses_params;
+use crate::utils::hygiene::local;
+use crate::utils::synerr::{ResultExt, SynErrorExt};
+
+/// Generate a `<Struct>Snapshot` holding clones of the saved fields, with `snapshot` and `restore`.
+// Fields with `snapshot = false` or `clone = false` are not saved, and are kept by `restore`.
+pub fn impl_snapshot(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+    let ident = &syntax.ident;
+    let vis = &syntax.vis;
+    let generics = &syntax.generics;
+    let (impl_g, type_g, where_clause) = generics.split_for_impl();
+    let snapshot_ident = format_ident!("{}Snapshot", ident);
+    let snapshot = local("snapshot");
+
+    let mut fields = Vec::new();
+    let mut err: Option<syn::Error> = None;
+    for field in syntax.fields.iter().filter(|f| f.config.snapshot) {
+        match field.config.clone.impl_clone(&field.ident) {
+            Ok(Some(expr)) => fields.push((field, expr)),
+            Ok(None) => {}
+            Err(e) => err.update_or_combine(e),
+        }
+    }
+    err.ok_or(()).swap()?;
+
+    let idents = fields.iter().map(|(f, _)| &f.ident).collect::<Vec<_>>();
+    let types = fields.iter().map(|(f, _)| &f.field_type);
+    let exprs = fields.iter().map(|(_, expr)| expr);
+    // every parameter is used, even if the fields using it are not saved
+    let (marker, marker_init) = if generics.params.is_empty() {
+        (quote! {}, quote! {})
+    } else {
+        (
+            quote! { __ds_marker: ::core::marker::PhantomData<fn() -> #ident #type_g>, },
+            quote! { __ds_marker: ::core::marker::PhantomData, },
+        )
+    };
+    // only the types using the parameters are bounded, as a false bound on a concrete type is an error
+    let mut clone_generics = syntax.generics_for(&syntax.config.where_bounds.clone);
+    let bounds = fields
+        .iter()
+        .filter(|(f, _)| matches!(f.config.clone, FieldCloneConfig::Inherit))
+        .map(|(f, _)| &f.field_type)
+        .filter(|ty| type_uses_params(ty, generics))
+        .map(|ty| -> WherePredicate { parse_quote! { #ty: ::core::clone::Clone } })
+        .collect::<Vec<_>>();
+    if !bounds.is_empty() {
+        clone_generics.make_where_clause().predicates.extend(bounds);
+    }
+    let (_, _, clone_where_clause) = clone_generics.split_for_impl();
+    let doc = format!("Saved fields of [`{}`], see `{}::snapshot`.", ident, ident);
+
+    Ok(quote! {
+        #[doc = #doc]
+        #vis struct #snapshot_ident #generics #where_clause {
+            #(#idents: #types,)*
+            #marker
+        }
+
+        impl #impl_g #ident #type_g #where_clause {
+            /// Save the fields, so that they can be restored later, e.g. for undo.
+            #vis fn snapshot(&self) -> #snapshot_ident #type_g
+            #clone_where_clause
+            {
+                #snapshot_ident {
+                    #(#idents: #exprs,)*
+                    #marker_init
+                }
+            }
+
+            /// Restore the saved fields, the others are kept.
+            #vis fn restore(&mut self, #snapshot: #snapshot_ident #type_g) {
+                #(self.#idents = #snapshot.#idents;)*
+            }
+        }
+    })
+}
@@ -0,0 +1,115 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{parse_quote, Generics, WherePredicate};
+
+use crate::generate::RichStructContent;
+use crate::utils::generics::type_uses_params;
+use crate::utils::hygiene::local;
+use crate::utils::synerr::{ResultExt, SynErrorExt};
+
+/// Generate `to_map` and `from_map`, converting the fields with `Display` and `FromStr`.
+// Fields are sorted by `seq`, so defaults may refer to the previous fields, either parsed or defaulted.
+pub fn impl_string_map(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+    let krate = &syntax.config.crate_path;
+    let ident = &syntax.ident;
+    let vis = &syntax.vis;
+    let generics = &syntax.generics;
+    let (map, value, err_value) = (local("map"), local("value"), local("err"));
+
+    let mut inserts = Vec::new();
+    let mut stmts = Vec::new();
+    let mut to_bounds: Vec<WherePredicate> = Vec::new();
+    let mut from_bounds: Vec<WherePredicate> = Vec::new();
+    let mut err: Option<syn::Error> = None;
+    for field in &syntax.fields {
+        let field_ident = &field.ident;
+        let ty = &field.field_type;
+        let key = field_ident.unraw().to_string();
+        let default = match &field.config.default_value {
+            Some(default_expr) => quote_spanned! { default_expr.span() => #default_expr },
+            None => quote! {
+                return ::core::result::Result::Err(#krate::FromMapError::Missing { field: #key })
+            },
+        };
+        if !field.config.string_map {
+            match &field.config.default_value {
+                Some(default_expr) => stmts.push(quote_spanned! {
+                    default_expr.span() => let #field_ident: #ty = #default_expr;
+                }),
+                None => err.update_or_combine(syn::Error::new(
+                    field.option_span("string_map"),
+                    "`string_map = false` requires a default value for `from_map`",
+                )),
+            }
+            continue;
+        }
+
+        if type_uses_params(ty, generics) {
+            to_bounds.push(parse_quote! { #ty: ::core::fmt::Display });
+            from_bounds.push(parse_quote! { #ty: ::core::str::FromStr });
+            from_bounds.push(parse_quote! { <#ty as ::core::str::FromStr>::Err: ::core::fmt::Display });
+        }
+        inserts.push(quote! {
+            #map.insert(
+                ::std::string::String::from(#key),
+                ::std::string::ToString::to_string(&self.#field_ident),
+            );
+        });
+        stmts.push(quote! {
+            let #field_ident: #ty = match #map.get(#key) {
+                ::core::option::Option::Some(#value) => match ::core::str::FromStr::from_str(#value) {
+                    ::core::result::Result::Ok(#value) => #value,
+                    ::core::result::Result::Err(#err_value) => {
+                        return ::core::result::Result::Err(#krate::FromMapError::Invalid {
+                            field: #key,
+                            value: ::std::clone::Clone::clone(#value),
+                            message: ::std::string::ToString::to_string(&#err_value),
+                        })
+                    }
+                },
+                ::core::option::Option::None => #default,
+            };
+        });
+    }
+    err.ok_or(()).swap()?;
+
+    let (impl_g, type_g, where_clause) = generics.split_for_impl();
+    // `from_map` evaluates the default values, so it has the `default` bounds
+    let where_of = |mut generics: Generics, bounds: Vec<WherePredicate>| {
+        if !bounds.is_empty() {
+            generics.make_where_clause().predicates.extend(bounds);
+        }
+        generics.where_clause
+    };
+    let to_where = where_of(generics.clone(), to_bounds);
+    let from_where = where_of(syntax.generics_for(&syntax.config.where_bounds.default), from_bounds);
+    let idents = syntax.fields.iter().map(|f| &f.ident);
+
+    Ok(quote! {
+        impl #impl_g #ident #type_g #where_clause {
+            /// Converts the fields to strings by their names, with `Display`.
+            #vis fn to_map(&self) -> ::std::collections::HashMap<::std::string::String, ::std::string::String>
+            #to_where
+            {
+                let mut #map = ::std::collections::HashMap::new();
+                #(#inserts)*
+                #map
+            }
+
+            /// Parses the fields from strings by their names, with `FromStr`,
+            /// the missing fields are initialized with their default values.
+            #vis fn from_map(
+                #map: &::std::collections::HashMap<::std::string::String, ::std::string::String>,
+            ) -> ::core::result::Result<Self, #krate::FromMapError>
+            #from_where
+            {
+                #(#stmts)*
+                ::core::result::Result::Ok(Self {
+                    #(#idents),*
+                })
+            }
+        }
+    })
+}
@@ -1,8 +1,8 @@
+use syn::ext::IdentExt;
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
 use syn::{braced, token, Attribute, Expr, Generics, Ident, Token, Type, Visibility};
 
-#[derive(Clone)]
 pub struct RichStruct {
     pub attrs: Vec<Attribute>,
     pub vis: Visibility,
@@ -16,19 +16,36 @@ pub struct RichStruct {
 impl RichStruct {
     pub fn parse_struct(input: ParseStream) -> syn::Result<Self> {
         let content;
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        if input.peek(Token![enum]) || input.peek(Token![union]) {
+            let kind: Ident = input.call(Ident::parse_any)?;
+            return Err(syn::Error::new(
+                kind.span(),
+                format!("DataStruct currently supports structs with named fields; {}s are not supported", kind),
+            ));
+        }
+        let struct_token = input.parse()?;
+        let ident = input.parse()?;
+        let generics = input.parse()?;
+        if !input.peek(token::Brace) {
+            return Err(syn::Error::new(
+                input.span(),
+                "DataStruct currently supports structs with named fields; tuple and unit structs are not supported",
+            ));
+        }
         Ok(RichStruct {
-            attrs: input.call(Attribute::parse_outer)?,
-            vis: input.parse()?,
-            _struct_token: input.parse()?,
-            ident: input.parse()?,
-            generics: input.parse()?,
+            attrs,
+            vis,
+            _struct_token: struct_token,
+            ident,
+            generics,
             _brace_token: braced!(content in input),
-            fields: content.parse_terminated(StructField::parse_field)?,
+            fields: content.parse_terminated(StructField::parse_field, Token![,])?,
         })
     }
 }
 
-#[derive(Clone)]
 pub struct StructField {
     pub attrs: Vec<Attribute>,
     pub vis: Visibility,
@@ -55,7 +72,6 @@ impl StructField {
     }
 }
 
-#[derive(Clone)]
 pub struct FieldDefaultValue {
     _eq: Token![=],
     pub value: Expr,
@@ -0,0 +1,44 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::{parse_quote, WherePredicate};
+
+use crate::generate::RichStructContent;
+use crate::utils::generics::type_uses_params;
+
+/// Generate `record_fields`, recording the fields into a `tracing` span, except those with `debug = false`.
+// `Span::record` ignores the fields not declared by the span,
+// so the fields are recorded unconditionally and the span decides what it keeps.
+pub fn impl_tracing(syntax: &RichStructContent) -> TokenStream2 {
+    let ident = &syntax.ident;
+    let vis = &syntax.vis;
+    let fields = syntax.fields.iter().filter(|field| !field.config.no_debug);
+    let records = fields.clone().map(|field| {
+        let field_ident = &field.ident;
+        let name = field_ident.unraw().to_string();
+        quote! {
+            span.record(#name, ::tracing::field::debug(&self.#field_ident));
+        }
+    });
+
+    let mut generics = syntax.generics_for(&syntax.config.where_bounds.debug);
+    let bounds: Vec<WherePredicate> = fields
+        .map(|field| &field.field_type)
+        .filter(|ty| type_uses_params(ty, &syntax.generics))
+        .map(|ty| parse_quote! { #ty: ::core::fmt::Debug })
+        .collect();
+    if !bounds.is_empty() {
+        generics.make_where_clause().predicates.extend(bounds);
+    }
+    let (impl_g, type_g, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_g #ident #type_g #where_clause {
+            /// Records the fields into the span by their names, with `Debug`,
+            /// the span should declare them, like `field = tracing::field::Empty`.
+            #vis fn record_fields(&self, span: &::tracing::Span) {
+                #(#records)*
+            }
+        }
+    }
+}
@@ -1,45 +1,158 @@
 use crate::utils::synerr::{ResultExt, SynErrorExt};
-use proc_macro2::Span;
+use proc_macro2::{Span, TokenStream as TokenStream2, TokenTree};
 use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
-use syn::spanned::Spanned;
-use syn::{Ident, Lit, Meta, MetaList, MetaNameValue, NestedMeta};
+use syn::ext::IdentExt;
+use syn::meta::ParseNestedMeta;
+use syn::parse::{ParseStream, Parser};
+use syn::{Expr, Ident, Lit, LitBool, LitStr, Token};
+
+/// Parses comma-separated options, like the content of `#[dstruct(...)]` or `cmp(...)`.
+///
+/// When `func` fails on an option, the rest of the option is skipped and the error is collected,
+/// so every invalid option is reported at once.
+pub fn parse_options(
+    tokens: TokenStream2,
+    mut func: impl FnMut(ParseNestedMeta) -> syn::Result<()>,
+) -> syn::Result<()> {
+    let mut err: Option<syn::Error> = None;
+    let res = syn::meta::parser(|meta| {
+        let input = meta.input;
+        if let Err(e) = func(meta) {
+            err.update_or_combine(e);
+            skip_option(input)?;
+        }
+        Ok(())
+    })
+    .parse2(tokens);
+    if let Err(e) = res {
+        err.update_or_combine(e);
+    }
+    err.ok_or(()).swap()
+}
+
+/// Parses the parenthesized options of `meta`, like the `eq, ord` of `cmp(eq, ord)`.
+pub fn parse_nested_options(
+    meta: &ParseNestedMeta,
+    func: impl FnMut(ParseNestedMeta) -> syn::Result<()>,
+) -> syn::Result<()> {
+    parse_options(list_tokens(meta)?, func)
+}
+
+/// The tokens between the parentheses of `meta`.
+pub fn list_tokens(meta: &ParseNestedMeta) -> syn::Result<TokenStream2> {
+    let content;
+    syn::parenthesized!(content in meta.input);
+    content.parse()
+}
+
+/// Whether the option is a simple path, like `debug`.
+pub fn is_flag(meta: &ParseNestedMeta) -> bool {
+    meta.input.is_empty() || meta.input.peek(Token![,])
+}
+
+/// Whether the option is a list, like `cmp(...)`.
+pub fn is_list(meta: &ParseNestedMeta) -> bool {
+    meta.input.peek(syn::token::Paren)
+}
+
+/// Whether the option has a value, like `crate = "..."`.
+pub fn is_value(meta: &ParseNestedMeta) -> bool {
+    meta.input.peek(Token![=])
+}
+
+/// The literal value of the option, like the `"..."` of `crate = "..."`.
+pub fn lit_value(meta: &ParseNestedMeta) -> syn::Result<Lit> {
+    meta.value()?.parse()
+}
+
+/// The string value of the option, like the `"..."` of `crate = "..."`.
+pub fn str_value(meta: &ParseNestedMeta) -> syn::Result<LitStr> {
+    meta.value()?.parse()
+}
+
+/// A flag like `debug` or `debug = false`.
+pub fn parse_flag(meta: &ParseNestedMeta) -> syn::Result<bool> {
+    if is_flag(meta) {
+        return Ok(true);
+    }
+    let name = meta.path.get_ident().map(ToString::to_string).unwrap_or_default();
+    let err = || meta.error(format!("`{0}` argument should be like `{0} = true` or simply `{0}`", name));
+    if !is_value(meta) {
+        return Err(err());
+    }
+    meta.value()?.parse::<LitBool>().map(|lit| lit.value).map_err(|_| err())
+}
+
+/// An expression, either written directly like `default = 1 + 1`, or as a string like `default = "1 + 1"`.
+pub fn parse_expr(meta: &ParseNestedMeta) -> syn::Result<Expr> {
+    let input = meta.value()?;
+    if input.peek(LitStr) {
+        let lit: LitStr = input.parse()?;
+        return lit.parse();
+    }
+    input.parse()
+}
+
+/// The tokens of the option after its path, like the `= "..."` of `crate = "..."`.
+///
+/// The tokens are not consumed.
+pub fn option_tokens(input: ParseStream) -> TokenStream2 {
+    let fork = input.fork();
+    let mut tokens = TokenStream2::new();
+    while !fork.is_empty() && !fork.peek(Token![,]) {
+        match fork.parse::<TokenTree>() {
+            Ok(tt) => tokens.extend([tt]),
+            Err(_) => break,
+        }
+    }
+    tokens
+}
+
+/// Consumes the rest of the option, up to the next comma.
+pub fn skip_option(input: ParseStream) -> syn::Result<()> {
+    while !input.is_empty() && !input.peek(Token![,]) {
+        input.parse::<TokenTree>()?;
+    }
+    Ok(())
+}
 
 pub fn collect_meta_set<T: Hash + Eq>(
-    meta_list: &MetaList,
+    meta: &ParseNestedMeta,
     mut func: impl FnMut(&str, Span) -> syn::Result<T>,
 ) -> syn::Result<HashSet<T>> {
     let mut set = HashSet::new();
     let mut err: Option<syn::Error> = None;
 
-    for meta in &meta_list.nested {
-        match meta {
-            NestedMeta::Meta(Meta::Path(pth)) => {
-                if let Some(path) = pth.get_ident() {
-                    match func(&path.to_string(), meta.span()) {
-                        Ok(val) => {
-                            set.insert(val);
-                        }
-                        Err(e) => err.update_or_combine(e),
-                    }
-                } else {
-                    err.update_or_combine(syn::Error::new(
-                        meta.span(),
-                        "invalid meta argument, expect ident or string literal"
-                    ))
-                }
-            }
-            NestedMeta::Lit(Lit::Str(lit)) => match func(&lit.value(), meta.span()) {
+    let parser = |input: ParseStream| {
+        while !input.is_empty() {
+            let item = if input.peek(LitStr) {
+                let lit: LitStr = input.parse()?;
+                Some((lit.value(), lit.span()))
+            } else if input.peek(Ident::peek_any) {
+                let ident = Ident::parse_any(input)?;
+                Some((ident.to_string(), ident.span()))
+            } else {
+                None
+            };
+            let item = item
+                .filter(|_| input.is_empty() || input.peek(Token![,]))
+                .ok_or_else(|| input.error("invalid meta argument, expect ident or string literal"));
+            match item.and_then(|(item, span)| func(&item, span)) {
                 Ok(val) => {
                     set.insert(val);
                 }
                 Err(e) => err.update_or_combine(e),
-            },
-            _ => err.update_or_combine(syn::Error::new(
-                meta.span(),
-                "invalid meta argument, expect ident or string literal",
-            )),
+            }
+            skip_option(input)?;
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
         }
+        Ok(())
+    };
+    if let Err(e) = parser.parse2(list_tokens(meta)?) {
+        err.update_or_combine(e);
     }
 
     err.ok_or(()).swap()?;
@@ -48,43 +161,29 @@ pub fn collect_meta_set<T: Hash + Eq>(
 }
 
 pub fn collect_meta_map<K: Hash + Eq, V>(
-    meta_list: &MetaList,
+    meta: &ParseNestedMeta,
     mut func: impl FnMut(usize, &Ident, Option<&Lit>) -> syn::Result<(K, V)>,
 ) -> syn::Result<HashMap<K, V>> {
     let mut map = HashMap::new();
-    let mut err: Option<syn::Error> = None;
+    let mut idx = 0;
 
-    for (idx, meta) in meta_list.nested.iter().enumerate() {
-        if let NestedMeta::Meta(meta) = meta {
-            match meta {
-                Meta::Path(pth) => {
-                    if let Some(ident) = pth.get_ident() {
-                        let res = func(idx, ident, None);
-                        match res {
-                            Err(e) => err.update_or_combine(e),
-                            Ok(res) => {
-                                map.insert(res.0, res.1);
-                            }
-                        }
-                    }
-                }
-                Meta::NameValue(MetaNameValue { path: pth, lit, .. }) => {
-                    if let Some(ident) = pth.get_ident() {
-                        let res = func(idx, ident, Some(lit));
-                        match res {
-                            Err(e) => err.update_or_combine(e),
-                            Ok(res) => {
-                                map.insert(res.0, res.1);
-                            }
-                        }
-                    }
-                }
-                _ => {}
-            }
-        }
-    }
-
-    err.ok_or(()).swap()?;
+    parse_nested_options(meta, |meta| {
+        let index = idx;
+        idx += 1;
+        let ident = match meta.path.get_ident() {
+            Some(ident) => ident,
+            None => return skip_option(meta.input),
+        };
+        let res = if is_flag(&meta) {
+            func(index, ident, None)?
+        } else if is_value(&meta) {
+            func(index, ident, Some(&lit_value(&meta)?))?
+        } else {
+            return skip_option(meta.input);
+        };
+        map.insert(res.0, res.1);
+        Ok(())
+    })?;
 
     Ok(map)
 }
@@ -0,0 +1,39 @@
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use quote::ToTokens;
+use syn::{GenericParam, Generics, Type};
+
+/// Whether the type refers to any type parameter declared in the generics.
+pub fn type_uses_params(ty: &Type, generics: &Generics) -> bool {
+    let params: Vec<_> = generics
+        .params
+        .iter()
+        .filter_map(|p| match p {
+            GenericParam::Type(t) => Some(&t.ident),
+            _ => None,
+        })
+        .collect();
+
+    fn walk(ts: TokenStream2, params: &[&syn::Ident]) -> bool {
+        ts.into_iter().any(|tt| match tt {
+            TokenTree::Ident(i) => params.iter().any(|p| **p == i),
+            TokenTree::Group(g) => walk(g.stream(), params),
+            _ => false,
+        })
+    }
+
+    !params.is_empty() && walk(ty.to_token_stream(), &params)
+}
+
+/// The generic arguments referring to each parameter, like `'a, T, N`.
+#[cfg(any(feature = "builder", feature = "reflect"))]
+pub fn generic_args(generics: &Generics) -> Vec<TokenStream2> {
+    generics
+        .params
+        .iter()
+        .map(|p| match p {
+            GenericParam::Type(t) => t.ident.to_token_stream(),
+            GenericParam::Lifetime(l) => l.lifetime.to_token_stream(),
+            GenericParam::Const(c) => c.ident.to_token_stream(),
+        })
+        .collect()
+}
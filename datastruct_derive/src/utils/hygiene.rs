@@ -0,0 +1,67 @@
+use proc_macro2::{Group, Ident, Span, TokenStream as TokenStream2, TokenTree};
+use quote::ToTokens;
+use syn::{Expr, LitStr};
+
+/// A local binding of the generated code, which identifiers written by users cannot refer to by accident.
+pub fn local(name: &str) -> Ident {
+    Ident::new(name, Span::mixed_site())
+}
+
+/// Parses a user expression written as a string, substituting each `$placeholder` with its tokens.
+///
+/// The placeholders are pairs of `(placeholder, tokens)`, e.g. `("rhs", local("rhs"))` binds `$rhs`.
+/// They are matched as tokens, so a `$rhs` inside a string of the expression is kept as is,
+/// and every token is located at the literal, so errors in the expression point at the attribute.
+pub fn parse_template(lit: &LitStr, placeholders: &[(&str, &dyn ToTokens)]) -> syn::Result<Expr> {
+    let span = lit.span();
+    let ts: TokenStream2 = syn::parse_str(&lit.value()).map_err(|e| syn::Error::new(span, e))?;
+    let ts = substitute(ts, span, placeholders)?;
+    syn::parse2(ts).map_err(|e| syn::Error::new(span, e))
+}
+
+fn substitute(ts: TokenStream2, span: Span, placeholders: &[(&str, &dyn ToTokens)]) -> syn::Result<TokenStream2> {
+    let mut out = TokenStream2::new();
+    let mut iter = ts.into_iter().peekable();
+    while let Some(tt) = iter.next() {
+        match tt {
+            TokenTree::Punct(p) if p.as_char() == '$' && matches!(iter.peek(), Some(TokenTree::Ident(_))) => {
+                let name = iter.next().unwrap().to_string();
+                let (_, tokens) = placeholders.iter().find(|(n, _)| *n == name).ok_or_else(|| {
+                    let expected = placeholders
+                        .iter()
+                        .map(|(n, _)| format!("`${}`", n))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    syn::Error::new(span, format!("unknown placeholder `${}`, expect one of {}", name, expected))
+                })?;
+                // the placeholder keeps its own hygiene, only its location changes
+                out.extend(tokens.to_token_stream().into_iter().map(|tt| locate(tt, span)));
+            }
+            TokenTree::Group(g) => {
+                let mut group = Group::new(g.delimiter(), substitute(g.stream(), span, placeholders)?);
+                group.set_span(span);
+                out.extend([TokenTree::Group(group)]);
+            }
+            mut tt => {
+                tt.set_span(span);
+                out.extend([tt]);
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn locate(tt: TokenTree, span: Span) -> TokenTree {
+    match tt {
+        TokenTree::Group(g) => {
+            let stream = g.stream().into_iter().map(|tt| locate(tt, span)).collect();
+            let mut group = Group::new(g.delimiter(), stream);
+            group.set_span(g.span().located_at(span));
+            TokenTree::Group(group)
+        }
+        mut tt => {
+            tt.set_span(tt.span().located_at(span));
+            tt
+        }
+    }
+}
@@ -1,2 +1,8 @@
 pub mod collect_meta;
+pub mod generics;
+pub mod hygiene;
+pub mod respan;
+pub mod source;
+pub mod suggest;
 pub mod synerr;
+pub mod warning;
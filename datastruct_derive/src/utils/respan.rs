@@ -0,0 +1,18 @@
+use proc_macro2::{Group, Span, TokenStream as TokenStream2, TokenTree};
+
+/// Moves every token of the stream to the span, so that errors inside point at the span.
+pub fn respan(ts: TokenStream2, span: Span) -> TokenStream2 {
+    ts.into_iter()
+        .map(|tt| match tt {
+            TokenTree::Group(g) => {
+                let mut group = Group::new(g.delimiter(), respan(g.stream(), span));
+                group.set_span(span);
+                TokenTree::Group(group)
+            }
+            mut tt => {
+                tt.set_span(span);
+                tt
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,58 @@
+use syn::{parse_quote, Attribute, Expr, ExprLit, File, Item, Lit, Meta, MetaNameValue};
+
+/// Renders a type as it's usually written, e.g. `Vec < u8 >` becomes `Vec<u8>`.
+#[cfg(feature = "reflect")]
+pub fn type_text(ty: &syn::Type) -> String {
+    unparse_item(parse_quote! { type __Ds = #ty; }, "type __Ds = ")
+}
+
+/// Renders an expression as it's usually written, on a single line.
+pub fn expr_text(expr: &Expr) -> String {
+    unparse_item(parse_quote! { const _: () = #expr; }, "const _: () = ")
+}
+
+// `prettyplease` only formats files, so the tokens are wrapped in an item and cut out of its text.
+fn unparse_item(item: Item, prefix: &str) -> String {
+    let file = prettyplease::unparse(&File {
+        shebang: None,
+        attrs: Vec::new(),
+        items: vec![item],
+    });
+    let text = file
+        .trim_end()
+        .strip_prefix(prefix)
+        .and_then(|text| text.strip_suffix(';'))
+        .unwrap_or(&file);
+
+    // long items are broken into lines, with trailing commas
+    let mut line = String::with_capacity(text.len());
+    for part in text.lines().map(str::trim) {
+        if part.starts_with([')', ']', '}']) && line.ends_with(',') {
+            line.pop();
+        }
+        let glued = line.is_empty() || line.ends_with(['(', '[']) || part.starts_with([')', ']']);
+        if !glued {
+            line.push(' ');
+        }
+        line.push_str(part);
+    }
+    line
+}
+
+/// The `///` documentation of an item, trimmed line by line, or an empty string.
+pub fn doc_text(attrs: &[Attribute]) -> String {
+    attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(MetaNameValue {
+                path,
+                value: Expr::Lit(ExprLit {
+                    lit: Lit::Str(lit), ..
+                }),
+                ..
+            }) if path.is_ident("doc") => Some(lit.value().trim().to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
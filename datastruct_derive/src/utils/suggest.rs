@@ -0,0 +1,39 @@
+use proc_macro2::Span;
+
+/// Edit distance between two strings.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut curr = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        prev = curr;
+    }
+    prev[b.len()]
+}
+
+/// The candidate closest to the name, if it's close enough to be a typo.
+pub fn closest<'a>(name: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let threshold = (name.chars().count() / 3).max(1);
+    candidates
+        .iter()
+        .map(|c| (levenshtein(name, c), *c))
+        .filter(|(d, _)| *d <= threshold)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c)
+}
+
+/// Error of an unknown option, suggesting the closest known option.
+pub fn unknown_option(attr: &str, name: &str, span: Span, candidates: &[&str]) -> syn::Error {
+    let msg = match closest(name, candidates) {
+        Some(suggestion) => format!(
+            "unknown `{}` option `{}`, did you mean `{}`?",
+            attr, name, suggestion
+        ),
+        None => format!("unknown `{}` option `{}`", attr, name),
+    };
+    syn::Error::new(span, msg)
+}
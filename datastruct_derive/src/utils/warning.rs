@@ -0,0 +1,15 @@
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+
+/// Emits a warning at the span.
+///
+/// Stable proc-macros cannot emit warnings, so a deprecated function is called at the span instead.
+pub fn warning(span: Span, note: &str) -> TokenStream2 {
+    quote_spanned! {
+        span => const _: () = {
+            #[deprecated(note = #note)]
+            const fn __ds_warning() {}
+            __ds_warning();
+        };
+    }
+}
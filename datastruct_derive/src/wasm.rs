@@ -0,0 +1,52 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+
+use crate::config::field_config::{GetterType, SetterType};
+use crate::generate::RichStructContent;
+
+/// Generate JavaScript accessors of the fields with getters or setters.
+// The Rust getters return references and the setters may consume the structure,
+// which `wasm_bindgen` cannot export, so the properties are backed by their own methods.
+pub fn impl_wasm(syntax: &RichStructContent) -> syn::Result<TokenStream2> {
+    let ident = &syntax.ident;
+    if !syntax.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            syntax.option_span("wasm"),
+            "`wasm` doesn't support generic structures",
+        ));
+    }
+
+    let mut methods = Vec::new();
+    for field in &syntax.fields {
+        let field_ident = &field.ident;
+        let ty = &field.field_type;
+        let property = field_ident.unraw();
+        if field.config.auto_get != GetterType::No {
+            let method = format_ident!("js_{}", property);
+            methods.push(quote! {
+                #[::wasm_bindgen::prelude::wasm_bindgen(getter = #property)]
+                pub fn #method(&self) -> #ty {
+                    ::core::clone::Clone::clone(&self.#field_ident)
+                }
+            });
+        }
+        if field.config.auto_set != SetterType::No {
+            let method = format_ident!("js_set_{}", property);
+            methods.push(quote! {
+                #[::wasm_bindgen::prelude::wasm_bindgen(setter = #property)]
+                pub fn #method(&mut self, value: #ty) {
+                    self.#field_ident = value;
+                }
+            });
+        }
+    }
+
+    // the block has an attribute, so it's kept apart from the other inherent methods
+    Ok(quote! {
+        #[::wasm_bindgen::prelude::wasm_bindgen]
+        impl #ident {
+            #(#methods)*
+        }
+    })
+}
@@ -1,5 +1,47 @@
 #[cfg(test)]
+mod test_alias;
+#[cfg(test)]
+mod test_arbitrary;
+#[cfg(test)]
+mod test_attrs;
+#[cfg(test)]
+mod test_builder;
+#[cfg(test)]
+mod test_clone;
+#[cfg(test)]
+mod test_default_params;
+#[cfg(test)]
+mod test_dirty;
+#[cfg(test)]
+mod test_env;
+#[cfg(test)]
+mod test_ffi;
+#[cfg(test)]
+mod test_gen_tests;
+#[cfg(test)]
+mod test_invariant;
+#[cfg(test)]
+mod test_lerp;
+#[cfg(test)]
+mod test_merge;
+#[cfg(test)]
 mod test_ops;
+#[cfg(test)]
+mod test_patch;
+#[cfg(test)]
+mod test_precedence;
+#[cfg(test)]
+mod test_random;
+#[cfg(test)]
+mod test_snapshot;
+#[cfg(test)]
+mod test_string_map;
+#[cfg(test)]
+mod test_testing;
+#[cfg(test)]
+mod test_tracing;
+#[cfg(test)]
+mod test_wasm;
 
 use datastruct::DataStruct;
 
@@ -44,11 +86,21 @@ struct SelfReference {
     val2: u8,
 }
 
+#[derive(Debug, Clone, PartialEq, DataStruct)]
+#[dstruct(default, const)]
+#[allow(dead_code)]
+struct Unquoted {
+    #[dfield(default = 3 * 4)]
+    val1: u8,
+    #[dfield(default = val1 as u32 + 1, seq = 1)]
+    val2: u32,
+}
+
 #[derive(Clone, Copy, DataStruct)]
 #[dstruct(debug)]
 struct Debuggable {
     val1: u8,
-    #[dfield(no_debug)]
+    #[dfield(debug = false)]
     val2: u8,
 }
 
@@ -136,6 +188,9 @@ fn test_attr() {
     );
     assert_eq!(SelfReference { val1: 11, val2: 10 }, SelfReference::DEFAULT);
 
+    assert_eq!(Unquoted { val1: 12, val2: 13 }, Unquoted::data_default());
+    assert_eq!(Unquoted { val1: 12, val2: 13 }, Unquoted::DEFAULT);
+
     assert_eq!(
         "Debuggable { val1: 10 }",
         format!("{:?}", Debuggable { val1: 10, val2: 10 })
@@ -171,7 +226,7 @@ fn test_adv() {
     struct Person {
         age: u8,
         name: String,
-        #[dfield(no_debug)]
+        #[dfield(debug = false)]
         private_key: u32,
     }
     let person = Person {
@@ -181,3 +236,1427 @@ fn test_adv() {
     };
     println!("{:#?}", person);
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DataStruct)]
+#[dstruct(zip_with)]
+#[allow(dead_code)]
+struct Bounds {
+    min: i32,
+    max: i32,
+}
+
+#[test]
+fn test_zip_with() {
+    let lhs = Bounds { min: 1, max: 10 };
+    let rhs = Bounds { min: -3, max: 20 };
+    assert_eq!(Bounds { min: -3, max: 10 }, lhs.zip_with(rhs, i32::min));
+    assert_eq!(Bounds { min: -2, max: 30 }, lhs.zip_with(rhs, |a, b| a + b));
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, DataStruct)]
+#[dstruct(cow)]
+#[allow(dead_code)]
+struct Message<'a> {
+    id: u32,
+    text: std::borrow::Cow<'a, str>,
+    payload: std::borrow::Cow<'a, [u8]>,
+}
+
+#[test]
+fn test_cow() {
+    use std::borrow::Cow;
+
+    let text = String::from("hello");
+    let borrowed = Message {
+        id: 1,
+        text: Cow::Borrowed(&text),
+        payload: Cow::Borrowed(&[1, 2, 3]),
+    };
+    let owned: Message<'static> = borrowed.clone().into_owned();
+    assert!(matches!(owned.text, Cow::Owned(_)));
+    assert!(matches!(owned.payload, Cow::Owned(_)));
+    assert_eq!(borrowed, owned);
+
+    let reborrowed = owned.to_borrowed();
+    assert!(matches!(reborrowed.text, Cow::Borrowed("hello")));
+    assert_eq!(owned, reborrowed);
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, DataStruct)]
+#[allow(dead_code)]
+struct Inventory {
+    #[dfield(delegate(methods(
+        "fn len(&self) -> usize",
+        "fn is_empty(&self) -> bool",
+        "fn push(&mut self, value: String)",
+        "fn contains(&self, x: &String) -> bool"
+    )))]
+    items: Vec<String>,
+    owner: String,
+}
+
+#[test]
+fn test_delegate() {
+    let mut inventory = Inventory {
+        items: vec![],
+        owner: "me".to_string(),
+    };
+    assert!(inventory.is_empty());
+    inventory.push("apple".to_string());
+    assert_eq!(1, inventory.len());
+    assert!(inventory.contains(&"apple".to_string()));
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, DataStruct)]
+#[allow(dead_code)]
+struct Tracked {
+    #[dfield(deref)]
+    values: Vec<u8>,
+    revision: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, DataStruct)]
+#[allow(dead_code)]
+struct ReadOnly {
+    #[dfield(deref = "ref")]
+    name: String,
+}
+
+#[test]
+fn test_deref() {
+    let mut tracked = Tracked {
+        values: vec![1],
+        revision: 0,
+    };
+    tracked.push(2);
+    assert_eq!(2, tracked.len());
+    assert_eq!(&[1, 2], &tracked[..]);
+
+    let read_only = ReadOnly {
+        name: "name".to_string(),
+    };
+    assert!(read_only.starts_with("na"));
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, DataStruct)]
+#[allow(dead_code)]
+struct Document {
+    #[dfield(as_ref)]
+    title: String,
+    #[dfield(as_ref = "mut")]
+    body: Vec<u8>,
+}
+
+#[test]
+fn test_as_ref() {
+    fn title_len(s: impl AsRef<String>) -> usize {
+        s.as_ref().len()
+    }
+
+    let mut doc = Document {
+        title: "title".to_string(),
+        body: vec![],
+    };
+    assert_eq!(5, title_len(doc.clone()));
+    AsMut::<Vec<u8>>::as_mut(&mut doc).push(1);
+    assert_eq!(&vec![1], AsRef::<Vec<u8>>::as_ref(&doc));
+}
+
+#[derive(Debug, Clone, DataStruct)]
+#[dstruct(cmp(peq, eq))]
+#[allow(dead_code)]
+struct User {
+    #[dfield(borrow)]
+    name: String,
+    #[dfield(cmp(eq = false))]
+    age: u32,
+}
+
+impl std::hash::Hash for User {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.name.hash(state)
+    }
+}
+
+#[test]
+fn test_borrow() {
+    use std::collections::HashSet;
+
+    let mut users = HashSet::new();
+    users.insert(User {
+        name: "alice".to_string(),
+        age: 30,
+    });
+    let (alice, bob) = (String::from("alice"), String::from("bob"));
+    assert_eq!(Some(30), users.get(&alice).map(|u| u.age));
+    assert!(!users.contains(&bob));
+}
+
+#[derive(Debug, Clone, PartialEq, DataStruct)]
+#[dstruct(tuple)]
+struct Row {
+    id: u64,
+    name: String,
+    #[dfield(tuple = false, default = "true")]
+    dirty: bool,
+}
+
+#[test]
+fn test_tuple() {
+    let row: Row = (1, "one".to_string()).into();
+    assert_eq!(
+        Row {
+            id: 1,
+            name: "one".to_string(),
+            dirty: true
+        },
+        row
+    );
+    let (id, name): (u64, String) = row.into();
+    assert_eq!((1, "one"), (id, name.as_str()));
+}
+
+#[derive(DataStruct)]
+#[dstruct(into_parts)]
+#[allow(dead_code)]
+struct Credentials {
+    user: String,
+    token: Vec<u8>,
+}
+
+#[derive(DataStruct)]
+#[dstruct(into_parts(named))]
+#[allow(dead_code)]
+struct Pair<T> {
+    left: T,
+    right: T,
+}
+
+#[test]
+fn test_into_parts() {
+    let credentials = Credentials {
+        user: "root".to_string(),
+        token: vec![1, 2],
+    };
+    let (user, token) = credentials.into_parts();
+    assert_eq!(("root", vec![1, 2]), (user.as_str(), token));
+
+    let PairParts { left, right } = Pair { left: 1, right: 2 }.into_parts();
+    assert_eq!((1, 2), (left, right));
+}
+
+#[allow(dead_code)]
+struct ProfileDto {
+    name: String,
+    age: u32,
+    tags: String,
+}
+
+#[derive(Debug, PartialEq, DataStruct)]
+#[dstruct(convert(from = "ProfileDto"))]
+#[allow(dead_code)]
+struct Profile {
+    name: String,
+    age: u64,
+    #[dfield(convert = "$from.tags.split(',').map(String::from).collect()")]
+    tags: Vec<String>,
+    #[dfield(convert = false, default = "false")]
+    verified: bool,
+}
+
+#[test]
+fn test_convert_from() {
+    let profile: Profile = ProfileDto {
+        name: "alice".to_string(),
+        age: 30,
+        tags: "a,b".to_string(),
+    }
+    .into();
+    assert_eq!(
+        Profile {
+            name: "alice".to_string(),
+            age: 30,
+            tags: vec!["a".to_string(), "b".to_string()],
+            verified: false,
+        },
+        profile
+    );
+}
+
+#[allow(dead_code)]
+struct RawEndpoint {
+    host: String,
+    port: String,
+}
+
+#[derive(Debug, PartialEq)]
+#[allow(dead_code)]
+struct EndpointError(String);
+
+impl From<String> for EndpointError {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<std::num::ParseIntError> for EndpointError {
+    fn from(value: std::num::ParseIntError) -> Self {
+        Self(value.to_string())
+    }
+}
+
+#[derive(Debug, PartialEq, DataStruct)]
+#[dstruct(convert(try_from = "RawEndpoint", error = "EndpointError"))]
+#[allow(dead_code)]
+struct Endpoint {
+    #[dfield(validate = "!$value.is_empty()")]
+    host: String,
+    #[dfield(try_convert = "$from.port.parse::<u16>()", validate = "$value != 0")]
+    port: u16,
+}
+
+#[derive(Debug, DataStruct)]
+#[dstruct(convert(try_from = "RawEndpoint"))]
+#[allow(dead_code)]
+struct LooseEndpoint {
+    host: String,
+    #[dfield(try_convert = "$from.port.parse::<u16>()")]
+    port: u16,
+}
+
+#[test]
+fn test_try_from() {
+    let raw = |host: &str, port: &str| RawEndpoint {
+        host: host.to_string(),
+        port: port.to_string(),
+    };
+
+    assert_eq!(
+        Ok(Endpoint {
+            host: "localhost".to_string(),
+            port: 80
+        }),
+        Endpoint::try_from(raw("localhost", "80"))
+    );
+    assert_eq!(
+        Err(EndpointError("validation of field `port` failed".to_string())),
+        Endpoint::try_from(raw("localhost", "0"))
+    );
+    assert_eq!(
+        Err(EndpointError("validation of field `host` failed".to_string())),
+        Endpoint::try_from(raw("", "80"))
+    );
+    assert!(Endpoint::try_from(raw("localhost", "port")).is_err());
+    assert!(LooseEndpoint::try_from(raw("localhost", "port")).is_err());
+    assert_eq!(8080, LooseEndpoint::try_from(raw("localhost", "8080")).unwrap().port);
+}
+
+struct RawTagged {
+    r#type: String,
+    value: i32,
+}
+
+#[derive(Debug, PartialEq, DataStruct)]
+#[dstruct(convert(try_from = "RawTagged", error = "EndpointError"))]
+#[allow(dead_code)]
+struct Tagged {
+    #[dfield(validate = "!$value.is_empty()")]
+    r#type: String,
+    #[dfield(validate = "$value >= 0")]
+    value: i32,
+}
+
+#[test]
+fn test_try_from_raw_ident() {
+    let raw = |ty: &str, value: i32| RawTagged {
+        r#type: ty.to_string(),
+        value,
+    };
+
+    assert_eq!(
+        Ok(Tagged {
+            r#type: "gauge".to_string(),
+            value: 3
+        }),
+        Tagged::try_from(raw("gauge", 3))
+    );
+    assert_eq!(
+        Err(EndpointError("validation of field `type` failed".to_string())),
+        Tagged::try_from(raw("", 3))
+    );
+    assert_eq!(
+        Err(EndpointError("validation of field `value` failed".to_string())),
+        Tagged::try_from(raw("gauge", -1))
+    );
+}
+
+#[derive(DataStruct)]
+#[dstruct(reflect)]
+#[allow(dead_code)]
+struct ServerConfig {
+    /// Address to listen on.
+    #[dfield(default = "String::from(\"0.0.0.0\")")]
+    address: String,
+    /// Connection limits,
+    /// per client.
+    limits: Vec<(u32, Option<u64>)>,
+    on_close: Option<Box<dyn Fn(&'static str) -> bool>>,
+}
+
+#[test]
+fn test_reflect() {
+    use datastruct::FieldInfo;
+
+    assert_eq!(&["address", "limits", "on_close"], ServerConfig::FIELD_NAMES);
+    assert_eq!(
+        &[
+            FieldInfo {
+                name: "address",
+                type_name: "String",
+                has_default: true,
+                doc: "Address to listen on.",
+            },
+            FieldInfo {
+                name: "limits",
+                type_name: "Vec<(u32, Option<u64>)>",
+                has_default: false,
+                doc: "Connection limits,\nper client.",
+            },
+            FieldInfo {
+                name: "on_close",
+                type_name: "Option<Box<dyn Fn(&'static str) -> bool>>",
+                has_default: false,
+                doc: "",
+            }
+        ],
+        ServerConfig::FIELDS
+    );
+}
+
+#[derive(DataStruct)]
+#[dstruct(reflect(debug))]
+#[allow(dead_code)]
+struct Lexeme {
+    r#type: u8,
+    r#match: &'static str,
+}
+
+#[test]
+fn test_reflect_raw_ident() {
+    use datastruct::DataStructFields;
+
+    assert_eq!(&["type", "match"], Lexeme::FIELD_NAMES);
+    assert_eq!(vec!["type", "match"], Lexeme::FIELDS.iter().map(|f| f.name).collect::<Vec<_>>());
+    let lexeme = Lexeme { r#type: 1, r#match: "a" };
+    assert_eq!("1", format!("{:?}", lexeme.field_debug("type").unwrap()));
+    assert!(lexeme.field_debug("r#type").is_none());
+}
+
+#[derive(DataStruct)]
+#[dstruct(reflect(any))]
+#[allow(dead_code)]
+struct Variables<T> {
+    count: u32,
+    value: T,
+}
+
+#[test]
+fn test_field_by_name() {
+    let mut vars = Variables {
+        count: 1,
+        value: "value".to_string(),
+    };
+    assert_eq!(Some(&1), vars.field("count").and_then(|v| v.downcast_ref::<u32>()));
+    assert!(vars.field("value").unwrap().downcast_ref::<u32>().is_none());
+    assert!(vars.field("missing").is_none());
+
+    *vars.field_mut("value").unwrap().downcast_mut::<String>().unwrap() = "changed".to_string();
+    assert_eq!("changed", vars.value);
+}
+
+#[derive(DataStruct)]
+#[dstruct(reflect(any, debug), where(debug = "T: std::fmt::Debug"))]
+#[allow(dead_code)]
+struct TableRow<T> {
+    id: u32,
+    value: T,
+    #[dfield(debug = false)]
+    secret: String,
+}
+
+#[allow(dead_code)]
+fn cells<T: datastruct::DataStructFields>(row: &T) -> Vec<String> {
+    T::FIELD_NAMES
+        .iter()
+        .filter_map(|name| row.field_debug(name))
+        .map(|value| format!("{:?}", value))
+        .collect()
+}
+
+#[test]
+fn test_data_struct_fields() {
+    use datastruct::DataStructFields;
+
+    let row = TableRow {
+        id: 1,
+        value: Some("value"),
+        secret: "secret".to_string(),
+    };
+    assert_eq!(vec!["1", "Some(\"value\")"], cells(&row));
+    assert!(row.field_debug("secret").is_none());
+    assert!(row.field_debug("missing").is_none());
+    assert!(row.field("secret").is_some());
+}
+
+#[test]
+fn test_set_field_by_name() {
+    use datastruct::SetFieldError;
+
+    let mut vars = Variables {
+        count: 1,
+        value: vec![1u8],
+    };
+    assert_eq!(Ok(()), vars.set_field("count", Box::new(2u32)));
+    assert_eq!(2, vars.count);
+    assert_eq!(
+        Err(SetFieldError::TypeMismatch {
+            field: "value",
+            expected: "T",
+        }),
+        vars.set_field("value", Box::new(2u32))
+    );
+    assert_eq!(
+        Err(SetFieldError::UnknownField),
+        vars.set_field("missing", Box::new(2u32))
+    );
+}
+
+#[derive(DataStruct)]
+#[dstruct(debug, set)]
+#[dstruct(group(secrets, fields(password, token), debug = false, set = "no"))]
+#[allow(dead_code)]
+struct Account {
+    username: String,
+    password: String,
+    // the field's own configuration takes precedence over the group's
+    #[dfield(set)]
+    token: String,
+}
+
+#[test]
+fn test_group() {
+    let mut account = Account {
+        username: "alice".to_string(),
+        password: "hunter2".to_string(),
+        token: "abc".to_string(),
+    };
+    account.set_username("bob".to_string());
+    account.set_token("def".to_string());
+    assert_eq!(r#"Account { username: "bob" }"#, format!("{:?}", account));
+    assert_eq!("def", account.token);
+}
+
+#[derive(DataStruct)]
+#[dstruct(preset = "value_object")]
+#[allow(dead_code)]
+struct Money {
+    amount: i64,
+    currency: &'static str,
+    #[dfield(cmp(eq = false))]
+    note: String,
+}
+
+#[derive(DataStruct)]
+#[dstruct(preset = "dto", debug = false)]
+#[allow(dead_code)]
+struct UserDto {
+    #[dfield(default = "String::from(\"guest\")")]
+    name: String,
+    #[dfield(default = "18")]
+    age: u8,
+}
+
+#[test]
+fn test_preset() {
+    use datastruct::DataStruct;
+    use std::collections::HashSet;
+
+    let money = |amount, note: &str| Money {
+        amount,
+        currency: "EUR",
+        note: note.to_string(),
+    };
+    let set: HashSet<Money> = [money(5, "a"), money(5, "b"), money(7, "a")].into_iter().collect();
+    assert_eq!(2, set.len());
+    assert_eq!(&"EUR", money(5, "a").currency());
+    assert_eq!(
+        r#"Money { amount: 5, currency: "EUR", note: "a" }"#,
+        format!("{:?}", money(5, "a"))
+    );
+
+    let mut dto = UserDto::data_default();
+    dto.set_age(20);
+    assert_eq!(("guest", 20), (dto.name().as_str(), *dto.age()));
+}
+
+#[derive(DataStruct)]
+#[dstruct(debug_fields)]
+#[allow(dead_code)]
+struct Session {
+    id: u32,
+    #[dfield(debug = false)]
+    secret: String,
+    tags: Vec<&'static str>,
+    r#type: u8,
+}
+
+#[test]
+fn test_debug_fields() {
+    let session = Session {
+        id: 1,
+        secret: "secret".to_string(),
+        tags: vec!["a"],
+        r#type: 2,
+    };
+    let fields = session
+        .debug_fields()
+        .map(|(name, value)| format!("{}={:?}", name, value))
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["id=1", "tags=[\"a\"]", "type=2"], fields);
+}
+
+#[derive(DataStruct)]
+#[dstruct(visit)]
+#[allow(dead_code)]
+struct Metrics<T> {
+    requests: u64,
+    errors: u64,
+    label: T,
+}
+
+#[test]
+fn test_visit() {
+    struct Sum(u64);
+
+    impl<T> MetricsVisitor<T> for Sum {
+        fn visit_requests(&mut self, value: &u64) {
+            self.0 += value;
+        }
+
+        fn visit_errors(&mut self, value: &u64) {
+            self.0 += value;
+        }
+    }
+
+    let metrics = Metrics {
+        requests: 10,
+        errors: 2,
+        label: "api",
+    };
+    let mut sum = Sum(0);
+    metrics.accept(&mut sum);
+    assert_eq!(12, sum.0);
+}
+
+#[derive(DataStruct)]
+#[dstruct(cache_key)]
+#[dstruct(computed(name = "len", ty = "usize", expr = "self.text.len()", cmp(eq)))]
+#[allow(dead_code)]
+struct Query {
+    text: String,
+    limit: usize,
+    #[dfield(cmp(eq = false))]
+    elapsed: f64,
+}
+
+#[test]
+fn test_cache_key() {
+    use std::hash::{Hash, Hasher};
+
+    let query = |text: &str, limit, elapsed| Query {
+        text: text.to_string(),
+        limit,
+        elapsed,
+    };
+    assert_eq!(query("rust", 10, 0.5).cache_key(), query("rust", 10, 1.5).cache_key());
+    assert_ne!(query("rust", 10, 0.5).cache_key(), query("rust", 20, 0.5).cache_key());
+
+    let mut hasher = datastruct::StableHasher::new();
+    "rust".hash(&mut hasher);
+    10usize.hash(&mut hasher);
+    4usize.hash(&mut hasher);
+    assert_eq!(hasher.finish(), query("rust", 10, 0.5).cache_key());
+}
+
+#[derive(DataStruct)]
+#[dstruct(debug, cmp(peq, pord))]
+#[dstruct(computed(name = "area", ty = "f64", expr = "self.w * self.h", debug, cmp(pord)))]
+#[dstruct(computed(name = "is_square", ty = "bool", expr = "self.w == self.h"))]
+#[allow(dead_code)]
+struct Rect {
+    #[dfield(cmp(eq = false))]
+    w: f64,
+    #[dfield(cmp(eq = false))]
+    h: f64,
+}
+
+#[test]
+fn test_computed() {
+    let rect = Rect { w: 2.0, h: 3.0 };
+    assert_eq!(6.0, rect.area());
+    assert!(!rect.is_square());
+    assert_eq!("Rect { w: 2.0, h: 3.0, area: 6.0 }", format!("{:?}", rect));
+
+    assert!(rect == Rect { w: 3.0, h: 2.0 });
+    assert!(rect != Rect { w: 1.0, h: 2.0 });
+    assert!(rect > Rect { w: 1.0, h: 5.0 });
+}
+
+#[derive(DataStruct)]
+#[dstruct(consts(MAX_SIZE = "1024usize", VERSION = "3", NAME = "\"packet\""))]
+#[dstruct(consts(HEADER(ty = "[u8; 2]", value = "[0xCA, 0xFE]")))]
+#[allow(dead_code)]
+struct Packet {
+    payload: Vec<u8>,
+}
+
+#[test]
+fn test_consts() {
+    let max_size: usize = Packet::MAX_SIZE;
+    let version: i32 = Packet::VERSION;
+    assert_eq!((1024, 3, "packet"), (max_size, version, Packet::NAME));
+    assert_eq!([0xCA, 0xFE], Packet::HEADER);
+}
+
+#[derive(DataStruct)]
+#[dstruct(serde_defaults, serde_is_default)]
+#[allow(dead_code)]
+struct Listener {
+    #[dfield(default = "String::from(\"localhost\")")]
+    host: String,
+    #[dfield(default = "8080")]
+    port: u16,
+    #[dfield(default = "format!(\"{}:{}\", host, port)")]
+    address: String,
+    backlog: u32,
+}
+
+#[test]
+fn test_serde_defaults() {
+    assert_eq!("localhost", Listener::__default_host());
+    assert_eq!(8080, Listener::__default_port());
+    assert_eq!("localhost:8080", Listener::__default_address());
+}
+
+#[test]
+fn test_serde_is_default() {
+    assert!(Listener::port_is_default(&8080));
+    assert!(!Listener::port_is_default(&80));
+    assert!(Listener::address_is_default(&"localhost:8080".to_string()));
+}
+
+#[derive(DataStruct, Debug, PartialEq)]
+#[dstruct(default, zeroed)]
+#[allow(dead_code)]
+struct Histogram {
+    #[dfield(default = "10")]
+    buckets: u32,
+    #[dfield(default = "1.5")]
+    scale: f64,
+    #[dfield(default = "true")]
+    enabled: bool,
+    #[dfield(default = "Some(String::from(\"latency\"))")]
+    label: Option<String>,
+    #[dfield(default = "[1; 4]")]
+    counts: [u64; 4],
+    #[dfield(default = "(-1, 'x')")]
+    range: (i8, char),
+}
+
+#[allow(dead_code)]
+const EMPTY_HISTOGRAM: Histogram = Histogram::zeroed();
+
+#[test]
+fn test_zeroed() {
+    use datastruct::DataStruct;
+
+    assert_eq!(
+        Histogram {
+            buckets: 0,
+            scale: 0.0,
+            enabled: false,
+            label: None,
+            counts: [0; 4],
+            range: (0, '\0'),
+        },
+        EMPTY_HISTOGRAM
+    );
+    assert_ne!(Histogram::data_default(), Histogram::zeroed());
+}
+
+#[derive(DataStruct, Debug, PartialEq)]
+#[dstruct(construct = "default", default, const, zeroed)]
+#[allow(dead_code)]
+struct Gauge {
+    #[dfield(default = "100")]
+    level: u16,
+    #[dfield(default = "true")]
+    active: bool,
+}
+
+#[test]
+fn test_zeroed_construct_default() {
+    const EMPTY: Gauge = Gauge::zeroed();
+    assert_eq!(Gauge { level: 0, active: false }, EMPTY);
+}
+
+// `#[cfg]`s are evaluated before the derive macro runs,
+// so disabled fields must not be referred to by any generated code.
+#[derive(DataStruct)]
+#[dstruct(default, const, debug, cmp(peq, eq, hash), ops(add), set, builder, patch, reflect)]
+#[allow(dead_code)]
+struct Features {
+    #[dfield(default = "1")]
+    base: i32,
+    #[cfg(not(test))]
+    #[dfield(default = "2")]
+    disabled: i32,
+    #[cfg(test)]
+    #[dfield(default = "3")]
+    enabled: i32,
+}
+
+#[test]
+fn test_cfg_fields() {
+    use datastruct::{ConstDataStruct, DataStruct};
+
+    let features = Features::data_default() + Features::DEFAULT;
+    assert_eq!((2, 6), (features.base, features.enabled));
+    assert_eq!("Features { base: 2, enabled: 6 }", format!("{:?}", features));
+    assert_eq!(&["base", "enabled"], Features::FIELD_NAMES);
+}
+
+#[derive(DataStruct)]
+#[cfg_attr(test, dstruct(debug))]
+#[cfg_attr(not(test), dstruct(cmp(peq)))]
+#[allow(dead_code)]
+struct Extras {
+    #[cfg_attr(test, dfield(set, debug = false))]
+    #[cfg_attr(not(test), dfield(get))]
+    value: i32,
+    other: i32,
+}
+
+#[test]
+fn test_cfg_attr() {
+    let mut extras = Extras { value: 1, other: 2 };
+    extras.set_value(3);
+    assert_eq!(3, extras.value);
+    assert_eq!("Extras { other: 2 }", format!("{:?}", extras));
+}
+
+mod facade {
+    pub use datastruct as ds;
+}
+
+#[derive(facade::ds::DataStruct)]
+#[dstruct(crate = "crate::facade::ds", default, const, lerp, reflect)]
+#[allow(dead_code)]
+struct Renamed {
+    #[dfield(default = "1.0")]
+    value: f64,
+}
+
+#[test]
+fn test_crate_path() {
+    use facade::ds::{ConstDataStruct, DataStruct, Lerp};
+
+    let renamed = Renamed::data_default().lerp(Renamed { value: 3.0 }, 0.5);
+    assert_eq!(2.0, renamed.value);
+    assert_eq!(1.0, Renamed::DEFAULT.value);
+    assert_eq!("value", Renamed::FIELDS[0].name);
+}
+
+#[derive(DataStruct)]
+#[dstruct(doc_hidden, default, set, get, builder, patch, debug)]
+pub struct Internal {
+    #[dfield(default = "1")]
+    value: i32,
+}
+
+#[test]
+fn test_doc_hidden() {
+    use datastruct::DataStruct;
+
+    let mut internal = Internal::builder().value(2).build();
+    internal.set_value(*internal.value() + 1);
+    assert_eq!(3, internal.value);
+    assert_eq!("Internal { value: 1 }", format!("{:?}", Internal::data_default()));
+}
+
+#[derive(DataStruct, Debug, PartialEq)]
+#[dstruct(construct = "default", default, ops(add), lerp, zip_with, builder, tuple)]
+struct Vector2 {
+    #[dfield(default = "0.0")]
+    x: f64,
+    #[dfield(default = "0.0")]
+    y: f64,
+}
+
+#[test]
+fn test_construct_default() {
+    use datastruct::Lerp;
+
+    let a = Vector2::from((1.0, 2.0));
+    let b = Vector2::builder().x(3.0).build();
+    assert_eq!(Vector2 { x: 4.0, y: 2.0 }, a + b);
+    let b = Vector2::builder().x(3.0).build();
+    assert_eq!(Vector2 { x: 2.0, y: 1.0 }, Vector2::from((1.0, 2.0)).lerp(b, 0.5));
+    assert_eq!(
+        Vector2 { x: 3.0, y: 2.0 },
+        Vector2::from((1.0, 2.0)).zip_with(Vector2::from((3.0, 0.0)), f64::max)
+    );
+}
+
+#[derive(DataStruct)]
+#[dstruct(default, debug, cmp(peq))]
+#[dstruct(where(default = "T: Default", debug = "T: std::fmt::Debug", cmp = "T: PartialEq"))]
+#[allow(dead_code)]
+struct Labeled<T> {
+    #[dfield(default = "T::default()")]
+    value: T,
+    #[dfield(default = "\"label\"")]
+    label: &'static str,
+}
+
+#[test]
+fn test_where_bounds() {
+    use datastruct::DataStruct;
+
+    struct Opaque;
+
+    let labeled: Labeled<u8> = Labeled::data_default();
+    assert_eq!(r#"Labeled { value: 0, label: "label" }"#, format!("{:?}", labeled));
+    assert!(labeled == Labeled { value: 0, label: "label" });
+    // the type is usable without the bounds of the unused implementations
+    let opaque = Labeled { value: Opaque, label: "opaque" };
+    assert_eq!("opaque", opaque.label);
+}
+
+#[derive(DataStruct, Debug, PartialEq)]
+#[dstruct(default, std_default)]
+#[allow(dead_code)]
+struct Window {
+    #[dfield(default = "800")]
+    width: u32,
+    #[dfield(default = "600")]
+    height: u32,
+    #[dfield(default = "String::from(\"untitled\")")]
+    title: String,
+}
+
+#[test]
+fn test_std_default_delegate() {
+    let window = Window::default();
+    assert_eq!(window, Window::data_default());
+    assert_eq!(window.title, "untitled");
+}
+
+#[derive(Debug, DataStruct)]
+#[dstruct(cmp(peq, ord, eq, pord))]
+#[allow(dead_code)]
+struct Version {
+    // repeated `cmp(...)` are merged, the field is ordered but not compared by `eq`
+    #[dfield(cmp(ord = 1, pord = 1))]
+    #[dfield(cmp(eq = false))]
+    major: u8,
+    #[dfield(cmp(ord = 2, pord = 2))]
+    build: u8,
+}
+
+#[test]
+fn test_cmp_merge() {
+    assert!(Version { major: 1, build: 0 } < Version { major: 2, build: 0 });
+    assert!(Version { major: 1, build: 0 } == Version { major: 2, build: 0 });
+}
+
+#[derive(DataStruct)]
+#[dstruct(get = "full", set)]
+#[allow(dead_code)]
+struct Token {
+    #[dfield(map, do_with)]
+    r#type: u8,
+}
+
+#[test]
+fn test_raw_ident_accessors() {
+    let mut token = Token { r#type: 1 }.with_type(2).map_type(|t| t + 1);
+    token.do_with_type(|t| *t *= 2);
+    token.set_type(*token.r#type() + 1);
+    assert_eq!(token.get_type(), 7);
+}
+
+#[allow(dead_code)]
+static UNQUOTED: Unquoted = datastruct::const_default!(Unquoted);
+#[allow(dead_code)]
+const UNQUOTED_VAL2: u32 = datastruct::const_default::<Unquoted>().val2;
+
+#[test]
+fn test_const_default_helpers() {
+    assert_eq!(12, UNQUOTED.val1);
+    assert_eq!(13, UNQUOTED_VAL2);
+    let values = [0u8; datastruct::const_default!(Unquoted).val1 as usize];
+    assert_eq!(12, values.len());
+}
+
+#[allow(dead_code)]
+fn or_default<T: Default>(value: Option<T>) -> T {
+    value.unwrap_or_default()
+}
+
+#[test]
+fn test_defaulted() {
+    use datastruct::Defaulted;
+
+    let mut defaulted: Defaulted<DevTest> = or_default(None);
+    assert_eq!(10, defaulted.field1);
+    defaulted.field1 = 5;
+    assert_eq!(DevTest { field1: 5, field2: 10 }, defaulted.into_inner());
+
+    let given = or_default(Some(Defaulted::from(DevTest { field1: 1, field2: 2 })));
+    assert_eq!(DevTest { field1: 1, field2: 2 }, *given);
+}
+
+#[derive(DataStruct)]
+#[dstruct(cmp(hash), cache_key)]
+#[allow(dead_code)]
+struct Route {
+    #[dfield(hash = "$self.path.to_lowercase()")]
+    path: String,
+    method: u8,
+}
+
+impl PartialEq for Route {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.path.eq_ignore_ascii_case(&rhs.path) && self.method == rhs.method
+    }
+}
+
+impl Eq for Route {}
+
+#[test]
+fn test_hash_expr() {
+    use std::collections::HashSet;
+
+    let route = |path: &str| Route {
+        path: path.to_string(),
+        method: 1,
+    };
+    let set: HashSet<Route> = [route("/Users"), route("/users"), route("/posts")].into_iter().collect();
+    assert_eq!(2, set.len());
+    assert_eq!(route("/USERS").cache_key(), route("/users").cache_key());
+    assert_ne!(route("/users").cache_key(), route("/posts").cache_key());
+}
+
+// the hashed expressions refer to items named like the locals of the generated code
+#[allow(dead_code)]
+fn state(len: usize) -> usize {
+    len % 4
+}
+
+fn __ds_hasher(len: usize) -> usize {
+    len / 4
+}
+
+#[derive(DataStruct, PartialEq, Eq)]
+#[dstruct(cmp(hash), cache_key)]
+#[allow(dead_code)]
+struct Bucketed {
+    #[dfield(hash = "(state($self.name.len()), __ds_hasher($self.name.len()))")]
+    name: String,
+}
+
+#[test]
+fn test_hash_expr_hygiene() {
+    use std::collections::HashSet;
+
+    let bucketed = |name: &str| Bucketed { name: name.to_string() };
+    let set: HashSet<Bucketed> = [bucketed("ab"), bucketed("cd")].into_iter().collect();
+    assert_eq!(2, set.len());
+    assert_eq!(bucketed("ab").cache_key(), bucketed("cd").cache_key());
+    assert_ne!(bucketed("ab").cache_key(), bucketed("abcde").cache_key());
+}
+
+#[derive(Debug, PartialEq, DataStruct)]
+#[dstruct(cmp(comparator))]
+#[allow(dead_code)]
+struct Task {
+    #[dfield(cmp(ord = 1))]
+    name: &'static str,
+    #[dfield(cmp(ord = 0))]
+    priority: u8,
+}
+
+#[test]
+fn test_comparator() {
+    let task = |name, priority| Task { name, priority };
+    let mut tasks = vec![task("b", 1), task("a", 2), task("a", 1)];
+    tasks.sort_by(Task::cmp_asc);
+    assert_eq!(vec![task("a", 1), task("b", 1), task("a", 2)], tasks);
+    tasks.sort_by(Task::cmp_desc);
+    assert_eq!(vec![task("a", 2), task("b", 1), task("a", 1)], tasks);
+}
+
+#[derive(Debug, PartialEq, DataStruct)]
+#[dstruct(reflect(compare), field_enum)]
+#[allow(dead_code)]
+struct Score {
+    #[dfield(cmp(ord))]
+    name: &'static str,
+    #[dfield(cmp(pord))]
+    score: f64,
+    hidden: u8,
+}
+
+#[test]
+fn test_compare_by() {
+    use datastruct::Order;
+    use std::cmp::Ordering;
+
+    let score = |name, score| Score { name, score, hidden: 0 };
+    let mut scores = vec![score("b", 1.0), score("a", 2.0), score("c", 2.0)];
+    scores.sort_by(|a, b| a.compare_by(b, &[(ScoreField::Score, Order::Descending), (ScoreField::Name, Order::Ascending)]));
+    assert_eq!(vec![score("a", 2.0), score("c", 2.0), score("b", 1.0)], scores);
+    scores.sort_by(|a, b| a.compare_by(b, &[(ScoreField::Name, Order::Descending)]));
+    assert_eq!(vec![score("c", 2.0), score("b", 1.0), score("a", 2.0)], scores);
+
+    assert_eq!(Ordering::Equal, score("a", f64::NAN).compare_by(&score("a", 1.0), &[(ScoreField::Score, Order::Ascending)]));
+    assert_eq!(Ordering::Equal, score("a", 1.0).compare_by(&score("a", 1.0), &[]));
+}
+
+#[test]
+#[should_panic(expected = "`Score::compare_by` can't compare field `hidden`")]
+fn test_compare_by_incomparable() {
+    let score = Score { name: "a", score: 1.0, hidden: 0 };
+    score.compare_by(&score, &[(ScoreField::Hidden, datastruct::Order::Ascending)]);
+}
+
+#[derive(DataStruct)]
+#[dstruct(field_enum)]
+#[allow(dead_code)]
+struct Preferences<T> {
+    volume: u8,
+    player_name: String,
+    r#type: T,
+}
+
+#[test]
+fn test_field_enum() {
+    let mut prefs = Preferences {
+        volume: 5,
+        player_name: "guest".to_string(),
+        r#type: 'a',
+    };
+    prefs.set(PreferencesFieldValue::Volume(7));
+    prefs.set(PreferencesFieldValue::Type('b'));
+    assert_eq!((7, 'b'), (prefs.volume, prefs.r#type));
+
+    let names = PreferencesField::ALL.map(PreferencesField::name);
+    assert_eq!(["volume", "player_name", "type"], names);
+    let value = prefs.get(PreferencesField::PlayerName);
+    assert_eq!(PreferencesField::PlayerName, value.field());
+    match value {
+        PreferencesFieldValue::PlayerName(name) => assert_eq!("guest", name),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_apply_values() {
+    let mut prefs = Preferences {
+        volume: 5,
+        player_name: "guest".to_string(),
+        r#type: 0u8,
+    };
+    let updates = [("volume", "3"), ("type", "2"), ("volume", "9")].map(|(name, value)| match name {
+        "volume" => PreferencesFieldValue::Volume(value.parse().unwrap()),
+        _ => PreferencesFieldValue::Type(value.parse().unwrap()),
+    });
+    prefs.apply_values(updates);
+    assert_eq!((9, "guest", 2), (prefs.volume, prefs.player_name.as_str(), prefs.r#type));
+}
+
+#[derive(DataStruct)]
+#[allow(dead_code)]
+struct SharedState<T> {
+    #[dfield(do_with = "lock")]
+    hits: std::sync::Mutex<u64>,
+    #[dfield(do_with = "lock")]
+    names: std::sync::RwLock<Vec<T>>,
+    // without `lock`, a lock is an ordinary field
+    #[dfield(do_with)]
+    pending: std::sync::Mutex<u64>,
+}
+
+#[test]
+fn test_lock_do_with() {
+    let state = SharedState {
+        hits: std::sync::Mutex::new(0),
+        names: std::sync::RwLock::new(vec![]),
+        pending: std::sync::Mutex::new(0),
+    };
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                state.do_with_hits(|hits| *hits += 1);
+                state.do_with_names(|names| names.push("name"));
+            });
+        }
+    });
+    assert_eq!(4, *state.hits.lock().unwrap());
+    assert_eq!(4, state.read_names(|names| names.len()));
+
+    let mut state = state;
+    state.do_with_pending(|pending| *pending.get_mut().unwrap() += 1);
+    assert_eq!(1, *state.pending.lock().unwrap());
+}
+
+#[derive(DataStruct)]
+#[dstruct(defaults_doc)]
+#[allow(dead_code)]
+struct ServeOptions {
+    /// Port to listen on.
+    #[dfield(default = "8080")]
+    port: u16,
+    /// Addresses to bind,
+    /// tried in order.
+    #[dfield(default = "vec![String::from(\"localhost\")]")]
+    hosts: Vec<String>,
+    #[dfield(default = "std::time::Duration::from_secs(30)")]
+    r#timeout: std::time::Duration,
+    verbose: bool,
+    #[dfield(default = "String::from(\"a , b ( c )\")")]
+    label: String,
+    #[dfield(default = Some( 3 ))]
+    retries: Option<u8>,
+}
+
+#[test]
+fn test_defaults_doc() {
+    assert_eq!(
+        [
+            ("port", "Port to listen on. [default: 8080]"),
+            (
+                "hosts",
+                "Addresses to bind, tried in order. [default: vec![String::from(\"localhost\")]]"
+            ),
+            ("timeout", "[default: std::time::Duration::from_secs(30)]"),
+            ("label", "[default: String::from(\"a , b ( c )\")]"),
+            ("retries", "[default: Some(3)]"),
+        ],
+        ServeOptions::DEFAULTS_DOC
+    );
+    let _ = ServeOptions {
+        port: 0,
+        hosts: Vec::new(),
+        timeout: Default::default(),
+        verbose: false,
+        label: String::new(),
+        retries: None,
+    };
+}
+
+#[derive(DataStruct, Clone)]
+#[dstruct(diff_display)]
+#[allow(dead_code)]
+struct ReloadConfig<T> {
+    host: String,
+    port: u16,
+    #[dfield(debug = false)]
+    password: String,
+    #[dfield(cmp(eq = false))]
+    loaded_at: u64,
+    extra: Option<T>,
+}
+
+#[test]
+fn test_diff_display() {
+    let old = ReloadConfig {
+        host: "localhost".to_string(),
+        port: 8080,
+        password: "old".to_string(),
+        loaded_at: 1,
+        extra: Some(1),
+    };
+    let mut new = old.clone();
+    new.loaded_at = 2;
+    assert!(old.diff_display(&new).is_empty());
+    assert_eq!("", old.diff_display(&new).to_string());
+
+    new.port = 9090;
+    new.password = "new".to_string();
+    new.extra = None;
+    let diff = old.diff_display(&new);
+    assert_eq!(vec!["port", "password", "extra"], diff.fields().collect::<Vec<_>>());
+    assert_eq!("port: 8080 → 9090, password: changed, extra: Some(1) → None", diff.to_string());
+    assert_eq!("port: 8080 → 9090\npassword: changed\nextra: Some(1) → None", format!("{:#}", diff));
+}
+
+#[derive(DataStruct, Debug, PartialEq)]
+#[dstruct(default, fixture)]
+#[allow(dead_code)]
+struct UserAccount {
+    #[dfield(default = "0", fixture = "42")]
+    id: u64,
+    #[dfield(default = "String::new()", fixture = "format!(\"user{}@example.com\", id)")]
+    email: String,
+    #[dfield(default = "false")]
+    admin: bool,
+}
+
+#[test]
+fn test_fixture() {
+    use datastruct::DataStruct;
+
+    assert_eq!(
+        UserAccount {
+            id: 42,
+            email: "user42@example.com".to_string(),
+            admin: false,
+        },
+        UserAccount::fixture()
+    );
+    assert_ne!(UserAccount::data_default(), UserAccount::fixture());
+}
+
+#[derive(DataStruct, Debug, PartialEq)]
+#[dstruct(const)]
+#[allow(dead_code)]
+struct Margin {
+    #[dfield(default = "8")]
+    size: u32,
+}
+
+#[derive(DataStruct, Debug, PartialEq)]
+#[dstruct(const, std_default, fixture, default(bound = "T: datastruct::ConstDataStruct"))]
+#[allow(dead_code)]
+struct Themed<T> {
+    #[dfield(default = "T::DEFAULT")]
+    inner: T,
+    #[dfield(default = "\"light\"", fixture = "\"dark\"")]
+    theme: &'static str,
+}
+
+#[test]
+fn test_default_bound() {
+    use datastruct::{ConstDataStruct, DataStruct};
+
+    const THEMED: Themed<Margin> = Themed::DEFAULT;
+    assert_eq!(8, THEMED.inner.size);
+    assert_eq!(THEMED, Themed::<Margin>::data_default());
+    assert_eq!(THEMED, Themed::<Margin>::default());
+    assert_eq!("dark", Themed::<Margin>::fixture().theme);
+    assert_eq!(
+        Themed {
+            inner: Themed {
+                inner: Margin { size: 8 },
+                theme: "light",
+            },
+            theme: "light",
+        },
+        Themed::<Themed<Margin>>::DEFAULT
+    );
+}
+
+#[derive(DataStruct, Debug, PartialEq)]
+#[dstruct(default, const(check))]
+#[allow(dead_code)]
+struct Clock {
+    #[dfield(default = "60")]
+    tick_rate: u32,
+    #[dfield(default = "1_000_000 / tick_rate as u64", seq = 1)]
+    tick_micros: u64,
+}
+
+#[test]
+fn test_const_check() {
+    use datastruct::{ConstDataStruct, DataStruct};
+
+    assert_eq!(16_666, Clock::DEFAULT.tick_micros);
+    assert_eq!(Clock::DEFAULT, Clock::data_default());
+}
+
+#[derive(DataStruct)]
+#[dstruct(assert(size = 16, align = 8))]
+#[repr(C)]
+struct Sample {
+    timestamp: u64,
+    value: f32,
+    channel: u16,
+}
+
+#[derive(DataStruct)]
+#[dstruct(assert(align = 4))]
+#[allow(dead_code)]
+struct Rgba {
+    channels: [u8; 4],
+    id: u32,
+}
+
+#[test]
+fn test_layout_assert() {
+    assert_eq!(16, std::mem::size_of::<Sample>());
+    assert_eq!(4, std::mem::align_of::<Rgba>());
+}
+
+#[derive(DataStruct, Debug, PartialEq)]
+#[dstruct(map_type("f64", "Option<f64>"))]
+#[allow(dead_code)]
+struct Reading {
+    celsius: f64,
+    peak: Option<f64>,
+    trough: Option<f64>,
+    samples: u32,
+    offset: f64,
+}
+
+#[derive(DataStruct)]
+#[dstruct(map_type("T"))]
+#[allow(dead_code)]
+struct Normalized<T> {
+    weight: T,
+    bias: T,
+    name: &'static str,
+}
+
+#[test]
+fn test_map_type() {
+    let mut reading = Reading {
+        celsius: 20.0,
+        peak: Some(25.0),
+        trough: None,
+        samples: 3,
+        offset: 0.5,
+    };
+    reading.map_fields_of_type::<f64>(|value| *value *= 2.0);
+    let mut count = 0;
+    reading.map_fields_of_type(|value: &mut Option<f64>| {
+        count += 1;
+        *value = value.map(|v| v + 32.0);
+    });
+    assert_eq!(2, count);
+    assert_eq!(
+        Reading {
+            celsius: 40.0,
+            peak: Some(57.0),
+            trough: None,
+            samples: 3,
+            offset: 1.0,
+        },
+        reading
+    );
+
+    let mut normalized = Normalized { weight: 4, bias: 2, name: "layer" };
+    normalized.map_fields_of_type::<i32>(|value| *value /= 2);
+    assert_eq!((2, 1, "layer"), (normalized.weight, normalized.bias, normalized.name));
+}
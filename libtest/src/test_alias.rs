@@ -0,0 +1,22 @@
+// renamed options are reported as deprecation warnings
+#![allow(deprecated)]
+
+use datastruct::DataStruct;
+
+#[derive(DataStruct)]
+#[dstruct(debug)]
+#[allow(dead_code)]
+struct Credentials {
+    user: String,
+    #[dfield(no_debug)]
+    password: String,
+}
+
+#[test]
+fn test_alias() {
+    let credentials = Credentials {
+        user: "alice".to_string(),
+        password: "hunter2".to_string(),
+    };
+    assert_eq!(r#"Credentials { user: "alice" }"#, format!("{:?}", credentials));
+}
@@ -0,0 +1,44 @@
+use datastruct::DataStruct;
+use proptest::prelude::*;
+
+#[derive(Debug, Clone, DataStruct)]
+#[dstruct(arbitrary(proptest, quickcheck))]
+#[allow(dead_code)]
+struct Sample {
+    #[dfield(arbitrary(range = "1..100", gen = "<u8 as quickcheck::Arbitrary>::arbitrary($g) % 99 + 1"))]
+    weight: u8,
+    #[dfield(arbitrary(strategy = "\"[a-z]{1,8}\"", gen = "String::from(\"name\")"))]
+    name: String,
+    enabled: bool,
+    #[dfield(default = "weight as u32 * 2", arbitrary(default))]
+    doubled: u32,
+}
+
+#[derive(Debug, Clone, DataStruct)]
+#[dstruct(arbitrary(proptest))]
+struct Wrapper<T> {
+    inner: T,
+}
+
+proptest! {
+    #[test]
+    fn test_proptest_arbitrary(sample in any::<Sample>(), wrapper in any::<Wrapper<u8>>()) {
+        prop_assert!((1..100).contains(&sample.weight));
+        prop_assert!(!sample.name.is_empty() && sample.name.len() <= 8);
+        prop_assert_eq!(sample.weight as u32 * 2, sample.doubled);
+        let _ = wrapper.inner;
+    }
+}
+
+#[test]
+fn test_quickcheck_arbitrary() {
+    use quickcheck::Gen;
+
+    let mut gen = Gen::new(16);
+    for _ in 0..64 {
+        let sample = <Sample as quickcheck::Arbitrary>::arbitrary(&mut gen);
+        assert!((1..100).contains(&sample.weight));
+        assert_eq!("name", sample.name);
+        assert_eq!(sample.weight as u32 * 2, sample.doubled);
+    }
+}
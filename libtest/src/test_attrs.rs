@@ -0,0 +1,38 @@
+use datastruct::DataStruct;
+
+#[derive(DataStruct)]
+struct Session {
+    #[dfield(get = "full", set = "full", attrs(get = "#[inline] #[must_use]", set = "#[cfg(any())]"))]
+    token: String,
+    #[dfield(
+        map,
+        attrs(map = "#[allow(clippy::needless_pass_by_value)]"),
+        attrs(with = "#[cfg(any())]"),
+        set = "with"
+    )]
+    retries: u32,
+}
+
+impl Session {
+    // the forwarded `#[cfg(any())]` removes the generated setter, so this does not conflict
+    pub fn set_token(&mut self, token: &str) {
+        self.token = token.to_uppercase();
+    }
+
+    pub fn with_retries(self, _retries: u32) -> Self {
+        self
+    }
+}
+
+#[test]
+fn test_attrs() {
+    let mut session = Session {
+        token: "abc".to_string(),
+        retries: 0,
+    };
+    session.set_token("def");
+    assert_eq!("DEF", session.token());
+    let session = session.map_retries(|r| r + 1).with_retries(10);
+    assert_eq!(1, session.retries);
+    assert_eq!("DEF", session.get_token());
+}
@@ -0,0 +1,208 @@
+use datastruct::DataStruct;
+
+#[derive(Debug, Clone, PartialEq, DataStruct)]
+#[dstruct(builder)]
+struct Connection {
+    host: String,
+    #[dfield(default = "8080")]
+    port: u16,
+    #[dfield(default = "format!(\"{}:{}\", host, port)", seq = 1)]
+    address: String,
+}
+
+#[test]
+fn test_builder() {
+    let conn = Connection::builder().host("localhost".to_string()).build();
+    assert_eq!(
+        Connection {
+            host: "localhost".to_string(),
+            port: 8080,
+            address: "localhost:8080".to_string(),
+        },
+        conn
+    );
+
+    let conn = ConnectionBuilder::default()
+        .host("example.com".to_string())
+        .port(80)
+        .build();
+    assert_eq!("example.com:80", conn.address);
+}
+
+#[test]
+#[should_panic(expected = "field `host` of `Connection` is not set")]
+fn test_builder_missing() {
+    Connection::builder().port(80).build();
+}
+
+#[derive(Debug, Clone, PartialEq, DataStruct)]
+#[dstruct(builder(typestate))]
+struct Request<T> {
+    method: &'static str,
+    url_path: String,
+    #[dfield(default = "None")]
+    body: Option<T>,
+    #[dfield(default = "30")]
+    timeout: u32,
+}
+
+#[test]
+fn test_typestate_builder() {
+    let req = Request::builder()
+        .timeout(5)
+        .url_path("/index".to_string())
+        .method("GET")
+        .body(Some(1_u8))
+        .build();
+    assert_eq!(
+        Request {
+            method: "GET",
+            url_path: "/index".to_string(),
+            body: Some(1),
+            timeout: 5,
+        },
+        req
+    );
+
+    let req: Request<()> = RequestBuilder::default()
+        .method("POST")
+        .url_path("/".to_string())
+        .build();
+    assert_eq!(30, req.timeout);
+}
+
+#[derive(Debug, Clone, PartialEq, DataStruct)]
+#[dstruct(builder(try))]
+struct Account {
+    user_name: String,
+    email: String,
+    #[dfield(default = "false")]
+    admin: bool,
+}
+
+#[test]
+fn test_try_builder() {
+    let account = Account::builder()
+        .user_name("root".to_string())
+        .email("root@localhost".to_string())
+        .build();
+    assert_eq!(
+        Ok(Account {
+            user_name: "root".to_string(),
+            email: "root@localhost".to_string(),
+            admin: false,
+        }),
+        account
+    );
+
+    let err = Account::builder().email("a@b".to_string()).build().unwrap_err();
+    assert_eq!(AccountBuilderError::MissingUserName, err);
+    assert_eq!("field `user_name` of `Account` is not set", err.to_string());
+}
+
+#[derive(Debug, Clone, PartialEq, DataStruct)]
+#[dstruct(builder(typestate))]
+struct Keyword {
+    r#type: &'static str,
+    r#match: bool,
+}
+
+#[test]
+fn test_typestate_builder_raw_ident() {
+    let keyword = Keyword::builder().r#match(true).r#type("fn").build();
+    assert_eq!(Keyword { r#type: "fn", r#match: true }, keyword);
+}
+
+#[derive(Debug, Clone, PartialEq, DataStruct)]
+#[dstruct(builder(try))]
+struct Token {
+    r#type: u8,
+    #[dfield(default = "0")]
+    r#ref: u32,
+}
+
+#[test]
+fn test_try_builder_raw_ident() {
+    assert_eq!(Ok(Token { r#type: 1, r#ref: 0 }), Token::builder().r#type(1).build());
+
+    let err = Token::builder().r#ref(2).build().unwrap_err();
+    assert_eq!(TokenBuilderError::MissingType, err);
+    assert_eq!("field `type` of `Token` is not set", err.to_string());
+}
+
+#[derive(Debug, Clone, PartialEq, DataStruct)]
+#[dstruct(default, builder)]
+struct Pool<T> {
+    #[dfield(default = "4")]
+    size: usize,
+    #[dfield(default = "size * 2")]
+    max_size: usize,
+    #[dfield(default = "None")]
+    tag: Option<T>,
+}
+
+#[derive(Debug, Clone, PartialEq, DataStruct)]
+#[dstruct(default, builder(typestate))]
+struct Retry {
+    #[dfield(default = "3")]
+    attempts: u32,
+}
+
+#[test]
+fn test_builder_from_default() {
+    assert_eq!(Pool::<u8>::data_default(), PoolBuilder::<u8>::from_default().build());
+    // the defaults are all taken from `data_default`, instead of following the overridden fields
+    let pool = Pool::builder().size(8).tag(Some("pool")).build();
+    assert_eq!(
+        Pool {
+            size: 8,
+            max_size: 8,
+            tag: Some("pool"),
+        },
+        pool
+    );
+    assert_eq!(5, Retry::builder().attempts(5).build().attempts);
+    assert_eq!(3, RetryBuilder::from_default().build().attempts);
+}
+
+#[derive(Debug, Clone, PartialEq, DataStruct)]
+#[dstruct(builder(mirror_setters), set = "with")]
+struct Endpoint {
+    url: String,
+    #[dfield(default = "30", set = "set")]
+    timeout: u32,
+    #[dfield(default = "3", set = "no")]
+    retries: u32,
+    #[dfield(default = "false", attrs(with = "#[must_use]"))]
+    r#async: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, DataStruct)]
+#[dstruct(builder(typestate, mirror_setters))]
+struct Upload {
+    path: String,
+    #[dfield(default = "1024", set = "with")]
+    chunk: usize,
+}
+
+#[test]
+fn test_builder_mirror_setters() {
+    let endpoint = Endpoint::builder()
+        .with_url("https://example.com".to_string())
+        .set_timeout(10)
+        .with_async(true)
+        .build();
+    assert_eq!(
+        Endpoint {
+            url: "https://example.com".to_string(),
+            timeout: 10,
+            retries: 3,
+            r#async: true,
+        },
+        endpoint
+    );
+    assert_eq!(endpoint.clone(), endpoint.with_url("https://example.com".to_string()));
+
+    let upload = Upload::builder().with_path("a.txt".to_string()).with_chunk(16).build();
+    assert_eq!((upload.path.as_str(), upload.chunk), ("a.txt", 16));
+}
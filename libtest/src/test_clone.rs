@@ -0,0 +1,77 @@
+use std::rc::Rc;
+use std::sync::Arc;
+
+use datastruct::DataStruct;
+
+struct Connection;
+
+#[derive(DataStruct)]
+#[dstruct(clone)]
+struct Client<T> {
+    #[dfield(clone = "Arc::clone(&$self.connection)")]
+    connection: Arc<Connection>,
+    #[dfield(clone = "$self.retries.min(3)")]
+    retries: u32,
+    state: Rc<T>,
+}
+
+#[test]
+fn test_clone() {
+    // `Connection` is not `Clone`, nor is it required to be
+    let client = Client {
+        connection: Arc::new(Connection),
+        retries: 5,
+        state: Rc::new(Connection),
+    };
+    let cloned = client.clone();
+    assert!(Arc::ptr_eq(&client.connection, &cloned.connection));
+    assert!(Rc::ptr_eq(&client.state, &cloned.state));
+    assert_eq!(3, cloned.retries);
+}
+
+#[derive(Debug, PartialEq, DataStruct)]
+#[dstruct(clone, where(clone = "T: Clone"))]
+struct Pair<T> {
+    left: T,
+    #[dfield(clone = "inherit")]
+    right: T,
+}
+
+#[test]
+fn test_clone_bounds() {
+    let pair = Pair {
+        left: "left".to_string(),
+        right: "right".to_string(),
+    };
+    assert_eq!(pair, pair.clone());
+}
+
+struct Socket(u16);
+
+#[derive(DataStruct)]
+#[dstruct(clone_with_default)]
+struct Session {
+    #[dfield(default = "8080")]
+    port: u16,
+    #[dfield(clone = "format!(\"{}-copy\", $self.name)")]
+    name: String,
+    #[dfield(clone = false, default = "Some(Socket(port))")]
+    socket: Option<Socket>,
+    #[dfield(clone = false, default = "0")]
+    sent: usize,
+}
+
+#[test]
+fn test_clone_with_default() {
+    let session = Session {
+        port: 80,
+        name: "session".to_string(),
+        socket: Some(Socket(1)),
+        sent: 10,
+    };
+    let copy = session.clone_with_default();
+    assert_eq!(80, copy.port);
+    assert_eq!("session-copy", copy.name);
+    assert_eq!(Some(80), copy.socket.map(|s| s.0));
+    assert_eq!(0, copy.sent);
+}
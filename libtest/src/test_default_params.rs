@@ -0,0 +1,53 @@
+use std::fmt::Debug;
+
+use datastruct::DataStruct;
+
+#[derive(DataStruct, Clone, Debug, PartialEq)]
+#[dstruct(default, set, get, builder, patch, dirty, snapshot, clone_with_default, field_enum, visit, tuple, into_parts)]
+struct Setting<T: Clone + Debug + PartialEq + 'static = u32> {
+    #[dfield(default = "None")]
+    value: Option<T>,
+    #[dfield(default = "1")]
+    version: u16,
+}
+
+#[derive(DataStruct, Debug)]
+#[dstruct(default, std_default, cmp(peq, eq, pord, ord, hash), diff_display, string_map, reflect(any, debug), ops(add))]
+struct Counter<T: Copy + Debug + Ord + std::hash::Hash + std::str::FromStr + std::fmt::Display + std::ops::Add<Output = T> + Default + 'static = u32> {
+    #[dfield(default = "T::default()", cmp(ord))]
+    count: T,
+}
+
+#[derive(DataStruct, Clone, Debug, PartialEq)]
+#[dstruct(const, builder(typestate), patch, snapshot)]
+struct Buffer<const N: usize = 4, T: Copy + Debug + PartialEq + 'static = u8> {
+    #[dfield(default = "[0; N]")]
+    data: [u8; N],
+    #[dfield(default = "None")]
+    tag: Option<T>,
+    #[dfield(default = "0")]
+    id: u16,
+}
+
+#[test]
+fn test_default_type_params() {
+    use datastruct::{ConstDataStruct, DataStruct};
+
+    let mut setting: Setting = Setting::data_default();
+    setting.set_value(Some(3u32));
+    let snapshot = setting.snapshot();
+    setting.set_version(2);
+    setting.restore(snapshot);
+    assert_eq!(Setting::builder().value(Some(3)).build(), setting);
+    assert_eq!((Some(3u32), 1), setting.into_parts());
+
+    let counter: Counter = Counter::default();
+    let added = counter + Counter { count: 2 };
+    assert_eq!(2u32, added.count);
+    assert_eq!("count: 0 → 2", Counter::default().diff_display(&added).to_string());
+
+    let buffer: Buffer = Buffer::builder().id(1).build();
+    assert_eq!([0u8; 4], buffer.data);
+    assert_eq!(None::<u8>, buffer.tag);
+    assert_eq!([0u8; 2], Buffer::<2, u8>::DEFAULT.data);
+}
@@ -0,0 +1,31 @@
+use datastruct::DataStruct;
+
+#[derive(DataStruct)]
+#[dstruct(dirty)]
+#[allow(dead_code)]
+struct Profile<T> {
+    name: String,
+    age: u8,
+    r#type: T,
+}
+
+#[test]
+fn test_dirty() {
+    let mut profile = Profile {
+        name: "alice".to_string(),
+        age: 30,
+        r#type: (),
+    };
+    let mut dirty = ProfileDirty::default();
+    assert!(dirty.is_clean());
+
+    profile.set_age_tracked(31, &mut dirty);
+    profile.set_type_tracked((), &mut dirty);
+    assert_eq!(31, profile.age);
+    assert!(dirty.age && dirty.r#type && !dirty.name);
+    assert_eq!(vec!["age", "type"], dirty.changed().collect::<Vec<_>>());
+
+    dirty.clear();
+    assert!(dirty.is_clean());
+    assert_eq!(0, dirty.changed().count());
+}
@@ -0,0 +1,42 @@
+use datastruct::DataStruct;
+
+#[derive(DataStruct, Debug, PartialEq)]
+#[dstruct(default(env_overrides, env_prefix = "DSTRUCT_TEST"), where(default = "T: Default"))]
+struct ServerEnv<T> {
+    #[dfield(default = "\"localhost\".to_string()")]
+    host: String,
+    #[dfield(default = "8080")]
+    port: u16,
+    #[dfield(default = "false", env = "DSTRUCT_TEST_VERBOSE_LOGS")]
+    verbose: bool,
+    #[dfield(default = "Default::default()")]
+    r#level: T,
+}
+
+#[derive(DataStruct, Debug, PartialEq)]
+#[dstruct(default(env_overrides))]
+struct WorkerEnv {
+    #[dfield(default = "1")]
+    max_threads: u32,
+}
+
+#[test]
+fn test_env_overrides() {
+    std::env::set_var("DSTRUCT_TEST_HOST", "example.com");
+    std::env::set_var("DSTRUCT_TEST_PORT", "not a port");
+    std::env::set_var("DSTRUCT_TEST_VERBOSE_LOGS", "true");
+    std::env::set_var("DSTRUCT_TEST_LEVEL", "3");
+    std::env::set_var("WORKER_ENV_MAX_THREADS", "8");
+
+    assert_eq!(
+        ServerEnv::<u8>::data_default_from_env(),
+        ServerEnv {
+            host: "example.com".to_string(),
+            port: 8080,
+            verbose: true,
+            level: 3,
+        }
+    );
+    assert_eq!(WorkerEnv::data_default_from_env(), WorkerEnv { max_threads: 8 });
+    assert_eq!(WorkerEnv::data_default(), WorkerEnv { max_threads: 1 });
+}
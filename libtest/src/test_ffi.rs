@@ -0,0 +1,51 @@
+use datastruct::DataStruct;
+
+#[derive(DataStruct)]
+#[dstruct(ffi, get, set = "set")]
+#[repr(C)]
+pub struct FfiPoint {
+    x: f64,
+    y: f64,
+    #[dfield(get = "no", set = "no")]
+    hidden: u8,
+    #[dfield(ffi = false)]
+    label: Option<String>,
+}
+
+#[derive(DataStruct)]
+#[dstruct(ffi(prefix = "lib_counter"), set = "set")]
+#[repr(C, align(8))]
+pub struct FfiCounter {
+    r#type: u32,
+    next: Option<extern "C" fn(u32) -> u32>,
+}
+
+extern "C" fn increment(value: u32) -> u32 {
+    value + 1
+}
+
+#[test]
+fn test_ffi() {
+    let mut point = FfiPoint {
+        x: 1.0,
+        y: 2.0,
+        hidden: 0,
+        label: None,
+    };
+    unsafe {
+        ffi_point_set_x(&mut point, 3.0);
+        assert_eq!(3.0, ffi_point_x(&point));
+        assert_eq!(2.0, ffi_point_y(&point));
+    }
+    assert_eq!(0, point.hidden);
+    point.set_label(Some("origin".to_string()));
+    assert_eq!(Some("origin"), point.label().as_deref());
+
+    let mut counter = FfiCounter { r#type: 0, next: None };
+    unsafe {
+        lib_counter_set_type(&mut counter, 2);
+        lib_counter_set_next(&mut counter, Some(increment));
+    }
+    assert_eq!(2, counter.r#type);
+    assert_eq!(Some(3), counter.next.map(|next| next(counter.r#type)));
+}
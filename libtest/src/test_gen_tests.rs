@@ -0,0 +1,43 @@
+use datastruct::DataStruct;
+
+// The generated tests run along with the tests below.
+
+#[derive(DataStruct, Debug, PartialEq)]
+#[dstruct(default, const, std_default, fixture, gen_tests, set, get)]
+struct Viewport {
+    #[dfield(default = "800", fixture = "1024")]
+    width: u32,
+    #[dfield(default = "600", fixture = "768", set = "with", get = "move")]
+    height: u32,
+    #[dfield(default = "1.0", set = "no")]
+    scale: f32,
+}
+
+#[derive(DataStruct, Clone, Debug, PartialEq)]
+#[dstruct(default, clone_with_default, string_map, gen_tests, set = "full", get = "full")]
+struct Profile {
+    #[dfield(default = "\"guest\".to_string()")]
+    name: String,
+    #[dfield(default = "0", clone = false)]
+    visits: u32,
+}
+
+#[derive(DataStruct, Copy, Debug, PartialEq)]
+#[dstruct(default, clone, ops(add = "both", sub, mul, identity), gen_tests)]
+struct Offset {
+    #[dfield(default = "3")]
+    x: i32,
+    #[dfield(default = "-2")]
+    y: i32,
+}
+
+#[test]
+fn test_gen_tests_values() {
+    let viewport = Viewport::fixture().with_height(720);
+    assert_eq!(1024, *viewport.width());
+    assert_eq!(720, viewport.get_height());
+
+    let profile = Profile::data_default().with_visits(3).clone_with_default();
+    assert_eq!(0, profile.visits);
+    assert_eq!(Offset::data_default(), Offset::data_default() + Offset::ZERO);
+}
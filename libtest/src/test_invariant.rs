@@ -0,0 +1,79 @@
+use datastruct::DataStruct;
+
+#[derive(Debug, PartialEq)]
+struct RangeError;
+
+impl std::fmt::Display for RangeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("`min` is greater than `max`")
+    }
+}
+
+impl std::error::Error for RangeError {}
+
+#[derive(Debug, PartialEq, DataStruct)]
+#[dstruct(default, builder(try), set = "set")]
+#[dstruct(invariant(check = "Range::check", error = "RangeError"))]
+#[dstruct(convert(try_from = "(i32, i32)", error = "RangeError"))]
+struct Range {
+    #[dfield(default = "0", try_convert = "Ok::<_, RangeError>($from.0)")]
+    min: i32,
+    #[dfield(default = "10", try_convert = "Ok::<_, RangeError>($from.1)")]
+    max: i32,
+}
+
+impl Range {
+    fn check(&self) -> Result<(), RangeError> {
+        if self.min <= self.max {
+            Ok(())
+        } else {
+            Err(RangeError)
+        }
+    }
+}
+
+#[derive(Debug, DataStruct)]
+#[dstruct(builder, invariant = "|s: &Percent| if s.value <= 100 { Ok(()) } else { Err(\"too large\") }")]
+struct Percent {
+    value: u8,
+}
+
+#[test]
+fn test_invariant_setter() {
+    let mut range = Range::try_data_default().unwrap();
+    assert_eq!(Err(RangeError), range.try_set_min(20));
+    assert_eq!(0, range.min);
+    assert_eq!(Ok(()), range.try_set_max(20));
+    assert_eq!(Ok(()), range.try_set_min(20));
+    assert_eq!(Range { min: 20, max: 20 }, range);
+}
+
+#[test]
+fn test_invariant_builder() {
+    assert!(Range::builder().min(5).build().is_ok());
+    match Range::builder().min(20).build() {
+        Err(e @ RangeBuilderError::Invariant(_)) => {
+            assert_eq!("invariant of `Range` is violated: `min` is greater than `max`", e.to_string())
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    assert_eq!(50, Percent::builder().value(50).build().value);
+    let result = std::panic::catch_unwind(|| Percent::builder().value(200).build());
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_invariant_try_from() {
+    assert_eq!(Ok(Range { min: 1, max: 2 }), Range::try_from((1, 2)));
+    assert_eq!(Err(RangeError), Range::try_from((2, 1)));
+}
+
+fn checked_default<T: datastruct::TryDataStruct>() -> Result<T, T::Error> {
+    T::try_data_default()
+}
+
+#[test]
+fn test_invariant_try_data_struct() {
+    assert_eq!(Ok(Range { min: 0, max: 10 }), checked_default::<Range>());
+}
@@ -0,0 +1,53 @@
+use datastruct::{DataStruct, Lerp};
+
+#[derive(Debug, Clone, Copy, PartialEq, DataStruct)]
+#[dstruct(lerp)]
+struct Keyframe {
+    x: f32,
+    y: f64,
+    alpha: u8,
+    #[dfield(lerp = false)]
+    id: u32,
+    #[dfield(lerp = "if $t < 0.5 { $self.visible } else { $other.visible }")]
+    visible: bool,
+}
+
+#[test]
+fn test_lerp() {
+    let from = Keyframe {
+        x: 0.0,
+        y: 10.0,
+        alpha: 0,
+        id: 1,
+        visible: false,
+    };
+    let to = Keyframe {
+        x: 4.0,
+        y: 20.0,
+        alpha: 200,
+        id: 2,
+        visible: true,
+    };
+
+    assert_eq!(from, from.lerp(to, 0.0));
+    assert_eq!(
+        Keyframe {
+            x: 2.0,
+            y: 15.0,
+            alpha: 100,
+            id: 1,
+            visible: true,
+        },
+        from.lerp(to, 0.5)
+    );
+    assert_eq!(
+        Keyframe {
+            x: 1.0,
+            y: 12.5,
+            alpha: 50,
+            id: 1,
+            visible: false,
+        },
+        from.lerp(to, 0.25)
+    );
+}
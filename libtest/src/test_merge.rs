@@ -0,0 +1,83 @@
+use datastruct::DataStruct;
+
+#[derive(Debug, Clone, PartialEq, DataStruct)]
+#[dstruct(merge)]
+struct Logging {
+    level: u8,
+    #[dfield(merge = "keep")]
+    name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, DataStruct)]
+#[dstruct(merge)]
+struct Layer {
+    #[dfield(merge = "$other.port.or($self.port)")]
+    port: Option<u16>,
+    #[dfield(merge = "append")]
+    plugins: Vec<String>,
+    #[dfield(merge = "merge")]
+    logging: Logging,
+}
+
+#[test]
+fn test_merge() {
+    let mut base = Layer {
+        port: Some(80),
+        plugins: vec!["a".to_string()],
+        logging: Logging {
+            level: 1,
+            name: "base".to_string(),
+        },
+    };
+    base.merge(Layer {
+        port: None,
+        plugins: vec!["b".to_string()],
+        logging: Logging {
+            level: 3,
+            name: "user".to_string(),
+        },
+    });
+    assert_eq!(
+        Layer {
+            port: Some(80),
+            plugins: vec!["a".to_string(), "b".to_string()],
+            logging: Logging {
+                level: 3,
+                name: "base".to_string(),
+            },
+        },
+        base
+    );
+}
+
+fn other() -> u32 {
+    100
+}
+
+#[derive(Debug, PartialEq, DataStruct)]
+#[dstruct(merge)]
+struct Budget {
+    // `other` is the function above, the merged value is only reachable via `$other`
+    #[dfield(merge = "($self.limit + $other.limit).min(other())")]
+    limit: u32,
+}
+
+#[test]
+fn test_merge_hygiene() {
+    let mut budget = Budget { limit: 60 };
+    budget.merge(Budget { limit: 70 });
+    assert_eq!(Budget { limit: 100 }, budget);
+}
+
+fn layered<T: datastruct::MergeDataStruct>(layers: impl IntoIterator<Item = T>, base: T) -> T {
+    layers.into_iter().fold(base, |mut acc, layer| {
+        acc.merge_from(layer);
+        acc
+    })
+}
+
+#[test]
+fn test_merge_trait() {
+    let budget = layered([Budget { limit: 10 }, Budget { limit: 20 }], Budget { limit: 5 });
+    assert_eq!(Budget { limit: 35 }, budget);
+}
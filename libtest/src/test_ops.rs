@@ -21,6 +21,60 @@ struct CanOpsAssign {
     min: i8,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, DataStruct)]
+#[dstruct(ops(add, sub))]
+struct Saturating {
+    #[dfield(ops(add = "$field.saturating_add($rhs_field)", sub = "$field.saturating_sub($rhs_field)"))]
+    low: u8,
+    #[dfield(ops(add = "$field.saturating_add($rhs_field)", sub = "$field.saturating_sub($rhs_field)"))]
+    high: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, DataStruct)]
+#[dstruct(ops(
+    add(expr = "$field.saturating_add($rhs_field)"),
+    sub(mode = "both", expr = "$field.saturating_sub($rhs_field)")
+))]
+struct SaturatingTemplate {
+    low: u8,
+    high: u8,
+    #[dfield(ops(add = "ignore", sub = "ignore", sub_assign = "ignore"))]
+    id: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, DataStruct)]
+#[dstruct(ops(add = "both", mul))]
+struct Vec2<T> {
+    x: T,
+    y: T,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, DataStruct)]
+#[dstruct(ops(sub, bound = "T: std::ops::Sub<Output = T> + Copy"))]
+struct Labeled<T> {
+    value: T,
+    #[dfield(ops(sub = "ignore"))]
+    label: &'static str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, DataStruct)]
+#[dstruct(ops(mul(mode = "both", scalar = "f32"), div(mode = "assign", scalar = "f32")))]
+struct Size {
+    w: f32,
+    h: f32,
+    #[dfield(ops(mul = "ignore", mul_assign = "ignore", div_assign = "ignore"))]
+    dpi: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, DataStruct)]
+#[dstruct(ops(add = "both", strict))]
+struct Audited {
+    #[dfield(ops(add = "inherit", add_assign = "inherit"))]
+    total: u32,
+    #[dfield(ops(add = "ignore", add_assign = "ignore"))]
+    version: u32,
+}
+
 #[test]
 fn test_ops() {
     let add1 = CanOps {
@@ -75,3 +129,272 @@ fn test_ops() {
         }
     );
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, DataStruct)]
+#[dstruct(ops(add, mul, identity))]
+struct Scale {
+    x: f32,
+    y: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, DataStruct)]
+#[dstruct(ops(add, identity))]
+struct Tagged {
+    value: u8,
+    #[dfield(ops(add = "ignore", zero = "'-'"))]
+    tag: char,
+}
+
+trait Origin {
+    const ORIGIN: Self;
+}
+
+impl Origin for i64 {
+    const ORIGIN: Self = 0;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, DataStruct)]
+#[dstruct(ops(identity), where(ops = "T: Origin + PartialEq"))]
+struct Offset<T> {
+    #[dfield(ops(zero = "T::ORIGIN"))]
+    value: T,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, DataStruct)]
+#[dstruct(ops(identity))]
+struct Unit {}
+
+#[derive(Debug, Clone, Copy, PartialEq, DataStruct)]
+#[dstruct(construct = "default", default, const, ops(mul, identity))]
+struct Weights {
+    #[dfield(default = "0.5")]
+    bias: f64,
+    #[dfield(default = "2", ops(one = "1"))]
+    scale: u32,
+}
+
+#[test]
+fn test_ops_field_placeholder() {
+    let lhs = Saturating { low: 10, high: 250 };
+    let rhs = Saturating { low: 20, high: 10 };
+    assert_eq!(Saturating { low: 30, high: 255 }, lhs + rhs);
+    assert_eq!(Saturating { low: 0, high: 240 }, lhs - rhs);
+}
+
+#[test]
+fn test_ops_template() {
+    let lhs = SaturatingTemplate {
+        low: 10,
+        high: 250,
+        id: 1,
+    };
+    let rhs = SaturatingTemplate {
+        low: 20,
+        high: 10,
+        id: 2,
+    };
+    assert_eq!(
+        SaturatingTemplate {
+            low: 30,
+            high: 255,
+            id: 1
+        },
+        lhs + rhs
+    );
+    assert_eq!(
+        SaturatingTemplate {
+            low: 0,
+            high: 240,
+            id: 1
+        },
+        lhs - rhs
+    );
+
+    let mut assign = lhs;
+    assign -= rhs;
+    assert_eq!(lhs - rhs, assign);
+}
+
+#[test]
+fn test_ops_identity() {
+    assert_eq!(Scale { x: 0.0, y: 0 }, Scale::ZERO);
+    assert_eq!(Scale { x: 1.0, y: 1 }, Scale::ONE);
+    assert!(Scale::ZERO.is_zero());
+    assert!(!Scale::ONE.is_zero());
+
+    let value = Scale { x: 2.5, y: -3 };
+    assert_eq!(value, value + Scale::ZERO);
+    assert_eq!(value, value * Scale::ONE);
+
+    assert_eq!(Tagged { value: 0, tag: '-' }, Tagged::ZERO);
+    assert!(!Tagged { value: 0, tag: 'a' }.is_zero());
+
+    assert_eq!(Weights { bias: 0.0, scale: 0 }, Weights::ZERO);
+    assert_eq!(Weights { bias: 1.0, scale: 1 }, Weights::ONE);
+    let weights = Weights { bias: 0.5, scale: 3 };
+    assert_eq!(weights, weights * Weights::ONE);
+
+    assert_eq!(Offset { value: 0i64 }, Offset::ZERO);
+    assert!(!Offset { value: 2i64 }.is_zero());
+    assert!(Unit::ZERO.is_zero());
+    assert_eq!(Unit {}, Unit::ONE);
+}
+
+#[test]
+fn test_ops_generic() {
+    let mut v = Vec2 { x: 1, y: 2 } + Vec2 { x: 3, y: 4 };
+    assert_eq!(Vec2 { x: 4, y: 6 }, v);
+    v += Vec2 { x: 1, y: 1 };
+    assert_eq!(Vec2 { x: 5, y: 7 }, v);
+    assert_eq!(
+        Vec2 { x: 2.0, y: 1.0 },
+        Vec2 { x: 1.0, y: 0.5 } * Vec2 { x: 2.0, y: 2.0 }
+    );
+
+    assert_eq!(
+        Labeled {
+            value: 1.5,
+            label: "a"
+        },
+        Labeled {
+            value: 2.0,
+            label: "a"
+        } - Labeled {
+            value: 0.5,
+            label: "b"
+        }
+    );
+}
+
+#[test]
+fn test_ops_scalar() {
+    let size = Size {
+        w: 2.0,
+        h: 3.0,
+        dpi: 96.0,
+    };
+    assert_eq!(
+        Size {
+            w: 4.0,
+            h: 6.0,
+            dpi: 96.0
+        },
+        size * 2.0
+    );
+
+    let mut scaled = size;
+    scaled *= 3.0;
+    assert_eq!(
+        Size {
+            w: 6.0,
+            h: 9.0,
+            dpi: 96.0
+        },
+        scaled
+    );
+    scaled /= 3.0;
+    assert_eq!(size, scaled);
+    assert_eq!(
+        Size {
+            w: 4.0,
+            h: 9.0,
+            dpi: 96.0
+        },
+        size * Size {
+            w: 2.0,
+            h: 3.0,
+            dpi: 0.0
+        }
+    );
+}
+
+#[test]
+fn test_ops_strict() {
+    let mut audited = Audited {
+        total: 1,
+        version: 3,
+    } + Audited {
+        total: 2,
+        version: 4,
+    };
+    assert_eq!(
+        Audited {
+            total: 3,
+            version: 3
+        },
+        audited
+    );
+    audited += Audited {
+        total: 1,
+        version: 5,
+    };
+    assert_eq!(
+        Audited {
+            total: 4,
+            version: 3
+        },
+        audited
+    );
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, DataStruct)]
+#[dstruct(ops(add, sub))]
+struct Frozen {
+    value: i32,
+    // repeated `ops(...)` are merged, so neither operation is applied
+    #[dfield(ops(add = "ignore"))]
+    #[dfield(ops(sub = "ignore"))]
+    frozen: i32,
+}
+
+#[test]
+fn test_ops_merge() {
+    let a = Frozen { value: 1, frozen: 1 };
+    let b = Frozen { value: 2, frozen: 2 };
+    assert_eq!(Frozen { value: 3, frozen: 1 }, a + b);
+    assert_eq!(Frozen { value: -1, frozen: 1 }, a - b);
+}
+
+#[derive(Debug, Clone, PartialEq, DataStruct)]
+#[dstruct(ops(add))]
+struct Caption {
+    // placeholders inside strings of the expression are not substituted
+    #[dfield(ops(add = "format!(\"{}$self{}\", $field, $rhs_field)"))]
+    label: String,
+}
+
+#[test]
+fn test_ops_placeholder_in_string() {
+    let a = Caption { label: "a".to_string() };
+    let b = Caption { label: "b".to_string() };
+    assert_eq!("a$selfb", (a + b).label);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, DataStruct)]
+#[dstruct(ops(add = "both", sub = "assign", mul, div, apply_op))]
+struct Register {
+    value: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, DataStruct)]
+#[dstruct(ops(add, sub, mul, div, apply_op))]
+struct Accumulator<T> {
+    total: T,
+}
+
+#[test]
+fn test_ops_apply_op() {
+    use datastruct::DataOp;
+
+    let rhs = Register { value: 4 };
+    let mut register = Register { value: 10 };
+    for (op, expected) in [(DataOp::Add, 14), (DataOp::Sub, 10), (DataOp::Mul, 40), (DataOp::Div, 10)] {
+        register.apply_op(op, &rhs);
+        assert_eq!(expected, register.value);
+    }
+
+    let mut accumulator = Accumulator { total: 1.5 };
+    accumulator.apply_op(DataOp::Mul, &Accumulator { total: 4.0 });
+    accumulator.apply_op(DataOp::Sub, &Accumulator { total: 2.0 });
+    assert_eq!(4.0, accumulator.total);
+}
@@ -0,0 +1,70 @@
+use datastruct::DataStruct;
+
+#[derive(Debug, Clone, PartialEq, DataStruct)]
+#[dstruct(patch(derive(Debug, Clone, PartialEq), diff))]
+struct Settings {
+    width: u32,
+    height: u32,
+    title: String,
+}
+
+#[test]
+fn test_patch() {
+    let mut settings = Settings {
+        width: 800,
+        height: 600,
+        title: "main".to_string(),
+    };
+
+    settings += SettingsPatch::default();
+    assert_eq!(
+        Settings {
+            width: 800,
+            height: 600,
+            title: "main".to_string(),
+        },
+        settings
+    );
+
+    settings += SettingsPatch {
+        width: Some(1024),
+        title: Some("resized".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        Settings {
+            width: 1024,
+            height: 600,
+            title: "resized".to_string(),
+        },
+        settings
+    );
+}
+
+#[test]
+fn test_patch_diff() {
+    let old = Settings {
+        width: 800,
+        height: 600,
+        title: "main".to_string(),
+    };
+    let new = Settings {
+        width: 800,
+        height: 720,
+        title: "main".to_string(),
+    };
+
+    let patch = old.diff(&new);
+    assert_eq!(
+        SettingsPatch {
+            height: Some(720),
+            ..Default::default()
+        },
+        patch
+    );
+    assert_eq!(SettingsPatch::default(), old.diff(&old));
+
+    let mut applied = old.clone();
+    applied += patch;
+    assert_eq!(new, applied);
+}
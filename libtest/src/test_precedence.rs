@@ -0,0 +1,47 @@
+// redeclared options are reported as deprecation warnings
+#![allow(deprecated)]
+
+use datastruct::DataStruct;
+
+#[derive(DataStruct)]
+#[dstruct(default, precedence = "first")]
+struct Retry {
+    #[dfield(default = "3")]
+    #[dfield(default = "5")]
+    attempts: u8,
+    #[dfield(default = "100")]
+    delay_ms: u64,
+}
+
+#[derive(DataStruct)]
+#[dstruct(default)]
+struct LastRetry {
+    #[dfield(default = "3")]
+    #[dfield(default = "5")]
+    attempts: u8,
+}
+
+// the same values written differently are not reported
+#[deny(deprecated)]
+mod same_values {
+    use datastruct::DataStruct;
+
+    #[derive(DataStruct)]
+    #[dstruct(default, debug, get = "full")]
+    #[dstruct(debug = true, get = "full")]
+    #[dstruct(get = r"full")]
+    #[allow(clippy::duplicated_attributes)]
+    pub(super) struct Polling {
+        #[dfield(default = "10", seq = 0x10)]
+        #[dfield(seq = 16, default = r"10")]
+        pub(super) interval: u32,
+    }
+}
+
+#[test]
+fn test_precedence() {
+    assert_eq!(3, Retry::data_default().attempts);
+    assert_eq!(100, Retry::data_default().delay_ms);
+    assert_eq!(5, LastRetry::data_default().attempts);
+    assert_eq!(10, same_values::Polling::data_default().interval);
+}
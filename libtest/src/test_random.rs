@@ -0,0 +1,39 @@
+use datastruct::DataStruct;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+#[derive(Debug, Clone, DataStruct)]
+#[dstruct(random)]
+#[allow(dead_code)]
+struct Particle {
+    #[dfield(random(range = "0..100"))]
+    x: u8,
+    #[dfield(random(range = "-1.0..=1.0"))]
+    velocity: f64,
+    #[dfield(random(gen = "$rng.gen_bool(0.5).then(|| String::from(\"tag\"))"))]
+    tag: Option<String>,
+    alive: bool,
+    #[dfield(default = "x as u32 * 2", random(default))]
+    doubled: u32,
+}
+
+#[derive(Debug, Clone, DataStruct)]
+#[dstruct(random)]
+struct Noise<T> {
+    value: T,
+}
+
+#[test]
+fn test_random() {
+    let mut rng = StdRng::seed_from_u64(42);
+    for _ in 0..64 {
+        let particle = Particle::random(&mut rng);
+        assert!(particle.x < 100);
+        assert!((-1.0..=1.0).contains(&particle.velocity));
+        assert!(matches!(particle.tag.as_deref(), None | Some("tag")));
+        assert_eq!(particle.x as u32 * 2, particle.doubled);
+    }
+
+    let noise: Noise<f32> = Noise::random(&mut rng);
+    assert!((0.0..1.0).contains(&noise.value));
+}
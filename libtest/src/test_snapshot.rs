@@ -0,0 +1,42 @@
+use std::rc::Rc;
+
+use datastruct::DataStruct;
+
+struct Texture;
+
+#[derive(DataStruct)]
+#[dstruct(snapshot)]
+struct Document<T> {
+    text: String,
+    cursor: usize,
+    #[dfield(clone = "Rc::clone(&$self.texture)")]
+    texture: Rc<Texture>,
+    #[dfield(snapshot = false)]
+    renders: u32,
+    #[dfield(clone = false)]
+    handle: Texture,
+    meta: Option<T>,
+}
+
+#[test]
+fn test_snapshot() {
+    let mut doc = Document {
+        text: "hello".to_string(),
+        cursor: 5,
+        texture: Rc::new(Texture),
+        renders: 1,
+        handle: Texture,
+        meta: Some(1),
+    };
+    let saved = doc.snapshot();
+    assert_eq!(2, Rc::strong_count(&doc.texture));
+
+    doc.text.push_str(" world");
+    doc.cursor = 11;
+    doc.renders = 2;
+    doc.meta = None;
+    doc.restore(saved);
+    assert_eq!(("hello", 5, 2, Some(1)), (doc.text.as_str(), doc.cursor, doc.renders, doc.meta));
+    assert_eq!(1, Rc::strong_count(&doc.texture));
+    let Texture = doc.handle;
+}
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use datastruct::{DataStruct, FromMapError};
+
+#[derive(DataStruct, Debug, PartialEq)]
+#[dstruct(string_map)]
+struct ServerConfig<T> {
+    host: String,
+    #[dfield(default = "8080")]
+    port: u16,
+    #[dfield(default = "format!(\"{}:{}\", host, port)")]
+    addr: String,
+    #[dfield(default = "0", string_map = false)]
+    connections: u32,
+    r#level: T,
+}
+
+fn map(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+fn parse<T: FromStr>(pairs: &[(&str, &str)]) -> Result<ServerConfig<T>, FromMapError>
+where
+    T::Err: Display,
+{
+    ServerConfig::from_map(&map(pairs))
+}
+
+#[test]
+fn test_string_map() {
+    let config: ServerConfig<u8> = parse(&[("host", "localhost"), ("level", "3")]).unwrap();
+    assert_eq!(
+        config,
+        ServerConfig {
+            host: "localhost".to_string(),
+            port: 8080,
+            addr: "localhost:8080".to_string(),
+            connections: 0,
+            level: 3,
+        }
+    );
+    assert_eq!(
+        config.to_map(),
+        map(&[
+            ("host", "localhost"),
+            ("port", "8080"),
+            ("addr", "localhost:8080"),
+            ("level", "3"),
+        ])
+    );
+    assert_eq!(ServerConfig::<u8>::from_map(&config.to_map()), Ok(config));
+
+    assert_eq!(
+        parse::<u8>(&[("level", "3")]),
+        Err(FromMapError::Missing { field: "host" })
+    );
+    let err = parse::<u8>(&[("host", "localhost"), ("port", "http"), ("level", "3")]).unwrap_err();
+    assert!(matches!(&err, FromMapError::Invalid { field: "port", value, .. } if value == "http"));
+    assert!(err.to_string().starts_with("invalid value `http` of field `port`: "));
+}
@@ -0,0 +1,47 @@
+use std::panic::catch_unwind;
+
+use datastruct::{assert_fields_eq, DataStruct};
+
+#[derive(DataStruct, Clone)]
+#[dstruct(assert_eq_verbose)]
+struct Order<T> {
+    id: u32,
+    items: Vec<&'static str>,
+    #[dfield(debug = false)]
+    token: String,
+    #[dfield(cmp(eq = false))]
+    created_at: u64,
+    note: T,
+}
+
+fn order() -> Order<&'static str> {
+    Order {
+        id: 1,
+        items: vec!["apple"],
+        token: "secret".to_string(),
+        created_at: 1,
+        note: "",
+    }
+}
+
+#[test]
+fn test_assert_eq_verbose() {
+    let mut other = order();
+    other.created_at = 2;
+    order().assert_eq_verbose(&other);
+    assert_fields_eq!(order(), other);
+
+    let err = catch_unwind(|| {
+        let mut other = order();
+        other.items.push("pear");
+        other.token = "leaked".to_string();
+        assert_fields_eq!(order(), other);
+    })
+    .unwrap_err();
+    assert_eq!(
+        "assertion `left == right` failed for `Order`, the differing fields are:\n\
+         items: [\"apple\"] → [\"apple\", \"pear\"]\n\
+         token: changed",
+        err.downcast_ref::<String>().unwrap()
+    );
+}
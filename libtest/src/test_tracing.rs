@@ -0,0 +1,73 @@
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+use datastruct::DataStruct;
+use tracing::field::{Empty, Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+#[derive(DataStruct)]
+#[dstruct(tracing)]
+#[allow(dead_code)]
+struct Request<T> {
+    method: &'static str,
+    path: String,
+    #[dfield(debug = false)]
+    token: String,
+    r#body: T,
+}
+
+#[derive(Default, Clone)]
+struct Recorder(Arc<Mutex<Vec<(String, String)>>>);
+
+impl Visit for Recorder {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        let pair = (field.name().to_string(), format!("{value:?}"));
+        self.0.lock().unwrap().push(pair);
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _: &Id, values: &Record<'_>) {
+        values.record(&mut self.clone());
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+#[test]
+fn test_record_fields() {
+    let recorder = Recorder::default();
+    let request = Request {
+        method: "GET",
+        path: "/index".to_string(),
+        token: "secret".to_string(),
+        body: Some(1),
+    };
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let span = tracing::info_span!("request", method = Empty, path = Empty, token = Empty, body = Empty);
+        request.record_fields(&span);
+    });
+    assert_eq!(
+        *recorder.0.lock().unwrap(),
+        [
+            ("method".to_string(), "\"GET\"".to_string()),
+            ("path".to_string(), "\"/index\"".to_string()),
+            ("body".to_string(), "Some(1)".to_string()),
+        ]
+    );
+}
@@ -0,0 +1,24 @@
+use datastruct::DataStruct;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[wasm_bindgen]
+#[derive(DataStruct)]
+#[dstruct(wasm, get, set = "set")]
+pub struct Viewport {
+    width: u32,
+    height: u32,
+    #[dfield(set = "no")]
+    r#type: u8,
+}
+
+#[test]
+fn test_wasm_accessors() {
+    let mut viewport = Viewport {
+        width: 800,
+        height: 600,
+        r#type: 1,
+    };
+    viewport.js_set_width(1024);
+    assert_eq!((1024, 600, 1), (viewport.js_width(), viewport.js_height(), viewport.js_type()));
+    assert_eq!(&1024, viewport.width());
+}